@@ -0,0 +1,444 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Layered configuration of the commands.
+//!
+//! Settings are layered: built-in defaults, then the preset of the selected network, then a TOML
+//! configuration file, then environment variables, then the command line. The file and the preset
+//! are applied as defaults of the command line arguments, so every setting is parsed and validated
+//! by the same value parsers, whichever layer it comes from. Keys in the file are the names of the
+//! command line options without the leading dashes, e.g. `node-url = "http://localhost:9000"`.
+//! Each option can also be set through the environment variable of its name in upper snake case
+//! with the prefix of the service, e.g. `ISAFE_INDEXER_NODE_URL`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    fmt::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches, ValueEnum, parser::ValueSource};
+
+/// Options that are not settings.
+const IGNORED_OPTIONS: &[&str] = &["help", "version", "config"];
+
+/// A network with known endpoints and deployments, selected with `--network`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localnet,
+}
+
+/// The option of the iSafe package address, named the same in every service.
+pub const PACKAGE_ADDRESS_OPTION: &str = "package-address";
+
+/// The environment variable of the iSafe package address, shared by every service.
+pub const PACKAGE_ADDRESS_ENV: &str = "ISAFE_PACKAGE_ADDRESS";
+
+impl Network {
+    /// The iSafe package published on the network, if any.
+    pub fn package_address(self) -> Option<&'static str> {
+        match self {
+            // TODO change on devnet deployment
+            Network::Devnet => {
+                Some("0x233478c5db4cc8724a94fdbc24ec6d613177280c02623b768e8b749f7b1d8e03")
+            }
+            Network::Mainnet | Network::Testnet | Network::Localnet => None,
+        }
+    }
+}
+
+/// The settings a network implies for a service, by option name. The package address of the
+/// network is added to them as [`PACKAGE_ADDRESS_OPTION`].
+pub type Preset = fn(Network) -> &'static [(&'static str, &'static str)];
+
+/// Where the effective value of a setting comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Default,
+    Network,
+    File,
+    Env,
+    CommandLine,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Source::Default => "default",
+            Source::Network => "network",
+            Source::File => "file",
+            Source::Env => "env",
+            Source::CommandLine => "command line",
+        })
+    }
+}
+
+/// The command line parsed on top of the other configuration layers.
+pub struct LayeredConfig {
+    command: Command,
+    matches: ArgMatches,
+    file: BTreeMap<String, String>,
+    preset: BTreeMap<String, String>,
+}
+
+impl LayeredConfig {
+    /// Parses the command line of the process, exiting on invalid arguments like clap does.
+    ///
+    /// The configuration file is given with `--config` or `<env_prefix>CONFIG`, the network with
+    /// `--network`, `<env_prefix>NETWORK` or `network` in the file.
+    pub fn parse<P: CommandFactory + FromArgMatches>(
+        env_prefix: &str,
+        preset: Preset,
+    ) -> Result<(P, Self)> {
+        Self::parse_from(std::env::args_os().collect(), env_prefix, preset)
+    }
+
+    fn parse_from<P: CommandFactory + FromArgMatches>(
+        args: Vec<OsString>,
+        env_prefix: &str,
+        preset: Preset,
+    ) -> Result<(P, Self)> {
+        let file = match find_option(&args, "config")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os(format!("{env_prefix}CONFIG")).map(PathBuf::from))
+        {
+            Some(path) => read_file(&path)?,
+            None => BTreeMap::new(),
+        };
+
+        let network = find_option(&args, "network")
+            .or_else(|| std::env::var(format!("{env_prefix}NETWORK")).ok())
+            .or_else(|| file.get("network").cloned())
+            .map(|network| {
+                Network::from_str(&network, true).map_err(|_| anyhow!("unknown network {network}"))
+            })
+            .transpose()?;
+        let preset = network
+            .map(|network| {
+                preset(network)
+                    .iter()
+                    .copied()
+                    .chain(
+                        network
+                            .package_address()
+                            .map(|address| (PACKAGE_ADDRESS_OPTION, address)),
+                    )
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut command = P::command();
+        let mut options = BTreeSet::new();
+        collect_options(&command, &mut options);
+        if let Some(unknown) = file.keys().find(|key| !options.contains(key.as_str())) {
+            bail!("unknown setting `{unknown}` in the configuration file");
+        }
+
+        command = apply_layers(command, env_prefix, &file, &preset);
+        let matches = command.clone().get_matches_from(args);
+        let parsed = P::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+        Ok((
+            parsed,
+            Self {
+                command,
+                matches,
+                file,
+                preset,
+            },
+        ))
+    }
+
+    /// Renders the effective settings of the subcommand at `path` as TOML, each annotated with
+    /// the layer it comes from.
+    pub fn render(&self, path: &[&str]) -> String {
+        // global options are declared on the parent commands, but parsed into the subcommand
+        let mut command = &self.command;
+        let mut matches = &self.matches;
+        let mut args = command.get_arguments().collect::<Vec<_>>();
+        for name in path {
+            command = command
+                .find_subcommand(name)
+                .expect("the rendered subcommand exists");
+            matches = matches
+                .subcommand_matches(name)
+                .expect("the rendered subcommand was parsed");
+            args.extend(command.get_arguments());
+        }
+
+        let mut out = String::new();
+        for arg in args {
+            let Some(long) = arg.get_long() else {
+                continue;
+            };
+            if IGNORED_OPTIONS.contains(&long) {
+                continue;
+            }
+            let id = arg.get_id().as_str();
+            let values = matches
+                .get_raw(id)
+                .map(|values| {
+                    values
+                        .map(|value| value.to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let [value] = values.as_slice() else {
+                writeln!(out, "# {long} is not set").unwrap();
+                continue;
+            };
+            let source = match matches.value_source(id) {
+                Some(ValueSource::CommandLine) => Source::CommandLine,
+                Some(ValueSource::EnvVariable) => Source::Env,
+                _ if self.file.contains_key(long) => Source::File,
+                _ if self.preset.contains_key(long) => Source::Network,
+                _ => Source::Default,
+            };
+            writeln!(out, "{long} = {}  # {source}", toml_value(value)).unwrap();
+        }
+        out
+    }
+}
+
+/// Finds the value of an option before the command line is parsed.
+fn find_option(args: &[OsString], long: &str) -> Option<String> {
+    let flag = format!("--{long}");
+    let prefix = format!("--{long}=");
+    let mut args = args.iter().skip(1).filter_map(|arg| arg.to_str());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == flag {
+            return args.next().map(str::to_string);
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Reads the settings of a configuration file, as the strings they would be given on the command
+/// line.
+fn read_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read configuration file {}", path.display()))?;
+    let table = content
+        .parse::<toml::Table>()
+        .with_context(|| format!("invalid configuration file {}", path.display()))?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(value) => value,
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::Float(value) => value.to_string(),
+                toml::Value::Boolean(value) => value.to_string(),
+                _ => bail!("setting `{key}` must be a string, number or boolean"),
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
+fn collect_options(command: &Command, options: &mut BTreeSet<String>) {
+    for arg in command.get_arguments() {
+        if let Some(long) = arg.get_long()
+            && !IGNORED_OPTIONS.contains(&long)
+        {
+            options.insert(long.to_string());
+        }
+    }
+    for subcommand in command.get_subcommands() {
+        collect_options(subcommand, options);
+    }
+}
+
+/// Sets the environment variables of the options without one, and the file and preset values as
+/// their defaults.
+fn apply_layers(
+    mut command: Command,
+    env_prefix: &str,
+    file: &BTreeMap<String, String>,
+    preset: &BTreeMap<String, String>,
+) -> Command {
+    let options = command
+        .get_arguments()
+        .filter_map(|arg| Some((arg.get_id().clone(), arg.get_long()?.to_string())))
+        .filter(|(_, long)| !IGNORED_OPTIONS.contains(&long.as_str()))
+        .collect::<Vec<_>>();
+    for (id, long) in options {
+        command = command.mut_arg(id, |mut arg| {
+            if arg.get_env().is_none() {
+                arg = arg.env(format!(
+                    "{env_prefix}{}",
+                    long.replace('-', "_").to_uppercase()
+                ));
+            }
+            match file.get(&long).or_else(|| preset.get(&long)) {
+                Some(value) => arg.default_value(value.clone()),
+                None => arg,
+            }
+        });
+    }
+
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |subcommand| {
+            apply_layers(subcommand, env_prefix, file, preset)
+        });
+    }
+    command
+}
+
+/// Formats a raw setting as a TOML value, unquoted if it is a number or boolean.
+fn toml_value(raw: &str) -> String {
+    if raw.parse::<i64>().is_ok() || raw == "true" || raw == "false" {
+        raw.to_string()
+    } else {
+        toml::Value::String(raw.to_string()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Args, Parser, Subcommand};
+
+    use super::*;
+
+    fn test_preset(network: Network) -> &'static [(&'static str, &'static str)] {
+        match network {
+            Network::Testnet => &[("node-url", "https://api.testnet.iota.cafe")],
+            _ => &[],
+        }
+    }
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[arg(long, global = true)]
+        config: Option<PathBuf>,
+        #[arg(long, global = true, value_enum)]
+        network: Option<Network>,
+        #[command(subcommand)]
+        command: TestCommand,
+    }
+
+    #[derive(Subcommand)]
+    enum TestCommand {
+        Start(TestArgs),
+    }
+
+    #[derive(Args)]
+    struct TestArgs {
+        #[arg(long, default_value = "http://127.0.0.1:9000")]
+        node_url: String,
+        #[arg(long, default_value_t = 1)]
+        num_workers: usize,
+        #[arg(long)]
+        enable_wal: bool,
+        #[arg(long)]
+        archive_dir: Option<PathBuf>,
+        #[arg(long, env = PACKAGE_ADDRESS_ENV)]
+        package_address: Option<String>,
+    }
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}.toml", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        std::iter::once("test")
+            .chain(args.iter().copied())
+            .map(OsString::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let path = write_file(
+            "layer-precedence",
+            "network = \"testnet\"\nnum-workers = 4\nenable-wal = true\n",
+        );
+        let (cli, config) = LayeredConfig::parse_from::<TestCli>(
+            args(&[
+                "--config",
+                path.to_str().unwrap(),
+                "start",
+                "--num-workers=8",
+            ]),
+            "ISAFE_COMMON_CONFIG_TEST_",
+            test_preset,
+        )
+        .unwrap();
+
+        assert_eq!(cli.config, Some(path));
+        assert_eq!(cli.network, Some(Network::Testnet));
+        let TestCommand::Start(start) = cli.command;
+        assert_eq!(start.node_url, "https://api.testnet.iota.cafe");
+        assert_eq!(start.num_workers, 8);
+        assert!(start.enable_wal);
+        assert_eq!(start.archive_dir, None);
+
+        assert_eq!(
+            config.render(&["start"]),
+            "network = \"testnet\"  # file\n\
+             node-url = \"https://api.testnet.iota.cafe\"  # network\n\
+             num-workers = 8  # command line\n\
+             enable-wal = true  # file\n\
+             # archive-dir is not set\n\
+             # package-address is not set\n"
+        );
+    }
+
+    #[test]
+    fn test_network_package_address() {
+        let (cli, config) = LayeredConfig::parse_from::<TestCli>(
+            args(&["--network", "devnet", "start"]),
+            "ISAFE_COMMON_CONFIG_TEST_",
+            test_preset,
+        )
+        .unwrap();
+
+        let TestCommand::Start(start) = cli.command;
+        assert_eq!(
+            start.package_address.as_deref(),
+            Network::Devnet.package_address()
+        );
+        assert!(
+            config
+                .render(&["start"])
+                .contains("package-address = \"0x233478c5db4cc8724a94fdbc24ec6d613177280c02623b768e8b749f7b1d8e03\"  # network\n")
+        );
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        for (name, content) in [
+            ("unknown-setting", "pool-size = 5\n"),
+            ("nested-setting", "[start]\nnum-workers = 4\n"),
+            ("unknown-network", "network = \"moonnet\"\n"),
+        ] {
+            let path = write_file(name, content);
+            assert!(
+                LayeredConfig::parse_from::<TestCli>(
+                    args(&["--config", path.to_str().unwrap(), "start"]),
+                    "ISAFE_COMMON_CONFIG_TEST_",
+                    test_preset,
+                )
+                .is_err(),
+                "{content}"
+            );
+        }
+    }
+}
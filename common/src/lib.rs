@@ -0,0 +1,7 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Code shared by the iSafe indexer and the tx-service.
+
+pub mod config;
+pub mod status;
@@ -0,0 +1,59 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The dependency checks of the status endpoints.
+
+use anyhow::Result;
+use diesel::{SqliteConnection, connection::SimpleConnection};
+use serde::Serialize;
+
+/// The outcome of checking a dependency of a service.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCheck {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<T> From<&Result<T>> for DependencyCheck {
+    fn from(result: &Result<T>) -> Self {
+        Self {
+            ok: result.is_ok(),
+            error: result.as_ref().err().map(|err| format!("{err:#}")),
+        }
+    }
+}
+
+/// Checks that the database answers queries.
+pub fn ping(conn: &mut SqliteConnection) -> Result<()> {
+    conn.batch_execute("SELECT 1")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, anyhow};
+    use diesel::Connection;
+
+    use super::*;
+
+    #[test]
+    fn test_dependency_check() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        let check = DependencyCheck::from(&ping(&mut conn));
+        assert!(check.ok);
+        assert_eq!(
+            serde_json::to_value(&check).unwrap(),
+            serde_json::json!({ "ok": true })
+        );
+
+        let failed: Result<()> = Err(anyhow!("connection refused")).context("node unreachable");
+        let check = DependencyCheck::from(&failed);
+        assert!(!check.ok);
+        assert_eq!(
+            check.error.as_deref(),
+            Some("node unreachable: connection refused")
+        );
+    }
+}
@@ -9,9 +9,13 @@ import { useIotaClient } from "@iota/dapp-kit";
 import { fromBase58 } from "@iota/iota-sdk/utils";
 import { bcs } from "@iota/iota-sdk/bcs";
 import { queryKey } from "@/hooks/queryKey";
-import { useSignAndExecuteTransaction } from "@iota/dapp-kit";
+import {
+  useSignAndExecuteTransaction,
+  useSignPersonalMessage,
+} from "@iota/dapp-kit";
 import { useQueryClient } from "@tanstack/react-query";
 import { useTxServiceClientContext } from "@/contexts";
+import { signSubmission } from "@/lib/clients/TxServiceClient";
 
 interface ExecuteSettingChangesDialogProps {
   action: SettingsAction;
@@ -33,6 +37,7 @@ export function ExecuteSettingChangesDialog({
   const [proposedTxDigest, setProposedTxDigest] = useState<string | null>(null);
   const iotaClient = useIotaClient();
   const { mutate: signAndExecuteTransaction } = useSignAndExecuteTransaction();
+  const { mutateAsync: signPersonalMessage } = useSignPersonalMessage();
   const queryClient = useQueryClient();
   const txServiceClient = useTxServiceClientContext();
 
@@ -140,6 +145,30 @@ export function ExecuteSettingChangesDialog({
 
     const toBeProposedTxDigest = await tx.getDigest();
 
+    let description = "";
+    switch (action.type) {
+      case "add_member":
+        description = `Add member ${action.address} with weight ${action.weight}`;
+        break;
+      case "remove_member":
+        description = `Remove member ${action.address}`;
+        break;
+      case "update_weight":
+        description = `Update weight of member ${action.address} to ${action.newWeight}`;
+        break;
+      case "set_threshold":
+        description = `Set new threshold to ${action.newThreshold}`;
+        break;
+      default:
+        // no additional action needed for now
+        break;
+    }
+    const signature = await signSubmission(
+      signPersonalMessage,
+      toBeProposedTxDigest,
+      description
+    );
+
     const proposingTx = new Transaction();
 
     proposingTx.moveCall({
@@ -156,27 +185,10 @@ export function ExecuteSettingChangesDialog({
       { transaction: proposingTx, waitForTransaction: true },
       {
         onSuccess: async () => {
-          let description = "";
-          switch (action.type) {
-            case "add_member":
-              description = `Add member ${action.address} with weight ${action.weight}`;
-              break;
-            case "remove_member":
-              description = `Remove member ${action.address}`;
-              break;
-            case "update_weight":
-              description = `Update weight of member ${action.address} to ${action.newWeight}`;
-              break;
-            case "set_threshold":
-              description = `Set new threshold to ${action.newThreshold}`;
-              break;
-            default:
-              // no additional action needed for now
-              break;
-          }
           await txServiceClient.addTransaction(
             toBase64(toBeProposedTxBytes),
-            description
+            description,
+            signature
           );
           // we just proposed a transaction that can change the account state, so we need to invalidate related queries
           queryClient.invalidateQueries();
@@ -2,12 +2,17 @@
 import { Transaction } from "@iota/iota-sdk/transactions";
 import { fromBase58, fromBase64 } from "@iota/iota-sdk/utils";
 import { useState } from "react";
-import { useIotaClient, useSignAndExecuteTransaction } from "@iota/dapp-kit";
+import {
+  useIotaClient,
+  useSignAndExecuteTransaction,
+  useSignPersonalMessage,
+} from "@iota/dapp-kit";
 import { bcs } from "@iota/iota-sdk/bcs";
 import { CONFIG } from "@/config/config";
 import { useISafeAccount } from "@/providers/ISafeAccountProvider";
 import { isTxAlreadyProposedError } from "@/lib/utils/errorResolution";
 import { useTxServiceClientContext } from "@/contexts";
+import { signSubmission } from "@/lib/clients/TxServiceClient";
 
 const TxAlreadyProposedError = "Transaction has already been proposed";
 
@@ -66,6 +71,7 @@ export function ProposeTransactionDialog({
   const client = useIotaClient();
   const { mutate: signAndExecuteTransaction, isPending } =
     useSignAndExecuteTransaction();
+  const { mutateAsync: signPersonalMessage } = useSignPersonalMessage();
   const { isafeAccount } = useISafeAccount();
   const txServiceClient = useTxServiceClientContext();
 
@@ -117,6 +123,12 @@ export function ProposeTransactionDialog({
 
       const proposingTxDigest = await validationResult.transaction!.getDigest();
 
+      const signature = await signSubmission(
+        signPersonalMessage,
+        proposingTxDigest,
+        description
+      );
+
       tx.moveCall({
         target: `${PACKAGE_ID}::dynamic_auth::propose_transaction`,
         arguments: [
@@ -136,7 +148,11 @@ export function ProposeTransactionDialog({
           onSuccess: async () => {
             setProposalSuccess(true);
             try {
-              await txServiceClient.addTransaction(txBytes, description);
+              await txServiceClient.addTransaction(
+                txBytes,
+                description,
+                signature
+              );
             } catch (err) {
               setProposalSuccess(false);
               const message = err instanceof Error ? err.message : String(err);
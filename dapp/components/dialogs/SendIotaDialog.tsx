@@ -7,10 +7,14 @@ import { ObjectRef, Transaction } from "@iota/iota-sdk/transactions";
 import { useIotaClient } from "@iota/dapp-kit";
 import { fromBase58 } from "@iota/iota-sdk/utils";
 import { bcs } from "@iota/iota-sdk/bcs";
-import { useSignAndExecuteTransaction } from "@iota/dapp-kit";
+import {
+  useSignAndExecuteTransaction,
+  useSignPersonalMessage,
+} from "@iota/dapp-kit";
 import { useQueryClient } from "@tanstack/react-query";
 import { formatIotaBalance } from "@/lib/utils/formatIotaBalance";
 import { useTxServiceClientContext } from "@/contexts";
+import { signSubmission } from "@/lib/clients/TxServiceClient";
 
 interface SendIotaDialogProps {
   accountAddress: string;
@@ -35,6 +39,7 @@ export function SendIotaDialog({
 
   const iotaClient = useIotaClient();
   const { mutate: signAndExecuteTransaction } = useSignAndExecuteTransaction();
+  const { mutateAsync: signPersonalMessage } = useSignPersonalMessage();
   const queryClient = useQueryClient();
   const txServiceClient = useTxServiceClientContext();
 
@@ -101,6 +106,13 @@ export function SendIotaDialog({
   async function proposeTransaction(tx: Transaction): Promise<string> {
     const toBeProposedTxBytes = await tx.build({ client: iotaClient });
     const toBeProposedTxDigest = await tx.getDigest();
+    const description = `Sending ${amount} IOTA(s) to ${targetAddress}`;
+
+    const signature = await signSubmission(
+      signPersonalMessage,
+      toBeProposedTxDigest,
+      description
+    );
 
     const proposingTx = new Transaction();
 
@@ -118,10 +130,10 @@ export function SendIotaDialog({
       { transaction: proposingTx, waitForTransaction: true },
       {
         onSuccess: async () => {
-          const description = `Sending ${amount} IOTA(s) to ${targetAddress}`;
           await txServiceClient.addTransaction(
             toBase64(toBeProposedTxBytes),
-            description
+            description,
+            signature
           );
           queryClient.invalidateQueries();
           setStep(4);
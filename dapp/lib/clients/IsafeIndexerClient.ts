@@ -124,7 +124,7 @@ export type GetTransactionsForAccountResponse = {
 export type TransactionSummary = {
     transactionDigest: string;
     proposerAddress: string;
-    status: 'Proposed' | 'Approved' | 'Executed' | 'Rejected';
+    status: 'Proposed' | 'Approved' | 'Executed' | 'Rejected' | 'Removed';
     currentApprovals: number;
     threshold: number;
     totalAccountWeight: number;
@@ -0,0 +1,26 @@
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+
+import { submissionMessage, type ProposalMetadata } from "./TxServiceClient.ts";
+
+type MessageVector = {
+  digest: string;
+  description: string;
+  metadata?: ProposalMetadata;
+  message: string;
+};
+
+// the tx-service checks its messages against the same vectors
+const vectors: MessageVector[] = JSON.parse(
+  readFileSync(
+    new URL("../../../test-vectors/submission-messages.json", import.meta.url),
+    "utf8"
+  )
+);
+
+test("submission messages match the tx-service", () => {
+  for (const { digest, description, metadata, message } of vectors) {
+    assert.equal(submissionMessage(digest, description, metadata), message);
+  }
+});
@@ -1,3 +1,53 @@
+/**
+ * The message a member signs when submitting a transaction, binding the
+ * metadata and description to the transaction digest. Must match the
+ * tx-service, see `test-vectors/submission-messages.json`.
+ */
+export function submissionMessage(
+  digest: string,
+  description: string,
+  metadata?: ProposalMetadata
+): string {
+  let message = `iSafe transaction proposal\nDigest: ${digest}\n`;
+  for (const line of metadataLines(metadata)) {
+    message += `${line}\n`;
+  }
+  return `${message}Description: ${description}`;
+}
+
+/**
+ * Signs the submission of a transaction with the connected wallet and returns
+ * the signature. Called before proposing the transaction on-chain, so that
+ * declining to sign doesn't leave a proposal the service doesn't know about.
+ */
+export async function signSubmission(
+  signPersonalMessage: (input: {
+    message: Uint8Array;
+  }) => Promise<{ signature: string }>,
+  digest: string,
+  description: string,
+  metadata?: ProposalMetadata
+): Promise<string> {
+  const { signature } = await signPersonalMessage({
+    message: new TextEncoder().encode(
+      submissionMessage(digest, description, metadata)
+    ),
+  });
+  return signature;
+}
+
+/** One line per set metadata field, in the order the tx-service signs them. */
+function metadataLines(metadata?: ProposalMetadata): string[] {
+  if (!metadata) return [];
+  const lines: string[] = [];
+  if (metadata.category !== undefined) lines.push(`Category: ${metadata.category}`);
+  if (metadata.invoiceRef !== undefined) lines.push(`Invoice: ${metadata.invoiceRef}`);
+  if (metadata.tags?.length) lines.push(`Tags: ${metadata.tags.join(",")}`);
+  if (metadata.ticketId !== undefined) lines.push(`Ticket: ${metadata.ticketId}`);
+  if (metadata.dueDate !== undefined) lines.push(`Due: ${metadata.dueDate}`);
+  return lines;
+}
+
 export class TxServiceClient {
   private baseUrl: string;
 
@@ -17,7 +67,9 @@ export class TxServiceClient {
 
   async addTransaction(
     txBytes: string,
-    description: string
+    description: string,
+    signature: string,
+    metadata?: ProposalMetadata
   ): Promise<AddTransactionResponse> {
     const response = await fetch(`${this.baseUrl}/add_transaction`, {
       method: "POST",
@@ -27,6 +79,8 @@ export class TxServiceClient {
       body: JSON.stringify({
         tx_bytes: txBytes,
         description: description,
+        signature: signature,
+        metadata: metadata,
       }),
     });
 
@@ -65,6 +119,20 @@ export type TransactionDetailsResponse = {
   sender: string;
   addedAt: number;
   description: string;
+  submitter?: string;
+  metadata?: ProposalMetadata;
+  lifecycle: "pending" | "executed" | "removed";
+  /** Set once the payload was pruned, `bcs` is empty then. */
+  prunedAt?: number;
+};
+
+export type ProposalMetadata = {
+  category?: "payroll" | "vendor_payment" | "governance" | "treasury" | "other";
+  invoiceRef?: string;
+  tags?: string[];
+  ticketId?: string;
+  /** `YYYY-MM-DD` */
+  dueDate?: string;
 };
 
 export type AddTransactionResponse = {
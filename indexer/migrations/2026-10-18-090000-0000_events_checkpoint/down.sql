@@ -0,0 +1 @@
+ALTER TABLE events DROP COLUMN checkpoint;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Events indexed before this migration have no checkpoint recorded.
+ALTER TABLE events ADD COLUMN checkpoint INTEGER;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS quarantined_checkpoints;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Checkpoints that kept failing and were skipped by the reader.
+CREATE TABLE IF NOT EXISTS quarantined_checkpoints (
+    checkpoint INTEGER PRIMARY KEY NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    quarantined_at INTEGER NOT NULL
+);
@@ -11,6 +11,8 @@ use reqwest::StatusCode;
 pub enum ApiError {
     // Invalid input data (e.g., malformed address)
     BadRequest(String),
+    // The requested resource does not exist
+    NotFound(String),
     // Database connection or query errors
     Database(anyhow::Error),
     // Internal server errors
@@ -29,6 +31,13 @@ impl IntoResponse for ApiError {
                     "message": msg
                 })),
             ),
+            ApiError::NotFound(msg) => (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Not Found",
+                    "message": msg
+                })),
+            ),
             ApiError::Database(err) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({
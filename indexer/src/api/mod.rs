@@ -1,12 +1,14 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-use crate::db::pool::DbConnectionPool;
+use crate::{
+    config::IsafeIndexerConfig, db::pool::DbConnectionPool, ingestion::IngestionHealth, node::Node,
+};
 
 mod error;
 mod responses;
@@ -15,14 +17,33 @@ mod routes;
 #[derive(Clone)]
 pub struct ApiState {
     pub pool: DbConnectionPool,
+    pub config: IsafeIndexerConfig,
+    pub node: Arc<dyn Node>,
+    pub health: IngestionHealth,
+    /// The lag in checkpoints up to which the indexer counts as ready.
+    pub max_ready_lag: u64,
+    /// The directory of the event archives, read by the export.
+    pub archive_dir: PathBuf,
 }
 
 pub async fn start_api_server(
     pool: DbConnectionPool,
+    config: IsafeIndexerConfig,
+    node: Arc<dyn Node>,
+    health: IngestionHealth,
+    max_ready_lag: u64,
+    archive_dir: PathBuf,
     port: u16,
     token: CancellationToken,
 ) -> anyhow::Result<()> {
-    let state = ApiState { pool };
+    let state = ApiState {
+        pool,
+        config,
+        node,
+        health,
+        max_ready_lag,
+        archive_dir,
+    };
 
     let app = routes::routes().with_state(state);
 
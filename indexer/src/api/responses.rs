@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use iota_types::{base_types::IotaAddress};
+use isafe_common::status::DependencyCheck;
 use serde::{Deserialize, Serialize};
-use crate::db::models::{TransactionSummary};
+use crate::{
+    db::models::{QuarantinedCheckpoint, TransactionSummary},
+    ingestion::IngestionStatus,
+    verify::AccountDriftReport,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -49,4 +54,57 @@ impl axum::response::IntoResponse for GetEventsResponse {
     fn into_response(self) -> axum::response::Response {
         axum::Json(self).into_response()
     }
-}
\ No newline at end of file
+}
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    pub consistent: bool,
+    pub accounts: Vec<AccountDriftReport>,
+}
+
+impl axum::response::IntoResponse for VerifyResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestionResponse {
+    #[serde(flatten)]
+    pub status: IngestionStatus,
+    /// The checkpoints skipped after they kept failing, including those of earlier runs.
+    pub quarantined_checkpoints: Vec<QuarantinedCheckpoint>,
+}
+
+impl axum::response::IntoResponse for IngestionResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusResponse {
+    pub version: &'static str,
+    pub git_revision: &'static str,
+    pub database: DependencyCheck,
+    pub node: DependencyCheck,
+    /// The latest checkpoint of the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_tip: Option<u64>,
+    pub ingestion: IngestionStatus,
+    /// The number of checkpoints the ingestion is behind the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag: Option<u64>,
+    /// The lag up to which the indexer counts as ready.
+    pub max_lag: u64,
+    /// Whether the database is reachable, the reader is running and the lag is within bounds.
+    pub ready: bool,
+}
+
+impl axum::response::IntoResponse for StatusResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
@@ -1,29 +1,59 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
-use crate::{api::responses::GetEventsResponse, db::queries};
+use crate::{
+    api::responses::GetEventsResponse,
+    coalitions::{
+        self, CoalitionReport, DEFAULT_COALITION_LIMIT, MAX_COALITION_LIMIT, ProposedChanges,
+        WeightOverflow,
+    },
+    db::queries,
+    export::{self, ExportFormat, ExportManifest},
+    ingestion::IngestionState,
+    stats::{self, AccountStats},
+    verify,
+};
 use axum::{
-    Router,
-    extract::{Path, State},
-    routing::get,
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderName, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
 };
 use iota_types::base_types::IotaAddress;
+use isafe_common::status::{DependencyCheck, ping};
+use serde::Deserialize;
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::api::{
     ApiState,
     error::ApiError,
-    responses::{Event, GetAccountsResponse, GetTransactionsResponse},
+    responses::{
+        Event, GetAccountsResponse, GetTransactionsResponse, IngestionResponse,
+        StatusResponse, VerifyResponse,
+    },
 };
 
 pub fn routes() -> Router<ApiState> {
     Router::new()
         .route("/health", get(health_check))
+        .route("/status", get(get_status))
+        .route("/ready", get(get_readiness))
         .route("/accounts/{member_address}", get(get_accounts))
         .route("/transactions/{account_address}", get(get_transactions))
         .route("/events/{account_address}", get(get_events))
+        .route("/account/{account_address}/stats", get(get_account_stats))
+        .route(
+            "/account/{account_address}/coalitions",
+            get(get_account_coalitions).post(analyze_account_changes),
+        )
+        .route("/export/{account_address}", get(export_account))
+        .route("/export/{account_address}/manifest", get(export_manifest))
+        .route("/admin/verify", get(verify_all_accounts))
+        .route("/admin/verify/{account_address}", get(verify_account))
+        .route("/admin/ingestion", get(get_ingestion))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -36,6 +66,57 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+/// How long the status waits for the node.
+const NODE_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn status(state: &ApiState) -> StatusResponse {
+    let database = state
+        .pool
+        .get_connection()
+        .and_then(|mut conn| ping(&mut conn));
+
+    let chain_tip = tokio::time::timeout(NODE_STATUS_TIMEOUT, state.node.latest_checkpoint())
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("node did not answer in time")));
+
+    let ingestion = state.health.status();
+    let lag = chain_tip
+        .as_ref()
+        .ok()
+        .and_then(|chain_tip| ingestion.lag(*chain_tip));
+    let ready = database.is_ok()
+        && ingestion.state != IngestionState::Failed
+        && lag.is_some_and(|lag| lag <= state.max_ready_lag);
+
+    StatusResponse {
+        version: crate::VERSION,
+        git_revision: crate::GIT_REVISION,
+        database: DependencyCheck::from(&database),
+        node: DependencyCheck::from(&chain_tip),
+        chain_tip: chain_tip.ok(),
+        ingestion,
+        lag,
+        max_lag: state.max_ready_lag,
+        ready,
+    }
+}
+
+/// The state of the database, the node and the ingestion.
+async fn get_status(State(state): State<ApiState>) -> StatusResponse {
+    status(&state).await
+}
+
+/// Like the status, but `503 Service Unavailable` unless the indexer is ready.
+async fn get_readiness(State(state): State<ApiState>) -> (StatusCode, StatusResponse) {
+    let status = status(&state).await;
+    let code = if status.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, status)
+}
+
 async fn get_accounts(
     State(state): State<ApiState>,
     Path(member_address): Path<String>,
@@ -98,3 +179,322 @@ async fn get_events(
             .collect(),
     })
 }
+
+async fn verify_account(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+) -> Result<VerifyResponse, ApiError> {
+    let address = IotaAddress::from_str(&account_address)
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let report =
+        verify::verify_account(&mut conn, state.node.as_ref(), &state.config, &address).await?;
+
+    Ok(VerifyResponse {
+        consistent: report.is_consistent(),
+        accounts: vec![report],
+    })
+}
+
+async fn verify_all_accounts(State(state): State<ApiState>) -> Result<VerifyResponse, ApiError> {
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let reports =
+        verify::verify_all_accounts(&mut conn, state.node.as_ref(), &state.config).await?;
+
+    Ok(VerifyResponse {
+        consistent: reports.iter().all(|r| r.is_consistent()),
+        accounts: reports,
+    })
+}
+
+/// The state of the checkpoint ingestion and the quarantined checkpoints.
+async fn get_ingestion(State(state): State<ApiState>) -> Result<IngestionResponse, ApiError> {
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let quarantined_checkpoints =
+        queries::get_quarantined_checkpoints(&mut conn).map_err(|err| ApiError::Database(err))?;
+
+    Ok(IngestionResponse {
+        status: state.health.status(),
+        quarantined_checkpoints,
+    })
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+fn export(
+    state: &ApiState,
+    account_address: &str,
+    format: ExportFormat,
+) -> Result<(Vec<u8>, ExportManifest), ApiError> {
+    let address = IotaAddress::from_str(account_address)
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    Ok(export::export_account(
+        &mut conn,
+        &address,
+        &state.archive_dir,
+        format,
+        crate::VERSION,
+    )?)
+}
+
+/// Returns the audit log of an account, the SHA-256 of the content is in the
+/// `x-export-sha256` header.
+async fn export_account(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let (content, manifest) = export(&state, &account_address, query.format)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, query.format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{account_address}.{}\"",
+                    query.format.extension()
+                ),
+            ),
+            (HeaderName::from_static("x-export-sha256"), manifest.sha256),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+async fn export_manifest(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Json<ExportManifest>, ApiError> {
+    let (_, manifest) = export(&state, &account_address, query.format)?;
+    Ok(Json(manifest))
+}
+
+async fn get_account_stats(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+) -> Result<Json<AccountStats>, ApiError> {
+    let address = IotaAddress::from_str(&account_address)
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let stats = stats::account_stats(&mut conn, &state.archive_dir, &address)
+        .map_err(|err| ApiError::Database(err))?
+        .ok_or_else(|| ApiError::NotFound("Account not found".to_string()))?;
+
+    Ok(Json(stats))
+}
+
+#[derive(Deserialize)]
+struct CoalitionQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoalitionRequest {
+    limit: Option<usize>,
+    #[serde(flatten)]
+    changes: ProposedChanges,
+}
+
+fn coalition_report(
+    state: &ApiState,
+    account_address: &str,
+    changes: &ProposedChanges,
+    limit: Option<usize>,
+) -> Result<CoalitionReport, ApiError> {
+    let address = IotaAddress::from_str(account_address)
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+    if changes.threshold == Some(0) {
+        return Err(ApiError::BadRequest(
+            "Threshold must be greater than zero".to_string(),
+        ));
+    }
+    let limit = limit.unwrap_or(DEFAULT_COALITION_LIMIT);
+    if limit == 0 || limit > MAX_COALITION_LIMIT {
+        return Err(ApiError::BadRequest(format!(
+            "The limit must be between 1 and {MAX_COALITION_LIMIT}"
+        )));
+    }
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    coalitions::account_coalitions(&mut conn, &address, changes, limit)
+        .map_err(|err| match err.downcast_ref::<WeightOverflow>() {
+            Some(overflow) => ApiError::BadRequest(overflow.to_string()),
+            None => ApiError::Database(err),
+        })?
+        .ok_or_else(|| ApiError::NotFound("Account not found".to_string()))
+}
+
+async fn get_account_coalitions(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+    Query(query): Query<CoalitionQuery>,
+) -> Result<Json<CoalitionReport>, ApiError> {
+    let report = coalition_report(
+        &state,
+        &account_address,
+        &ProposedChanges::default(),
+        query.limit,
+    )?;
+    Ok(Json(report))
+}
+
+/// Analyzes the account as it is and after the proposed weight and threshold changes.
+async fn analyze_account_changes(
+    State(state): State<ApiState>,
+    Path(account_address): Path<String>,
+    Json(request): Json<CoalitionRequest>,
+) -> Result<Json<CoalitionReport>, ApiError> {
+    let report = coalition_report(&state, &account_address, &request.changes, request.limit)?;
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        config::IsafeIndexerConfig,
+        db::pool::{DbConnectionPool, DbConnectionPoolConfig},
+        ingestion::IngestionHealth,
+        node::tests::MockNode,
+    };
+
+    fn test_state(chain_tip: Option<u64>, health: IngestionHealth) -> ApiState {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        ApiState {
+            pool,
+            config: IsafeIndexerConfig::new(IotaAddress::random_for_testing_only()),
+            node: Arc::new(MockNode {
+                chain_tip,
+                ..Default::default()
+            }),
+            health,
+            max_ready_lag: 10,
+            archive_dir: std::env::temp_dir(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let health = IngestionHealth::default();
+        health.processed(100, 1_000);
+        let status = get_status(State(test_state(Some(105), health))).await;
+        assert!(status.database.ok);
+        assert!(status.node.ok);
+        assert_eq!(status.chain_tip, Some(105));
+        assert_eq!(status.ingestion.last_checkpoint, Some(100));
+        assert_eq!(status.lag, Some(5));
+        assert!(status.ready);
+
+        let status = get_status(State(test_state(None, IngestionHealth::default()))).await;
+        assert!(!status.node.ok);
+        assert_eq!(status.node.error.as_deref(), Some("node unreachable"));
+        assert_eq!(status.chain_tip, None);
+        assert_eq!(status.lag, None);
+        assert!(!status.ready);
+    }
+
+    #[tokio::test]
+    async fn test_readiness() {
+        let ready = |chain_tip, health| async move {
+            get_readiness(State(test_state(chain_tip, health))).await.0
+        };
+
+        // resumed after a restart, before the first checkpoint of this run
+        let health = IngestionHealth::default();
+        health.resumed(100);
+        assert_eq!(ready(Some(110), health.clone()).await, StatusCode::OK);
+        assert_eq!(
+            ready(Some(111), health.clone()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            ready(None, health.clone()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        // nothing processed yet
+        assert_eq!(
+            ready(Some(0), IngestionHealth::default()).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        health.failed(&anyhow::anyhow!("checkpoint store unreachable"));
+        assert_eq!(
+            ready(Some(100), health).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_account_stats_of_unknown_account() {
+        let state = test_state(Some(0), IngestionHealth::default());
+        let account = IotaAddress::random_for_testing_only();
+        let stats = |state: ApiState| get_account_stats(State(state), Path(account.to_string()));
+        assert!(matches!(
+            stats(state.clone()).await,
+            Err(ApiError::NotFound(_))
+        ));
+
+        let mut conn = state.pool.get_connection().unwrap();
+        queries::insert_new_account_entry(&mut conn, account, 1, String::new(), 0).unwrap();
+        drop(conn);
+        let Json(stats) = stats(state).await.unwrap();
+        assert_eq!(stats.account_address, account);
+        assert_eq!(stats.total_transactions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_coalitions_of_unknown_account() {
+        let state = test_state(Some(0), IngestionHealth::default());
+        let result = get_account_coalitions(
+            State(state),
+            Path(IotaAddress::random_for_testing_only().to_string()),
+            Query(CoalitionQuery { limit: None }),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Analysis of how signing power is distributed among the members of an account.
+
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Result;
+use diesel::{Connection, SqliteConnection};
+use iota_types::base_types::IotaAddress;
+use serde::{Deserialize, Serialize};
+
+use crate::db::queries;
+
+/// The default maximum number of minimal winning coalitions to enumerate.
+pub const DEFAULT_COALITION_LIMIT: usize = 100;
+/// The highest limit of minimal winning coalitions a request can ask for.
+pub const MAX_COALITION_LIMIT: usize = 1_000;
+
+/// The combined weight of the members doesn't fit a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightOverflow;
+
+impl fmt::Display for WeightOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The total member weight overflows")
+    }
+}
+
+impl std::error::Error for WeightOverflow {}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberPower {
+    pub member_address: IotaAddress,
+    pub weight: u64,
+    /// Share of the total account weight, between 0 and 1.
+    pub weight_share: f64,
+    /// Whether the member alone can prevent the threshold from being reached.
+    pub can_block: bool,
+    /// Number of the enumerated minimal winning coalitions the member is part of.
+    pub coalitions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoalitionAnalysis {
+    pub threshold: u64,
+    pub total_weight: u64,
+    /// Whether the members can reach the threshold at all.
+    pub reachable: bool,
+    /// The minimum number of members whose combined weight reaches the threshold.
+    pub minimum_coalition_size: Option<usize>,
+    /// Coalitions that reach the threshold, but not anymore if any of their members drops out.
+    pub minimal_winning_coalitions: Vec<Vec<IotaAddress>>,
+    /// Whether the enumeration stopped at the limit.
+    pub coalitions_truncated: bool,
+    /// Whether any single member can block every transaction.
+    pub single_member_can_block: bool,
+    pub members: Vec<MemberPower>,
+}
+
+/// A proposed change to the weight of a member. A weight of zero removes the member, an unknown
+/// address adds a new one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberWeightChange {
+    pub member_address: IotaAddress,
+    pub weight: u64,
+}
+
+/// The current analysis of an account and, if changes were proposed, the analysis after them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoalitionReport {
+    pub account_address: IotaAddress,
+    pub current: CoalitionAnalysis,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposed: Option<CoalitionAnalysis>,
+}
+
+/// Proposed changes to the configuration of an account.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedChanges {
+    pub threshold: Option<u64>,
+    #[serde(default)]
+    pub members: Vec<MemberWeightChange>,
+}
+
+impl ProposedChanges {
+    fn is_empty(&self) -> bool {
+        self.threshold.is_none() && self.members.is_empty()
+    }
+}
+
+/// Analyzes an account from the `members` and `accounts` tables, returns `None` if the account
+/// is not indexed.
+///
+/// Fails with [`WeightOverflow`] if the proposed weights overflow.
+pub(crate) fn account_coalitions(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+    changes: &ProposedChanges,
+    limit: usize,
+) -> Result<Option<CoalitionReport>> {
+    let Some((threshold, members)) = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let Some(entry) = queries::get_account_entry(conn, account)? else {
+            return Ok(None);
+        };
+        Ok(Some((
+            entry.threshold as u64,
+            queries::get_members_for_account(conn, account)?,
+        )))
+    })?
+    else {
+        return Ok(None);
+    };
+
+    let proposed = (!changes.is_empty())
+        .then(|| {
+            analyze(
+                &apply_changes(&members, &changes.members),
+                changes.threshold.unwrap_or(threshold),
+                limit,
+            )
+        })
+        .transpose()?;
+
+    Ok(Some(CoalitionReport {
+        account_address: *account,
+        current: analyze(&members, threshold, limit)?,
+        proposed,
+    }))
+}
+
+/// Applies proposed weight changes to the members of an account.
+pub fn apply_changes(
+    members: &[(IotaAddress, u64)],
+    changes: &[MemberWeightChange],
+) -> Vec<(IotaAddress, u64)> {
+    let mut members: BTreeMap<IotaAddress, u64> = members.iter().copied().collect();
+    for change in changes {
+        if change.weight == 0 {
+            members.remove(&change.member_address);
+        } else {
+            members.insert(change.member_address, change.weight);
+        }
+    }
+    members.into_iter().collect()
+}
+
+/// Analyzes the distribution of signing power for the given members and threshold.
+///
+/// At most `limit` minimal winning coalitions are enumerated.
+pub fn analyze(
+    members: &[(IotaAddress, u64)],
+    threshold: u64,
+    limit: usize,
+) -> Result<CoalitionAnalysis, WeightOverflow> {
+    // heaviest first, so the greedy choice is optimal and the search prunes early
+    let mut sorted = members.to_vec();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let total_weight = sorted
+        .iter()
+        .try_fold(0u64, |total, (_, weight)| total.checked_add(*weight))
+        .ok_or(WeightOverflow)?;
+    let reachable = threshold <= total_weight;
+
+    let minimum_coalition_size = reachable.then(|| {
+        let mut sum = 0;
+        sorted
+            .iter()
+            .take_while(|(_, weight)| {
+                let missing = sum < threshold;
+                sum += weight;
+                missing
+            })
+            .count()
+    });
+
+    let mut search = CoalitionSearch {
+        members: &sorted,
+        suffix_weights: suffix_sums(&sorted),
+        threshold,
+        limit,
+        current: Vec::new(),
+        found: Vec::new(),
+        truncated: false,
+    };
+    if reachable {
+        search.run(0, 0)?;
+    }
+    let CoalitionSearch {
+        found, truncated, ..
+    } = search;
+
+    let members = sorted
+        .iter()
+        .map(|&(member_address, weight)| MemberPower {
+            member_address,
+            weight,
+            weight_share: if total_weight == 0 {
+                0.0
+            } else {
+                weight as f64 / total_weight as f64
+            },
+            can_block: total_weight - weight < threshold,
+            coalitions: found.iter().filter(|c| c.contains(&member_address)).count(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(CoalitionAnalysis {
+        threshold,
+        total_weight,
+        reachable,
+        minimum_coalition_size,
+        single_member_can_block: members.iter().any(|m| m.can_block),
+        minimal_winning_coalitions: found,
+        coalitions_truncated: truncated,
+        members,
+    })
+}
+
+fn suffix_sums(members: &[(IotaAddress, u64)]) -> Vec<u64> {
+    let mut sums = vec![0; members.len() + 1];
+    for i in (0..members.len()).rev() {
+        sums[i] = sums[i + 1] + members[i].1;
+    }
+    sums
+}
+
+/// Depth-first enumeration of minimal winning coalitions over members sorted by descending weight.
+struct CoalitionSearch<'a> {
+    members: &'a [(IotaAddress, u64)],
+    suffix_weights: Vec<u64>,
+    threshold: u64,
+    limit: usize,
+    current: Vec<usize>,
+    found: Vec<Vec<IotaAddress>>,
+    truncated: bool,
+}
+
+impl CoalitionSearch<'_> {
+    fn run(&mut self, index: usize, weight: u64) -> Result<(), WeightOverflow> {
+        if self.truncated {
+            return Ok(());
+        }
+        if weight >= self.threshold {
+            // The last added member is the lightest one, if the coalition loses without it, it
+            // loses without any of its members. Adding more members can't be minimal anymore.
+            let lightest = self.current.last().map_or(0, |&i| self.members[i].1);
+            if weight - lightest < self.threshold {
+                if self.found.len() == self.limit {
+                    self.truncated = true;
+                    return Ok(());
+                }
+                self.found
+                    .push(self.current.iter().map(|&i| self.members[i].0).collect());
+            }
+            return Ok(());
+        }
+        if index == self.members.len()
+            || weight
+                .checked_add(self.suffix_weights[index])
+                .ok_or(WeightOverflow)?
+                < self.threshold
+        {
+            return Ok(());
+        }
+
+        self.current.push(index);
+        let with_member = weight
+            .checked_add(self.members[index].1)
+            .ok_or(WeightOverflow)?;
+        self.run(index + 1, with_member)?;
+        self.current.pop();
+        self.run(index + 1, weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze() {
+        let a = IotaAddress::random_for_testing_only();
+        let b = IotaAddress::random_for_testing_only();
+        let c = IotaAddress::random_for_testing_only();
+        let members = vec![(a, 3), (b, 2), (c, 1)];
+
+        let analysis = analyze(&members, 4, DEFAULT_COALITION_LIMIT).unwrap();
+        assert!(analysis.reachable);
+        assert_eq!(analysis.total_weight, 6);
+        assert_eq!(analysis.minimum_coalition_size, Some(2));
+        assert_eq!(
+            analysis.minimal_winning_coalitions,
+            vec![vec![a, b], vec![a, c]]
+        );
+        assert!(!analysis.coalitions_truncated);
+        // without a, b and c only have 3
+        assert!(analysis.single_member_can_block);
+        assert!(analysis.members[0].can_block);
+        assert!(!analysis.members[1].can_block);
+        assert_eq!(analysis.members[0].coalitions, 2);
+
+        let truncated = analyze(&members, 4, 1).unwrap();
+        assert_eq!(truncated.minimal_winning_coalitions.len(), 1);
+        assert!(truncated.coalitions_truncated);
+
+        let unreachable = analyze(&members, 7, DEFAULT_COALITION_LIMIT).unwrap();
+        assert!(!unreachable.reachable);
+        assert_eq!(unreachable.minimum_coalition_size, None);
+        assert!(unreachable.minimal_winning_coalitions.is_empty());
+    }
+
+    #[test]
+    fn test_what_if() {
+        let a = IotaAddress::random_for_testing_only();
+        let b = IotaAddress::random_for_testing_only();
+        let c = IotaAddress::random_for_testing_only();
+        let members = vec![(a, 3), (b, 2), (c, 1)];
+
+        let changed = apply_changes(
+            &members,
+            &[
+                MemberWeightChange {
+                    member_address: a,
+                    weight: 1,
+                },
+                MemberWeightChange {
+                    member_address: c,
+                    weight: 0,
+                },
+            ],
+        );
+        let analysis = analyze(&changed, 2, DEFAULT_COALITION_LIMIT).unwrap();
+        assert_eq!(analysis.total_weight, 3);
+        assert_eq!(analysis.minimum_coalition_size, Some(1));
+        assert_eq!(analysis.minimal_winning_coalitions, vec![vec![b]]);
+        // without b, a only has 1
+        assert!(analysis.single_member_can_block);
+        assert!(!analysis.members[1].can_block);
+
+        let overflowing = apply_changes(
+            &members,
+            &[MemberWeightChange {
+                member_address: a,
+                weight: u64::MAX,
+            }],
+        );
+        assert_eq!(
+            analyze(&overflowing, 2, DEFAULT_COALITION_LIMIT),
+            Err(WeightOverflow)
+        );
+    }
+}
@@ -1,11 +1,21 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::str::FromStr;
+//! The iSafe deployment the indexer follows, and the network presets of its settings.
+//!
+//! The settings are layered as described in [`isafe_common::config`], their environment variables
+//! are prefixed with `ISAFE_INDEXER_`, e.g. `ISAFE_INDEXER_NODE_URL`.
 
+use anyhow::{Result, anyhow};
 use iota_types::base_types::IotaAddress;
+pub use isafe_common::config::{
+    LayeredConfig, Network, PACKAGE_ADDRESS_ENV, PACKAGE_ADDRESS_OPTION,
+};
 use serde::{Deserialize, Serialize};
 
+/// The prefix of the environment variables of the settings.
+pub const ENV_PREFIX: &str = "ISAFE_INDEXER_";
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct IsafeIndexerConfig {
@@ -13,31 +23,21 @@ pub struct IsafeIndexerConfig {
     pub package_address: IotaAddress,
 }
 
-impl Default for IsafeIndexerConfig {
-    fn default() -> Self {
-        Self::from_env().expect("Failed to load iSafe indexer config from environment variables")
-    }
-}
-
 impl IsafeIndexerConfig {
     pub fn new(package_address: IotaAddress) -> Self {
         Self { package_address }
     }
 
-    pub fn from_env() -> anyhow::Result<Self> {
-        Ok(Self::new(std::env::var("ISAFE_PACKAGE_ADDRESS")?.parse()?))
-    }
-
-    // Create a config based on the package published on devnet.
-    #[expect(unused)]
-    pub fn devnet() -> Self {
-        // TODO change on devnet deployment
-        const ISAFE_PACKAGE_ADDRESS: &str =
-            "0x233478c5db4cc8724a94fdbc24ec6d613177280c02623b768e8b749f7b1d8e03";
-
-        let package_address = IotaAddress::from_str(ISAFE_PACKAGE_ADDRESS).unwrap();
-
-        Self::new(package_address)
+    /// Creates the config from the `package-address` setting, which has no default outside of the
+    /// networks with a deployment, see [`Network::package_address`].
+    pub fn from_setting(package_address: Option<IotaAddress>) -> Result<Self> {
+        package_address.map(Self::new).ok_or_else(|| {
+            anyhow!(
+                "no iSafe package address configured, set `{PACKAGE_ADDRESS_OPTION}` in the \
+                 configuration file, {PACKAGE_ADDRESS_ENV}, --{PACKAGE_ADDRESS_OPTION} or a \
+                 --network with a deployment"
+            )
+        })
     }
 
     /// Checks whether the given package address is an iSage one.
@@ -47,3 +47,29 @@ impl IsafeIndexerConfig {
         package_address == self.package_address
     }
 }
+
+/// The settings a network implies for the service, by option name.
+pub fn preset(network: Network) -> &'static [(&'static str, &'static str)] {
+    match network {
+        Network::Mainnet => &[
+            ("node-url", "https://api.mainnet.iota.cafe"),
+            ("checkpoint-source", "remote"),
+            ("checkpoint-url", "https://checkpoints.mainnet.iota.cafe"),
+        ],
+        Network::Testnet => &[
+            ("node-url", "https://api.testnet.iota.cafe"),
+            ("checkpoint-source", "remote"),
+            ("checkpoint-url", "https://checkpoints.testnet.iota.cafe"),
+        ],
+        Network::Devnet => &[
+            ("node-url", "https://api.devnet.iota.cafe"),
+            ("checkpoint-source", "remote"),
+            ("checkpoint-url", "https://checkpoints.devnet.iota.cafe"),
+        ],
+        Network::Localnet => &[
+            ("node-url", "http://localhost:9000"),
+            ("checkpoint-source", "rest"),
+            ("checkpoint-url", "http://localhost:9000"),
+        ],
+    }
+}
@@ -4,7 +4,7 @@ use std::fmt::Debug;
 use diesel::{Associations, Identifiable, Insertable, Queryable, Selectable, prelude::AsChangeset};
 use diesel::ExpressionMethods;
 use serde::{Deserialize, Serialize};
-use crate::db::schema::{members, transactions, approvals, accounts, events};
+use crate::db::schema::{members, transactions, approvals, accounts, events, quarantined_checkpoints};
 use iota_types::base_types::IotaAddress;
 
 #[derive(Queryable, Identifiable, Debug, Clone)]
@@ -29,7 +29,7 @@ pub struct StoredMember {
 
 #[derive(Queryable, Identifiable, Debug, Clone, Insertable, Selectable, AsChangeset)]
 #[diesel(table_name = events)]
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct StoredEvent {
     pub id: Option<i32>,
     pub account_address: String,
@@ -37,6 +37,9 @@ pub struct StoredEvent {
     pub event_type: String,
     pub timestamp: i64,
     pub content: String,
+    // not recorded for events indexed before checkpoints were tracked
+    #[serde(default)]
+    pub checkpoint: Option<i64>,
 }
 
 #[derive(Queryable, Identifiable, Debug, Clone, Insertable, Selectable, AsChangeset)]
@@ -66,6 +69,7 @@ pub enum Status {
     Approved,
     Executed,
     Rejected,
+    Removed,
 }
 
 impl From<String> for Status {
@@ -75,6 +79,7 @@ impl From<String> for Status {
             "Approved" => Status::Approved,
             "Executed" => Status::Executed,
             "Rejected" => Status::Rejected,
+            "Removed" => Status::Removed,
             _ => panic!("Unknown status string"),
         }
     }
@@ -87,6 +92,7 @@ impl From<Status> for String {
             Status::Approved => "Approved".to_string(),
             Status::Executed => "Executed".to_string(),
             Status::Rejected => "Rejected".to_string(),
+            Status::Removed => "Removed".to_string(),
         }
     }
 }
@@ -113,4 +119,16 @@ pub struct TransactionSummary {
     pub total_account_weight: u64,
     pub approved_by: Vec<IotaAddress>,
     pub created_at: i64,
-}
\ No newline at end of file
+}
+/// A checkpoint the reader skipped after it kept failing.
+#[derive(Queryable, Identifiable, Debug, Clone, Serialize)]
+#[diesel(table_name = quarantined_checkpoints)]
+#[diesel(primary_key(checkpoint))]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedCheckpoint {
+    pub checkpoint: i64,
+    /// The error of the last attempt.
+    pub error: String,
+    pub attempts: i32,
+    pub quarantined_at: i64,
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 
 use anyhow::{Result, anyhow};
 use clap::Args;
@@ -14,13 +14,16 @@ use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 
 // The migrations directory that contains the SQL migration files.
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
-// The path for the sqlite database.
+// The default path for the sqlite database.
 pub const ACCOUNTS_DB_PATH: &str = "data/isafe.db";
 
 pub type PoolConnection = PooledConnection<ConnectionManager<SqliteConnection>>;
 
 #[derive(Args, Debug, Clone)]
 pub struct DbConnectionPoolConfig {
+    /// The path of the sqlite database.
+    #[arg(long, default_value = ACCOUNTS_DB_PATH)]
+    pub db_path: String,
     #[arg(long, default_value_t = Self::DEFAULT_POOL_SIZE)]
     pub pool_size: u32,
     #[arg(long, value_parser = parse_duration, default_value = "30")]
@@ -30,7 +33,7 @@ pub struct DbConnectionPoolConfig {
     pub enable_wal: bool,
 }
 
-fn parse_duration(arg: &str) -> Result<std::time::Duration, std::num::ParseIntError> {
+pub(crate) fn parse_duration(arg: &str) -> Result<std::time::Duration, std::num::ParseIntError> {
     let seconds = arg.parse()?;
     Ok(std::time::Duration::from_secs(seconds))
 }
@@ -43,6 +46,7 @@ impl DbConnectionPoolConfig {
 impl Default for DbConnectionPoolConfig {
     fn default() -> Self {
         Self {
+            db_path: ACCOUNTS_DB_PATH.to_string(),
             pool_size: Self::DEFAULT_POOL_SIZE,
             connection_timeout_secs: Duration::from_secs(Self::DEFAULT_CONNECTION_TIMEOUT_SECS),
             enable_wal: false,
@@ -81,17 +85,21 @@ impl diesel::r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error>
 pub struct DbConnectionPool(Pool<ConnectionManager<SqliteConnection>>);
 
 impl DbConnectionPool {
-    /// Build a new pool of connections.
-    ///
-    /// Resolves the filename URL from the environment.
+    /// Build a new pool of connections to the configured database.
     pub fn new(pool_config: DbConnectionPoolConfig) -> Result<Self> {
-        Self::new_with_path(ACCOUNTS_DB_PATH, pool_config)
+        let path = pool_config.db_path.clone();
+        Self::new_with_path(&path, pool_config)
     }
 
     /// Build a new pool of connections to the given path.
     pub fn new_with_path(path: &str, pool_config: DbConnectionPoolConfig) -> Result<Self> {
-        // Create the data directory if it doesn't exist
-        std::fs::create_dir_all("./data")?;
+        // Create the directory of the database if it doesn't exist
+        if let Some(dir) = Path::new(path)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(dir)?;
+        }
         let manager = ConnectionManager::new(path);
 
         Ok(Self(
@@ -109,7 +117,10 @@ impl DbConnectionPool {
     /// Get a connection from the pool.
     pub fn get_connection(&self) -> Result<PoolConnection> {
         self.0.get().map_err(|e| {
-            anyhow!("failed to get connection from Sqlite connection pool with error: {e:?}",)
+            let message =
+                format!("failed to get connection from Sqlite connection pool with error: {e:?}");
+            // keep the pool error as the source, so the timeout can be told apart
+            anyhow::Error::new(e).context(message)
         })
     }
 
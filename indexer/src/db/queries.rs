@@ -16,6 +16,7 @@ use crate::db::schema::accounts;
 use crate::db::schema::approvals;
 use crate::db::schema::events;
 use crate::db::schema::members;
+use crate::db::schema::quarantined_checkpoints;
 use crate::db::schema::transactions;
 
 pub fn account_exists(conn: &mut SqliteConnection, account: &IotaAddress) -> Result<bool> {
@@ -307,6 +308,7 @@ pub fn delete_approvals_for_not_yet_executed_transactions(
 pub fn recheck_account_transactions_status(
     conn: &mut SqliteConnection,
     account: &IotaAddress,
+    checkpoint: u64,
     timestamp: &mut u64,
 ) -> Result<()> {
     let account_str = account.to_string();
@@ -349,6 +351,7 @@ pub fn recheck_account_transactions_status(
                 conn,
                 account.to_string(),
                 tx.transaction_digest.clone(),
+                checkpoint,
                 threshold_reached_event.type_().to_string(),
                 *timestamp,
                 Base64::encode(bcs::to_bytes(&th_reached_event_inner)?),
@@ -392,6 +395,7 @@ pub fn recheck_account_transactions_status(
             conn,
             account.to_string(),
             tx.transaction_digest.clone(),
+            checkpoint,
             threshold_lost_event.type_().to_string(),
             *timestamp,
             Base64::encode(bcs::to_bytes(&th_lost_event_inner)?),
@@ -405,6 +409,7 @@ pub fn insert_event_entry(
     conn: &mut SqliteConnection,
     account_address: String,
     firing_tx_digest: String,
+    checkpoint: u64,
     event_type: String,
     timestamp: u64,
     // base64 encoded event
@@ -417,6 +422,7 @@ pub fn insert_event_entry(
             events::event_type.eq(event_type),
             events::content.eq(content),
             events::timestamp.eq(timestamp as i64),
+            events::checkpoint.eq(checkpoint as i64),
         ))
         .execute(conn)?;
     Ok(())
@@ -444,3 +450,221 @@ pub fn update_account_threshold(
         .execute(conn)?;
     Ok(())
 }
+
+pub fn get_account_entry(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Option<models::Account>> {
+    let result = accounts::table
+        .filter(accounts::account_address.eq(account.to_string()))
+        .first::<models::Account>(conn)
+        .optional()?;
+    Ok(result)
+}
+
+pub fn get_all_accounts(conn: &mut SqliteConnection) -> Result<Vec<IotaAddress>> {
+    let results = accounts::table
+        .select(accounts::account_address)
+        .load::<String>(conn)?;
+
+    let accounts = results
+        .into_iter()
+        .filter_map(|addr_str| IotaAddress::from_str(&addr_str).ok())
+        .collect();
+
+    Ok(accounts)
+}
+
+/// Returns the members of an account together with their weights.
+pub fn get_members_for_account(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Vec<(IotaAddress, u64)>> {
+    let results = members::table
+        .filter(members::account_address.eq(account.to_string()))
+        .select((members::member_address, members::weight))
+        .load::<(String, i32)>(conn)?;
+
+    let members = results
+        .into_iter()
+        .filter_map(|(addr, weight)| {
+            IotaAddress::from_str(&addr)
+                .ok()
+                .map(|a| (a, weight as u64))
+        })
+        .collect();
+
+    Ok(members)
+}
+
+/// Returns the digests of the transactions of an account that are not removed, together with
+/// their approvers.
+pub fn get_transaction_approvers_for_account(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Vec<(String, Vec<IotaAddress>)>> {
+    conn.transaction(|conn| {
+        let account_str = account.to_string();
+
+        let digests = transactions::table
+            .filter(transactions::account_address.eq(&account_str))
+            .filter(transactions::status.ne(String::from(Status::Removed)))
+            .select(transactions::transaction_digest)
+            .load::<String>(conn)?;
+
+        let mut result = Vec::with_capacity(digests.len());
+        for digest in digests {
+            let approvers = approvals::table
+                .filter(approvals::transaction_digest.eq(&digest))
+                .filter(approvals::account_address.eq(&account_str))
+                .select(approvals::approver_address)
+                .load::<String>(conn)?
+                .into_iter()
+                .filter_map(|addr| IotaAddress::from_str(&addr).ok())
+                .collect();
+            result.push((digest, approvers));
+        }
+
+        Ok(result)
+    })
+}
+
+/// Returns the events of the given types that are older than `before`, oldest first.
+pub fn get_events_before(
+    conn: &mut SqliteConnection,
+    before: u64,
+    event_types: &[&str],
+) -> Result<Vec<models::StoredEvent>> {
+    let results = events::table
+        .filter(events::timestamp.lt(before as i64))
+        .filter(events::event_type.eq_any(event_types.iter().copied()))
+        .order(events::timestamp.asc())
+        .load::<models::StoredEvent>(conn)?;
+
+    Ok(results)
+}
+
+pub fn get_transaction_status(
+    conn: &mut SqliteConnection,
+    account: &str,
+    tx_digest: &str,
+) -> Result<Option<Status>> {
+    let status = transactions::table
+        .filter(transactions::account_address.eq(account))
+        .filter(transactions::transaction_digest.eq(tx_digest))
+        .select(transactions::status)
+        .first::<String>(conn)
+        .optional()?;
+
+    Ok(status.map(Status::from))
+}
+
+pub fn delete_events(conn: &mut SqliteConnection, ids: &[i32]) -> Result<usize> {
+    let deleted =
+        delete(events::table.filter(events::id.eq_any(ids.iter().copied()))).execute(conn)?;
+    Ok(deleted)
+}
+
+/// Inserts a previously archived event, keeping its original id.
+///
+/// Events that are already present are skipped, while a different event with the same id is an
+/// error, as the archive then doesn't belong to this database.
+pub fn insert_archived_event(
+    conn: &mut SqliteConnection,
+    event: &models::StoredEvent,
+) -> Result<usize> {
+    if let Some(id) = event.id {
+        let existing = events::table
+            .filter(events::id.eq(id))
+            .get_result::<models::StoredEvent>(conn)
+            .optional()?;
+        if let Some(existing) = existing {
+            let same = existing.account_address == event.account_address
+                && existing.firing_tx_digest == event.firing_tx_digest
+                && existing.event_type == event.event_type
+                && existing.timestamp == event.timestamp
+                && existing.content == event.content;
+            if !same {
+                return Err(anyhow::anyhow!(
+                    "Archived event {id} collides with a different indexed event"
+                ));
+            }
+            return Ok(0);
+        }
+    }
+    let inserted = insert_into(events::table).values(event).execute(conn)?;
+    Ok(inserted)
+}
+
+pub fn get_stored_transactions_for_account(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Vec<models::StoredTransaction>> {
+    let results = transactions::table
+        .filter(transactions::account_address.eq(account.to_string()))
+        .order(transactions::created_at.asc())
+        .load::<models::StoredTransaction>(conn)?;
+    Ok(results)
+}
+
+/// Returns the approvals of all transactions of an account as (digest, approver, approved_at).
+pub fn get_approvals_for_account(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Vec<(String, String, i64)>> {
+    let results = approvals::table
+        .filter(approvals::account_address.eq(account.to_string()))
+        .select((
+            approvals::transaction_digest,
+            approvals::approver_address,
+            approvals::approved_at,
+        ))
+        .load::<(String, String, i64)>(conn)?;
+    Ok(results)
+}
+
+
+/// Records a checkpoint the reader skipped, adding to the attempts if it was skipped before.
+pub fn insert_quarantined_checkpoint(
+    conn: &mut SqliteConnection,
+    checkpoint: u64,
+    error: &str,
+    attempts: u32,
+    quarantined_at: u64,
+) -> Result<()> {
+    insert_into(quarantined_checkpoints::table)
+        .values((
+            quarantined_checkpoints::checkpoint.eq(checkpoint as i64),
+            quarantined_checkpoints::error.eq(error),
+            quarantined_checkpoints::attempts.eq(attempts as i32),
+            quarantined_checkpoints::quarantined_at.eq(quarantined_at as i64),
+        ))
+        .on_conflict(quarantined_checkpoints::checkpoint)
+        .do_update()
+        .set((
+            quarantined_checkpoints::error.eq(error),
+            quarantined_checkpoints::attempts
+                .eq(quarantined_checkpoints::attempts + attempts as i32),
+            quarantined_checkpoints::quarantined_at.eq(quarantined_at as i64),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Returns the quarantined checkpoints, lowest first.
+pub fn get_quarantined_checkpoints(
+    conn: &mut SqliteConnection,
+) -> Result<Vec<models::QuarantinedCheckpoint>> {
+    let results = quarantined_checkpoints::table
+        .order(quarantined_checkpoints::checkpoint.asc())
+        .load::<models::QuarantinedCheckpoint>(conn)?;
+    Ok(results)
+}
+
+/// Returns the highest checkpoint events were indexed from, if any.
+pub fn get_latest_event_checkpoint(conn: &mut SqliteConnection) -> Result<Option<u64>> {
+    let checkpoint = events::table
+        .select(dsl::max(events::checkpoint))
+        .first::<Option<i64>>(conn)?;
+    Ok(checkpoint.map(|checkpoint| checkpoint as u64))
+}
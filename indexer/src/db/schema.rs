@@ -29,6 +29,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    quarantined_checkpoints (checkpoint) {
+        checkpoint -> Int8,
+        error -> Text,
+        attempts -> Integer,
+        quarantined_at -> Int8,
+    }
+}
+
 diesel::table! {
     transactions (transaction_digest, account_address) {
         transaction_digest -> Text,
@@ -47,6 +56,7 @@ diesel::table! {
         event_type -> Text,
         timestamp -> Int8,
         content -> Text,
+        checkpoint -> Nullable<Int8>,
     }
 }
 
@@ -54,4 +64,11 @@ diesel::joinable!(events -> accounts (account_address));
 diesel::joinable!(members -> accounts (account_address));
 diesel::joinable!(transactions -> accounts (account_address));
 
-diesel::allow_tables_to_appear_in_same_query!(accounts, approvals, events, members, transactions,);
+diesel::allow_tables_to_appear_in_same_query!(
+    accounts,
+    approvals,
+    events,
+    members,
+    quarantined_checkpoints,
+    transactions,
+);
@@ -35,23 +35,66 @@ impl IsafeEvent {
             return Ok(None);
         }
 
-        Ok(match event.type_.name.as_str() {
-            "AccountCreatedEvent" => Some(Self::AccountCreated(bcs::from_bytes(&event.contents)?)),
-            "AccountRotatedEvent" => Some(Self::AccountRotated(bcs::from_bytes(&event.contents)?)),
-            "MemberAddedEvent" => Some(Self::MemberAdded(bcs::from_bytes(&event.contents)?)),
-            "MemberRemovedEvent" => Some(Self::MemberRemoved(bcs::from_bytes(&event.contents)?)),
-            "MemberWeightUpdatedEvent" => Some(Self::MemberWeightUpdated(bcs::from_bytes(&event.contents)?)),
-            "ThresholdChangedEvent" => Some(Self::ThresholdChanged(bcs::from_bytes(&event.contents)?)),
-            "GuardianChangedEvent" => Some(Self::GuardianChanged(bcs::from_bytes(&event.contents)?)),
-            "TransactionProposedEvent" => Some(Self::TransactionProposed(bcs::from_bytes(&event.contents)?)),
-            "TransactionApprovedEvent" => Some(Self::TransactionApproved(bcs::from_bytes(&event.contents)?)),
-            "TransactionApprovalThresholdReachedEvent" => Some(Self::TransactionApprovalThresholdReached(bcs::from_bytes(&event.contents)?)),
-            "TransactionApprovalThresholdLostEvent" => Some(Self::TransactionApprovalThresholdLost(bcs::from_bytes(&event.contents)?)),
-            "TransactionExecutedEvent" => Some(Self::TransactionExecuted(bcs::from_bytes(&event.contents)?)),
-            "TransactionRemovedEvent" => Some(Self::TransactionRemoved(bcs::from_bytes(&event.contents)?)),
+        Self::from_bcs(event.type_.name.as_str(), &event.contents)
+    }
+
+    /// Decodes an event as stored in the `events` table.
+    pub(crate) fn from_stored(event_type: &str, content: &str) -> anyhow::Result<Self> {
+        Self::from_bcs(event_type, &Base64::decode(content)?)?
+            .ok_or_else(|| anyhow::anyhow!("unknown event type {event_type}"))
+    }
+
+    fn from_bcs(type_name: &str, contents: &[u8]) -> anyhow::Result<Option<Self>> {
+        Ok(match type_name {
+            "AccountCreatedEvent" => Some(Self::AccountCreated(bcs::from_bytes(contents)?)),
+            "AccountRotatedEvent" => Some(Self::AccountRotated(bcs::from_bytes(contents)?)),
+            "MemberAddedEvent" => Some(Self::MemberAdded(bcs::from_bytes(contents)?)),
+            "MemberRemovedEvent" => Some(Self::MemberRemoved(bcs::from_bytes(contents)?)),
+            "MemberWeightUpdatedEvent" => Some(Self::MemberWeightUpdated(bcs::from_bytes(contents)?)),
+            "ThresholdChangedEvent" => Some(Self::ThresholdChanged(bcs::from_bytes(contents)?)),
+            "GuardianChangedEvent" => Some(Self::GuardianChanged(bcs::from_bytes(contents)?)),
+            "TransactionProposedEvent" => Some(Self::TransactionProposed(bcs::from_bytes(contents)?)),
+            "TransactionApprovedEvent" => Some(Self::TransactionApproved(bcs::from_bytes(contents)?)),
+            "TransactionApprovalThresholdReachedEvent" => Some(Self::TransactionApprovalThresholdReached(bcs::from_bytes(contents)?)),
+            "TransactionApprovalThresholdLostEvent" => Some(Self::TransactionApprovalThresholdLost(bcs::from_bytes(contents)?)),
+            "TransactionExecutedEvent" => Some(Self::TransactionExecuted(bcs::from_bytes(contents)?)),
+            "TransactionRemovedEvent" => Some(Self::TransactionRemoved(bcs::from_bytes(contents)?)),
             _ => None,
         })
     }
+
+    /// Returns the digest of the proposed transaction this event refers to, if any.
+    pub(crate) fn transaction_digest(&self) -> Option<&[u8]> {
+        match self {
+            IsafeEvent::TransactionProposed(e) => Some(&e.transaction_digest),
+            IsafeEvent::TransactionApproved(e) => Some(&e.transaction_digest),
+            IsafeEvent::TransactionApprovalThresholdReached(e) => Some(&e.transaction_digest),
+            IsafeEvent::TransactionApprovalThresholdLost(e) => Some(&e.transaction_digest),
+            IsafeEvent::TransactionExecuted(e) => Some(&e.transaction_digest),
+            IsafeEvent::TransactionRemoved(e) => Some(&e.transaction_digest),
+            _ => None,
+        }
+    }
+
+    /// Returns the decoded fields of the event as JSON.
+    pub(crate) fn fields(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            IsafeEvent::AccountCreated(e) => serde_json::to_value(e),
+            IsafeEvent::AccountRotated(e) => serde_json::to_value(e),
+            IsafeEvent::MemberAdded(e) => serde_json::to_value(e),
+            IsafeEvent::MemberRemoved(e) => serde_json::to_value(e),
+            IsafeEvent::MemberWeightUpdated(e) => serde_json::to_value(e),
+            IsafeEvent::ThresholdChanged(e) => serde_json::to_value(e),
+            IsafeEvent::GuardianChanged(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionProposed(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionApproved(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionApprovalThresholdReached(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionApprovalThresholdLost(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionExecuted(e) => serde_json::to_value(e),
+            IsafeEvent::TransactionRemoved(e) => serde_json::to_value(e),
+        }
+    }
+
     pub fn type_(&self) -> &str {
         match self {
             IsafeEvent::AccountCreated(_) => "AccountCreatedEvent",
@@ -123,7 +166,7 @@ pub struct GuardianChangedEvent {
     pub new_guardian: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Member {
     pub member_address: IotaAddress,
     pub weight: u64,
@@ -0,0 +1,415 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Audit log export of the full history of an account.
+//!
+//! The history includes the events the retention moved to the archives.
+
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use diesel::SqliteConnection;
+use fastcrypto::{
+    encoding::{Encoding, Hex},
+    hash::{HashFunction, Sha256},
+};
+use iota_types::{base_types::IotaAddress, digests::TransactionDigest};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{IsafeEvent, Member},
+    retention,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// The configuration of an account after an event was applied.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountConfiguration {
+    pub threshold: u64,
+    pub total_weight: u64,
+    pub members: Vec<Member>,
+}
+
+/// Tracks the configuration of an account while replaying its events.
+#[derive(Debug, Default)]
+struct ConfigurationReplay {
+    threshold: u64,
+    members: BTreeMap<IotaAddress, u64>,
+}
+
+impl ConfigurationReplay {
+    /// Applies the changes of a governance event.
+    fn apply(&mut self, event: &IsafeEvent) {
+        match event {
+            IsafeEvent::AccountCreated(e) => {
+                self.threshold = e.threshold;
+                self.members = e
+                    .members
+                    .iter()
+                    .map(|m| (m.member_address, m.weight))
+                    .collect();
+            }
+            IsafeEvent::AccountRotated(e) => {
+                self.threshold = e.threshold;
+                self.members = e
+                    .members
+                    .iter()
+                    .map(|m| (m.member_address, m.weight))
+                    .collect();
+            }
+            IsafeEvent::MemberAdded(e) => {
+                self.members
+                    .insert(e.member.member_address, e.member.weight);
+            }
+            IsafeEvent::MemberRemoved(e) => {
+                self.members.remove(&e.member.member_address);
+            }
+            IsafeEvent::MemberWeightUpdated(e) => {
+                self.members.insert(e.member.member_address, e.new_weight);
+            }
+            IsafeEvent::ThresholdChanged(e) => {
+                self.threshold = e.new_threshold;
+            }
+            _ => {}
+        }
+    }
+
+    fn snapshot(&self) -> AccountConfiguration {
+        AccountConfiguration {
+            threshold: self.threshold,
+            total_weight: self.members.values().sum(),
+            members: self
+                .members
+                .iter()
+                .map(|(&member_address, &weight)| Member {
+                    member_address,
+                    weight,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One row of the audit log.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub event_id: Option<i32>,
+    pub timestamp: i64,
+    pub time: String,
+    pub checkpoint: Option<i64>,
+    /// The digest of the on-chain transaction that emitted the event.
+    pub tx_digest: String,
+    pub event_type: String,
+    /// The address that triggered the event. Governance changes and executions are performed by
+    /// the account itself, events derived by the indexer have no actor.
+    pub actor: Option<IotaAddress>,
+    /// The proposed transaction the event refers to, if any.
+    pub transaction_digest: Option<String>,
+    pub data: serde_json::Value,
+    pub configuration: AccountConfiguration,
+}
+
+impl AuditRecord {
+    const CSV_HEADER: [&'static str; 12] = [
+        "event_id",
+        "timestamp",
+        "time",
+        "checkpoint",
+        "tx_digest",
+        "event_type",
+        "actor",
+        "transaction_digest",
+        "threshold",
+        "total_weight",
+        "members",
+        "data",
+    ];
+
+    fn csv_record(&self) -> Result<[String; 12]> {
+        let members = self
+            .configuration
+            .members
+            .iter()
+            .map(|m| format!("{}:{}", m.member_address, m.weight))
+            .collect::<Vec<_>>()
+            .join(";");
+        Ok([
+            self.event_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.timestamp.to_string(),
+            self.time.clone(),
+            self.checkpoint.map(|c| c.to_string()).unwrap_or_default(),
+            self.tx_digest.clone(),
+            self.event_type.clone(),
+            self.actor.map(|a| a.to_string()).unwrap_or_default(),
+            self.transaction_digest.clone().unwrap_or_default(),
+            self.configuration.threshold.to_string(),
+            self.configuration.total_weight.to_string(),
+            members,
+            serde_json::to_string(&self.data)?,
+        ])
+    }
+}
+
+/// Describes an export so recipients can verify its integrity.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub account_address: IotaAddress,
+    pub format: ExportFormat,
+    pub records: usize,
+    /// The number of records read from the event archives.
+    pub archived_records: usize,
+    pub first_timestamp: Option<i64>,
+    pub last_timestamp: Option<i64>,
+    /// Hex encoded SHA-256 of the export content.
+    pub sha256: String,
+    pub generated_at: String,
+    pub indexer_version: String,
+}
+
+/// Builds the audit log of an account from the database and the archives in `archive_dir`,
+/// oldest event first.
+///
+/// Returns the records and how many of them were read from the archives.
+pub(crate) fn audit_records(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+    archive_dir: &Path,
+) -> Result<(Vec<AuditRecord>, usize)> {
+    let (events, archived) = retention::account_events(conn, archive_dir, account)?;
+
+    let mut configuration = ConfigurationReplay::default();
+    let mut records = Vec::with_capacity(events.len());
+    for stored in events {
+        let event = IsafeEvent::from_stored(&stored.event_type, &stored.content)?;
+        configuration.apply(&event);
+
+        let transaction_digest = event
+            .transaction_digest()
+            .map(|digest| {
+                <[u8; 32]>::try_from(digest)
+                    .map(|digest| TransactionDigest::from(digest).to_string())
+                    .map_err(|_| anyhow!("invalid transaction digest length"))
+            })
+            .transpose()?;
+
+        records.push(AuditRecord {
+            event_id: stored.id,
+            timestamp: stored.timestamp,
+            time: DateTime::<Utc>::from_timestamp_millis(stored.timestamp)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            checkpoint: stored.checkpoint,
+            tx_digest: stored.firing_tx_digest,
+            event_type: stored.event_type,
+            actor: actor(&event, account),
+            transaction_digest,
+            data: event.fields()?,
+            configuration: configuration.snapshot(),
+        });
+    }
+
+    Ok((records, archived))
+}
+
+fn actor(event: &IsafeEvent, account: &IotaAddress) -> Option<IotaAddress> {
+    match event {
+        IsafeEvent::TransactionProposed(e) => Some(e.proposer),
+        IsafeEvent::TransactionApproved(e) => Some(e.approver),
+        IsafeEvent::AccountCreated(_)
+        | IsafeEvent::TransactionApprovalThresholdReached(_)
+        | IsafeEvent::TransactionApprovalThresholdLost(_) => None,
+        _ => Some(*account),
+    }
+}
+
+/// Renders the audit log of an account in the given format and returns it with its manifest.
+pub(crate) fn export_account(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+    archive_dir: &Path,
+    format: ExportFormat,
+    indexer_version: &str,
+) -> Result<(Vec<u8>, ExportManifest)> {
+    let (records, archived_records) = audit_records(conn, account, archive_dir)?;
+
+    let content = match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(AuditRecord::CSV_HEADER)?;
+            for record in &records {
+                writer.write_record(record.csv_record()?)?;
+            }
+            writer.into_inner().map_err(|e| anyhow!("{e}"))?
+        }
+        ExportFormat::Jsonl => {
+            let mut content = Vec::new();
+            for record in &records {
+                serde_json::to_writer(&mut content, record)?;
+                content.push(b'\n');
+            }
+            content
+        }
+    };
+
+    let manifest = ExportManifest {
+        account_address: *account,
+        format,
+        records: records.len(),
+        archived_records,
+        first_timestamp: records.first().map(|r| r.timestamp),
+        last_timestamp: records.last().map(|r| r.timestamp),
+        sha256: Hex::encode(Sha256::digest(&content).digest),
+        generated_at: Utc::now().to_rfc3339(),
+        indexer_version: indexer_version.to_string(),
+    };
+
+    Ok((content, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use fastcrypto::encoding::Base64;
+
+    use super::*;
+    use crate::{
+        db::{
+            models::Status,
+            pool::{DbConnectionPool, DbConnectionPoolConfig},
+            queries,
+        },
+        events::TransactionProposedEvent,
+        retention::{archive_events, import_archives},
+    };
+
+    #[test]
+    fn test_export_includes_archived_events() {
+        let dir = std::env::temp_dir().join(format!("isafe-export-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+
+        let account = IotaAddress::random_for_testing_only();
+        let proposer = IotaAddress::random_for_testing_only();
+        let [executed, pending] = [(); 2].map(|_| TransactionDigest::random());
+        let mut conn = pool.get_connection().unwrap();
+        queries::insert_new_account_entry(&mut conn, account, 1, String::new(), 0).unwrap();
+        for (digest, status, timestamp) in [
+            (executed, Status::Executed, 1_000),
+            (pending, Status::Proposed, 3_000),
+        ] {
+            queries::insert_transaction_entry(
+                &mut conn,
+                digest.to_string(),
+                &account,
+                &proposer,
+                status.into(),
+                0,
+            )
+            .unwrap();
+            let event = TransactionProposedEvent {
+                account_id: account,
+                transaction_digest: digest.into_inner().to_vec(),
+                proposer,
+            };
+            queries::insert_event_entry(
+                &mut conn,
+                account.to_string(),
+                digest.to_string(),
+                1,
+                "TransactionProposedEvent".to_string(),
+                timestamp,
+                Base64::encode(bcs::to_bytes(&event).unwrap()),
+            )
+            .unwrap();
+        }
+        drop(conn);
+        assert_eq!(archive_events(&pool, &dir, 2_000).unwrap(), 1);
+
+        let mut conn = pool.get_connection().unwrap();
+        let (content, manifest) =
+            export_account(&mut conn, &account, &dir, ExportFormat::Jsonl, "1.0.0").unwrap();
+        let records: Vec<serde_json::Value> = String::from_utf8(content.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["transactionDigest"], executed.to_string());
+        assert_eq!(records[0]["actor"], proposer.to_string());
+        assert_eq!(records[1]["transactionDigest"], pending.to_string());
+
+        assert_eq!(manifest.account_address, account);
+        assert_eq!(manifest.format, ExportFormat::Jsonl);
+        assert_eq!(manifest.records, 2);
+        assert_eq!(manifest.archived_records, 1);
+        assert_eq!(manifest.first_timestamp, Some(1_000));
+        assert_eq!(manifest.last_timestamp, Some(3_000));
+        assert_eq!(
+            manifest.sha256,
+            Hex::encode(Sha256::digest(&content).digest)
+        );
+        assert_eq!(manifest.indexer_version, "1.0.0");
+
+        let (content, manifest) =
+            export_account(&mut conn, &account, &dir, ExportFormat::Csv, "1.0.0").unwrap();
+        let mut reader = csv::Reader::from_reader(content.as_slice());
+        assert_eq!(
+            reader.headers().unwrap().iter().collect::<Vec<_>>(),
+            AuditRecord::CSV_HEADER
+        );
+        let rows: Vec<_> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][4], executed.to_string());
+        assert_eq!(&rows[1][5], "TransactionProposedEvent");
+        assert_eq!(manifest.records, 2);
+        assert_eq!(
+            manifest.sha256,
+            Hex::encode(Sha256::digest(&content).digest)
+        );
+        drop(conn);
+
+        // an imported archive isn't exported twice
+        assert_eq!(import_archives(&pool, &dir).unwrap(), 1);
+        let mut conn = pool.get_connection().unwrap();
+        let (_, manifest) =
+            export_account(&mut conn, &account, &dir, ExportFormat::Jsonl, "1.0.0").unwrap();
+        assert_eq!(manifest.records, 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
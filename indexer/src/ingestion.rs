@@ -0,0 +1,287 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Error policy and health of the checkpoint ingestion.
+//!
+//! Checkpoints that fail on a busy or locked database are retried until they succeed. Any other
+//! failure, including a panic, is retried [`ErrorPolicy::checkpoint_max_attempts`] times before
+//! the checkpoint is quarantined: it is recorded in the `quarantined_checkpoints` table and
+//! skipped, and the ingestion is reported as degraded. The API keeps serving in either case.
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use clap::Args;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::Serialize;
+
+/// How the reader deals with checkpoints that fail to process.
+#[derive(Args, Debug, Clone)]
+pub struct ErrorPolicy {
+    /// Quarantine a checkpoint after it failed this many times. Busy database errors are retried
+    /// without limit.
+    #[arg(long, default_value_t = 5)]
+    pub checkpoint_max_attempts: u32,
+    /// The delay before the first retry of a failed checkpoint, in milliseconds. It doubles with
+    /// every further retry.
+    #[arg(long, default_value_t = 500)]
+    pub retry_backoff_ms: u64,
+    /// The upper bound of the delay between retries, in milliseconds.
+    #[arg(long, default_value_t = 30_000)]
+    pub max_retry_backoff_ms: u64,
+}
+
+impl Default for ErrorPolicy {
+    fn default() -> Self {
+        Self {
+            checkpoint_max_attempts: 5,
+            retry_backoff_ms: 500,
+            max_retry_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl ErrorPolicy {
+    /// The delay before the given retry, counting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let delay = self
+            .retry_backoff_ms
+            .saturating_mul(1 << retry.min(32))
+            .min(self.max_retry_backoff_ms);
+        Duration::from_millis(delay)
+    }
+}
+
+/// The messages of the SQLite result codes `SQLITE_BUSY` and `SQLITE_LOCKED`. Diesel reports
+/// both as [`DatabaseErrorKind::Unknown`] without the code, so they are told apart by the exact
+/// message SQLite assigns to the code.
+const BUSY_MESSAGES: [&str; 2] = ["database is locked", "database table is locked"];
+
+/// Whether the error is worth retrying as is: the database was busy or locked, the connection
+/// was closed, or no pooled connection became available in time.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if cause.is::<diesel::r2d2::PoolError>() {
+            return true;
+        }
+        match cause.downcast_ref::<DieselError>() {
+            Some(DieselError::DatabaseError(DatabaseErrorKind::ClosedConnection, _)) => true,
+            Some(DieselError::DatabaseError(DatabaseErrorKind::Unknown, info)) => {
+                BUSY_MESSAGES.contains(&info.message())
+            }
+            _ => false,
+        }
+    })
+}
+
+/// The state of the ingestion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestionState {
+    Running,
+    /// At least one checkpoint is failing and being retried.
+    Retrying,
+    /// Checkpoints were quarantined since the start.
+    Degraded,
+    /// The reader stopped with an error, only the API is served.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestionStatus {
+    pub state: IngestionState,
+    /// The checkpoints currently being retried.
+    pub retrying: Vec<u64>,
+    /// The number of checkpoints quarantined since the start.
+    pub quarantined: u64,
+    /// The last checkpoint processed or quarantined since the start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checkpoint: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checkpoint_timestamp_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl IngestionStatus {
+    /// The number of checkpoints the ingestion is behind `chain_tip`, unknown until the first
+    /// checkpoint is processed.
+    pub fn lag(&self, chain_tip: u64) -> Option<u64> {
+        self.last_checkpoint
+            .map(|last| chain_tip.saturating_sub(last))
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthInner {
+    retrying: BTreeSet<u64>,
+    quarantined: u64,
+    failure: Option<String>,
+    last_error: Option<String>,
+    /// The sequence number and timestamp of the last checkpoint. The timestamp is unknown for
+    /// the checkpoint an earlier run stopped at.
+    last_checkpoint: Option<(u64, Option<u64>)>,
+}
+
+/// The health of the ingestion, shared by the reader and the API.
+#[derive(Debug, Clone, Default)]
+pub struct IngestionHealth(Arc<Mutex<HealthInner>>);
+
+impl IngestionHealth {
+    fn update<T>(&self, f: impl FnOnce(&mut HealthInner) -> T) -> T {
+        let mut inner = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut inner)
+    }
+
+    pub fn retrying(&self, checkpoint: u64, err: &anyhow::Error) {
+        self.update(|inner| {
+            inner.retrying.insert(checkpoint);
+            inner.last_error = Some(err.to_string());
+        });
+    }
+
+    /// Marks a checkpoint as done, whether it was committed or quarantined.
+    pub fn processed(&self, checkpoint: u64, timestamp_ms: u64) {
+        self.update(|inner| {
+            inner.retrying.remove(&checkpoint);
+            if inner
+                .last_checkpoint
+                .is_none_or(|(last, _)| last < checkpoint)
+            {
+                inner.last_checkpoint = Some((checkpoint, Some(timestamp_ms)));
+            }
+        });
+    }
+
+    /// Sets the last checkpoint an earlier run processed, so the lag is known before the first
+    /// checkpoint of this run is processed.
+    pub fn resumed(&self, checkpoint: u64) {
+        self.update(|inner| {
+            if inner.last_checkpoint.is_none() {
+                inner.last_checkpoint = Some((checkpoint, None));
+            }
+        });
+    }
+
+    pub fn quarantined(&self, checkpoint: u64) {
+        self.update(|inner| {
+            inner.retrying.remove(&checkpoint);
+            inner.quarantined += 1;
+        });
+    }
+
+    pub fn failed(&self, err: &anyhow::Error) {
+        self.update(|inner| {
+            inner.failure = Some(err.to_string());
+            inner.last_error = Some(err.to_string());
+        });
+    }
+
+    pub fn status(&self) -> IngestionStatus {
+        self.update(|inner| {
+            let state = if inner.failure.is_some() {
+                IngestionState::Failed
+            } else if !inner.retrying.is_empty() {
+                IngestionState::Retrying
+            } else if inner.quarantined > 0 {
+                IngestionState::Degraded
+            } else {
+                IngestionState::Running
+            };
+            IngestionStatus {
+                state,
+                retrying: inner.retrying.iter().copied().collect(),
+                quarantined: inner.quarantined,
+                last_checkpoint: inner.last_checkpoint.map(|(checkpoint, _)| checkpoint),
+                last_checkpoint_timestamp_ms: inner
+                    .last_checkpoint
+                    .and_then(|(_, timestamp)| timestamp),
+                last_error: inner.last_error.clone(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = ErrorPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(3), Duration::from_millis(4_000));
+        assert_eq!(policy.backoff(6), Duration::from_millis(30_000));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(30_000));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        use diesel::{Connection, SqliteConnection, connection::SimpleConnection};
+
+        // a second writer fails right away while the first holds the lock
+        let path = std::env::temp_dir().join(format!("isafe-busy-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut holder = SqliteConnection::establish(path.to_str().unwrap()).unwrap();
+        let mut writer = SqliteConnection::establish(path.to_str().unwrap()).unwrap();
+        holder.batch_execute("BEGIN IMMEDIATE;").unwrap();
+        let busy = anyhow::Error::new(writer.batch_execute("BEGIN IMMEDIATE;").unwrap_err())
+            .context("failed to insert event");
+        assert!(is_transient(&busy));
+        holder.batch_execute("ROLLBACK;").unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let constraint = anyhow::Error::new(DieselError::DatabaseError(
+            DatabaseErrorKind::UniqueViolation,
+            Box::new("UNIQUE constraint failed: accounts.account_address".to_string()),
+        ));
+        assert!(!is_transient(&constraint));
+        assert!(!is_transient(&anyhow!("invalid transaction digest length")));
+    }
+
+    #[test]
+    fn test_health_state() {
+        let health = IngestionHealth::default();
+        assert_eq!(health.status().state, IngestionState::Running);
+        assert_eq!(health.status().lag(10), None);
+
+        health.resumed(5);
+        let status = health.status();
+        assert_eq!(status.last_checkpoint, Some(5));
+        assert_eq!(status.last_checkpoint_timestamp_ms, None);
+        assert_eq!(status.lag(10), Some(5));
+
+        health.retrying(7, &anyhow!("database is locked"));
+        assert_eq!(health.status().state, IngestionState::Retrying);
+        health.processed(7, 1_000);
+        let status = health.status();
+        assert_eq!(status.state, IngestionState::Running);
+        assert_eq!(status.last_checkpoint, Some(7));
+        assert_eq!(status.last_checkpoint_timestamp_ms, Some(1_000));
+        assert_eq!(status.lag(10), Some(3));
+        assert_eq!(status.lag(5), Some(0));
+
+        health.retrying(8, &anyhow!("invalid transaction digest length"));
+        health.quarantined(8);
+        health.processed(8, 1_250);
+        // a checkpoint finishing late doesn't move the last checkpoint back
+        health.processed(6, 750);
+        let status = health.status();
+        assert_eq!(status.last_checkpoint, Some(8));
+        assert_eq!(status.state, IngestionState::Degraded);
+        assert_eq!(status.quarantined, 1);
+        assert!(status.retrying.is_empty());
+
+        health.failed(&anyhow!("checkpoint store unreachable"));
+        assert_eq!(health.status().state, IngestionState::Failed);
+    }
+}
@@ -1,12 +1,21 @@
 mod api;
+mod coalitions;
 mod config;
 mod db;
 mod metrics;
+mod node;
 mod worker;
 mod events;
+mod export;
+mod ingestion;
+mod retention;
+mod stats;
+mod verify;
 
-use anyhow::Result;
-use clap::Parser;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{Result, anyhow, bail};
+use clap::{Args, Parser, Subcommand};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
@@ -14,12 +23,18 @@ use tracing_subscriber::{
     EnvFilter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
 };
 
+use iota_types::base_types::IotaAddress;
+
 use crate::{
     api::start_api_server,
-    config::IsafeIndexerConfig,
+    config::{ENV_PREFIX, IsafeIndexerConfig, LayeredConfig, Network, PACKAGE_ADDRESS_ENV, preset},
     db::pool::{DbConnectionPool, DbConnectionPoolConfig},
+    export::ExportFormat,
+    ingestion::{ErrorPolicy, IngestionHealth},
     metrics::PrometheusServer,
-    worker::{IsafeWorker, run_isafe_reader},
+    node::NodeClient,
+    retention::{RetentionConfig, run_retention},
+    worker::{CheckpointConfig, CheckpointSource, IsafeWorker, run_isafe_reader},
 };
 
 // Define the `GIT_REVISION` and `VERSION` consts
@@ -33,36 +48,153 @@ bin_version::bin_version!();
     version = VERSION,
     propagate_version = true,
 )]
+struct Cli {
+    /// A TOML file with settings, keyed by option name. Environment variables and options
+    /// override it.
+    #[arg(long, global = true, env = "ISAFE_INDEXER_CONFIG")]
+    config: Option<PathBuf>,
+    /// Use the endpoints and deployment of a known network as defaults.
+    #[arg(long, global = true, value_enum)]
+    network: Option<Network>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
 enum Command {
-    Start {
+    Start(StartArgs),
+    /// Inspect the configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Compare the indexed state against the on-chain state and print the drift.
+    Verify {
         #[clap(flatten)]
         connection_pool_config: DbConnectionPoolConfig,
         /// The URL of an IOTA node with JSON API.
         #[arg(long, default_value = "http://localhost:9000")]
         node_url: String,
-        /// The URL of an IOTA node with REST API enabled or a historical store.
-        #[arg(long, default_value = "http://localhost:9000")]
-        checkpoint_url: String,
-        /// The number of workers to spawn in parallel.
-        #[arg(long, default_value_t = 1)]
-        num_workers: usize,
-        /// The port to run the API server on.
-        #[arg(long, default_value_t = 3030)]
-        api_port: u16,
+        /// Address of the isafe package.
+        #[arg(long, env = PACKAGE_ADDRESS_ENV)]
+        package_address: Option<IotaAddress>,
+        /// The account to verify. All indexed accounts are verified if omitted.
+        #[arg(long)]
+        account: Option<IotaAddress>,
+    },
+    /// Export the audit log of an account together with a SHA-256 manifest.
+    Export {
+        #[clap(flatten)]
+        connection_pool_config: DbConnectionPoolConfig,
+        /// The account to export.
+        #[arg(long)]
+        account: IotaAddress,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+        /// The file to write the export to. The manifest is written next to it with a
+        /// `.manifest.json` suffix.
+        #[arg(long)]
+        output: PathBuf,
+        /// The directory containing the archived events, which are included in the export.
+        #[arg(long, default_value = "./data/archive")]
+        archive_dir: PathBuf,
+    },
+    /// Import archived events back into the database.
+    ImportArchive {
+        #[clap(flatten)]
+        connection_pool_config: DbConnectionPoolConfig,
+        /// The directory containing the archived events.
+        #[arg(long, default_value = "./data/archive")]
+        archive_dir: PathBuf,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective settings of `start` with the layer each comes from, and validate them.
+    Print(StartArgs),
+}
+
+#[derive(Args)]
+struct StartArgs {
+    #[clap(flatten)]
+    connection_pool_config: DbConnectionPoolConfig,
+    /// The URL of an IOTA node with JSON API.
+    #[arg(long, default_value = "http://localhost:9000")]
+    node_url: String,
+    #[clap(flatten)]
+    checkpoint_config: CheckpointConfig,
+    #[clap(flatten)]
+    error_policy: ErrorPolicy,
+    /// Address of the isafe package.
+    #[arg(long, env = PACKAGE_ADDRESS_ENV)]
+    package_address: Option<IotaAddress>,
+    /// The number of workers to spawn in parallel.
+    #[arg(long, default_value_t = 1)]
+    num_workers: usize,
+    /// The port to run the API server on.
+    #[arg(long, default_value_t = 3030)]
+    api_port: u16,
+    /// The readiness endpoint fails once the ingestion is more than this many checkpoints
+    /// behind the node.
+    #[arg(long, default_value_t = 100)]
+    readiness_max_lag: u64,
+    #[clap(flatten)]
+    retention_config: RetentionConfig,
+}
+
+impl StartArgs {
+    /// Checks the settings the value parsers can't check on their own.
+    fn validate(&self) -> Result<IsafeIndexerConfig> {
+        if self.connection_pool_config.pool_size == 0 {
+            bail!("pool-size must be at least 1");
+        }
+        if self.num_workers == 0 {
+            bail!("num-workers must be at least 1");
+        }
+        if self.checkpoint_config.reader_timeout_secs.is_zero() {
+            bail!("reader-timeout-secs must be at least 1");
+        }
+        reqwest::Url::parse(&self.node_url)
+            .map_err(|err| anyhow!("invalid node-url {}: {err}", self.node_url))?;
+        let checkpoints = &self.checkpoint_config;
+        if checkpoints.checkpoint_source != CheckpointSource::Local {
+            reqwest::Url::parse(&checkpoints.checkpoint_url).map_err(|err| {
+                anyhow!("invalid checkpoint-url {}: {err}", checkpoints.checkpoint_url)
+            })?;
+        }
+        if self.error_policy.checkpoint_max_attempts == 0 {
+            bail!("checkpoint-max-attempts must be at least 1");
+        }
+        if let (Some(start), Some(stop)) = (checkpoints.start_checkpoint, checkpoints.stop_checkpoint)
+            && start > stop
+        {
+            bail!("start-checkpoint must not be after stop-checkpoint");
+        }
+        if self.retention_config.event_retention_days == Some(0) {
+            bail!("event-retention-days must be at least 1");
+        }
+        IsafeIndexerConfig::from_setting(self.package_address)
+    }
+}
+
 impl Command {
-    async fn execute(self) -> Result<()> {
+    async fn execute(self, config: &LayeredConfig) -> Result<()> {
         match self {
-            Command::Start {
-                connection_pool_config,
-                node_url,
-                checkpoint_url,
-                num_workers,
-                api_port,
-            } => {
+            Command::Start(args) => {
                 info!("Starting iSafe Indexer");
+                let isafe_config = args.validate()?;
+                let StartArgs {
+                    connection_pool_config,
+                    node_url,
+                    checkpoint_config,
+                    error_policy,
+                    package_address: _,
+                    num_workers,
+                    api_port,
+                    readiness_max_lag,
+                    retention_config,
+                } = args;
 
                 let prometheus = PrometheusServer::new();
                 let registry = prometheus.registry();
@@ -74,27 +206,64 @@ impl Command {
                 let connection_pool = DbConnectionPool::new(connection_pool_config)?;
                 connection_pool.run_migrations()?;
 
+                info!("Starting with iSafe config: {isafe_config:#?}");
+
+                let health = IngestionHealth::default();
+
                 // Spawn the auction API server
                 let handle = cancel_token.clone();
                 let database_pool = connection_pool.clone();
-                tasks.spawn(async move { start_api_server(database_pool, api_port, handle).await });
+                let api_config = isafe_config.clone();
+                let api_node = Arc::new(NodeClient::new(node_url.clone()));
+                let api_health = health.clone();
+                let archive_dir = retention_config.archive_dir.clone();
+                tasks.spawn(async move {
+                    start_api_server(
+                        database_pool,
+                        api_config,
+                        api_node,
+                        api_health,
+                        readiness_max_lag,
+                        archive_dir,
+                        api_port,
+                        handle,
+                    )
+                    .await
+                });
 
-                // spawn the main isafe reader worker
-                let isafe_config = IsafeIndexerConfig::from_env().unwrap_or_default();
-                info!("Starting with iSafe config: {isafe_config:#?}");
+                // Spawn the event retention, if enabled
+                if let Some(days) = retention_config.event_retention_days {
+                    let handle = cancel_token.clone();
+                    let database_pool = connection_pool.clone();
+                    let retention = Duration::from_secs(days * 24 * 60 * 60);
+                    tasks.spawn(async move {
+                        run_retention(database_pool, retention_config, retention, handle).await
+                    });
+                }
 
+                // spawn the main isafe reader worker, on a child token so a failing reader
+                // leaves the API running
                 let handle = cancel_token.clone();
                 tasks.spawn(async move {
+                    let reader_handle = handle.child_token();
                     let worker = IsafeWorker::new(
                         connection_pool,
                         isafe_config,
-                        handle.clone(),
+                        reader_handle.clone(),
+                        error_policy,
+                        health.clone(),
                     )?;
 
-                    tokio::select! {
-                        res = run_isafe_reader(worker, &node_url, &checkpoint_url, &registry, num_workers) => res,
-                        _ = handle.cancelled() => Ok(()),
+                    let res = tokio::select! {
+                        res = run_isafe_reader(worker, &node_url, &checkpoint_config, &registry, num_workers) => res,
+                        _ = reader_handle.cancelled() => Ok(()),
+                    };
+                    if let Err(err) = res {
+                        error!("the checkpoint reader failed with error: {err:#}; serving the API only");
+                        health.failed(&err);
+                        handle.cancelled().await;
                     }
+                    Ok(())
                 });
 
                 let mut exit_code = Ok(());
@@ -149,6 +318,79 @@ impl Command {
 
                 exit_code
             }
+            Command::Config {
+                command: ConfigCommand::Print(args),
+            } => {
+                print!("{}", config.render(&["config", "print"]));
+                args.validate()?;
+                Ok(())
+            }
+            Command::Verify {
+                connection_pool_config,
+                node_url,
+                package_address,
+                account,
+            } => {
+                let isafe_config = IsafeIndexerConfig::from_setting(package_address)?;
+                let connection_pool = DbConnectionPool::new(connection_pool_config)?;
+                connection_pool.run_migrations()?;
+
+                let node = NodeClient::new(node_url);
+                let mut conn = connection_pool.get_connection()?;
+
+                let reports = match account {
+                    Some(account) => vec![
+                        verify::verify_account(&mut conn, &node, &isafe_config, &account).await?,
+                    ],
+                    None => verify::verify_all_accounts(&mut conn, &node, &isafe_config).await?,
+                };
+                println!("{}", serde_json::to_string_pretty(&reports)?);
+
+                let drifted = reports.iter().filter(|r| !r.is_consistent()).count();
+                if drifted > 0 {
+                    bail!("{drifted} of {} accounts drifted from the on-chain state", reports.len());
+                }
+                info!("{} accounts are consistent with the on-chain state", reports.len());
+                Ok(())
+            }
+            Command::Export {
+                connection_pool_config,
+                account,
+                format,
+                output,
+                archive_dir,
+            } => {
+                let connection_pool = DbConnectionPool::new(connection_pool_config)?;
+                connection_pool.run_migrations()?;
+                let mut conn = connection_pool.get_connection()?;
+
+                let (content, manifest) =
+                    export::export_account(&mut conn, &account, &archive_dir, format, VERSION)?;
+                std::fs::write(&output, content)?;
+
+                let mut manifest_path = output.clone().into_os_string();
+                manifest_path.push(".manifest.json");
+                std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+                info!(
+                    "Exported {} events of account {account} to {} (sha256 {})",
+                    manifest.records,
+                    output.display(),
+                    manifest.sha256
+                );
+                Ok(())
+            }
+            Command::ImportArchive {
+                connection_pool_config,
+                archive_dir,
+            } => {
+                let connection_pool = DbConnectionPool::new(connection_pool_config)?;
+                connection_pool.run_migrations()?;
+
+                let imported = retention::import_archives(&connection_pool, &archive_dir)?;
+                info!("Imported {imported} events from {}", archive_dir.display());
+                Ok(())
+            }
         }
     }
 }
@@ -159,7 +401,14 @@ async fn main() -> Result<()> {
 
     set_up_logging()?;
 
-    Command::parse().execute().await?;
+    let (cli, config) = LayeredConfig::parse::<Cli>(ENV_PREFIX, preset)?;
+    if let Some(path) = &cli.config {
+        info!("Using configuration file {}", path.display());
+    }
+    if let Some(network) = cli.network {
+        info!("Using the defaults of {network:?}");
+    }
+    cli.command.execute(&config).await?;
     Ok(())
 }
 
@@ -178,7 +427,6 @@ fn set_up_logging() -> Result<()> {
 pub async fn interrupt_or_terminate() -> Result<()> {
     #[cfg(unix)]
     {
-        use anyhow::anyhow;
         use tokio::signal::unix::{SignalKind, signal};
         let mut terminate = signal(SignalKind::terminate())
             .map_err(|e| anyhow!("cannot listen to `SIGTERM`: {e}"))?;
@@ -0,0 +1,163 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The node client shared by the API and the commands, and the requests they make to the node.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use iota_json_rpc_types::{IotaObjectDataOptions, IotaObjectResponse, IotaRawData};
+use iota_sdk::{IotaClient, IotaClientBuilder};
+use iota_types::{base_types::ObjectID, dynamic_field::DynamicFieldName};
+use tokio::sync::OnceCell;
+
+/// The requests the indexer makes to the node, besides reading checkpoints.
+///
+/// Implemented by [`NodeClient`], and by a mocked node in the tests.
+#[async_trait]
+pub(crate) trait Node: Send + Sync {
+    /// Returns the sequence number of the latest checkpoint.
+    async fn latest_checkpoint(&self) -> Result<u64>;
+
+    /// Returns the BCS bytes of the dynamic field `name` of `parent`, if it exists.
+    async fn dynamic_field_bcs(
+        &self,
+        parent: ObjectID,
+        name: DynamicFieldName,
+    ) -> Result<Option<Vec<u8>>>;
+
+    /// Returns the object ids of all dynamic fields of `parent`.
+    async fn dynamic_field_ids(&self, parent: ObjectID) -> Result<Vec<ObjectID>>;
+
+    /// Returns the BCS bytes of the given object, if it exists.
+    async fn object_bcs(&self, id: ObjectID) -> Result<Option<Vec<u8>>>;
+}
+
+/// A client of an IOTA node that connects on first use.
+///
+/// Connecting lazily keeps the API available while the node is unreachable, requests that need
+/// the node fail until it can be reached.
+pub struct NodeClient {
+    node_url: String,
+    client: OnceCell<IotaClient>,
+}
+
+impl NodeClient {
+    pub fn new(node_url: String) -> Self {
+        Self {
+            node_url,
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Returns the client, connecting to the node if not connected yet.
+    async fn get(&self) -> Result<&IotaClient> {
+        self.client
+            .get_or_try_init(|| async {
+                Ok(IotaClientBuilder::default().build(&self.node_url).await?)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Node for NodeClient {
+    async fn latest_checkpoint(&self) -> Result<u64> {
+        Ok(self
+            .get()
+            .await?
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await?)
+    }
+
+    async fn dynamic_field_bcs(
+        &self,
+        parent: ObjectID,
+        name: DynamicFieldName,
+    ) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .get()
+            .await?
+            .read_api()
+            .get_dynamic_field_object(parent, name)
+            .await?;
+        Ok(move_object_bcs(response))
+    }
+
+    async fn dynamic_field_ids(&self, parent: ObjectID) -> Result<Vec<ObjectID>> {
+        let client = self.get().await?;
+        let mut ids = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = client
+                .read_api()
+                .get_dynamic_fields(parent, cursor, None)
+                .await?;
+            ids.extend(page.data.into_iter().map(|info| info.object_id));
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(ids)
+    }
+
+    async fn object_bcs(&self, id: ObjectID) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .get()
+            .await?
+            .read_api()
+            .get_object_with_options(id, IotaObjectDataOptions::new().with_bcs())
+            .await?;
+        Ok(move_object_bcs(response))
+    }
+}
+
+fn move_object_bcs(response: IotaObjectResponse) -> Option<Vec<u8>> {
+    match response.data?.bcs? {
+        IotaRawData::MoveObject(object) => Some(object.bcs_bytes),
+        IotaRawData::Package(_) => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    /// A node serving checkpoints and dynamic fields from memory.
+    #[derive(Default)]
+    pub(crate) struct MockNode {
+        pub chain_tip: Option<u64>,
+        /// The BCS bytes of dynamic fields, by parent and name type.
+        pub fields: HashMap<(ObjectID, String), Vec<u8>>,
+        pub objects: HashMap<ObjectID, Vec<u8>>,
+        pub children: HashMap<ObjectID, Vec<ObjectID>>,
+    }
+
+    #[async_trait]
+    impl Node for MockNode {
+        async fn latest_checkpoint(&self) -> Result<u64> {
+            self.chain_tip.ok_or_else(|| anyhow!("node unreachable"))
+        }
+
+        async fn dynamic_field_bcs(
+            &self,
+            parent: ObjectID,
+            name: DynamicFieldName,
+        ) -> Result<Option<Vec<u8>>> {
+            Ok(self.fields.get(&(parent, name.type_.to_string())).cloned())
+        }
+
+        async fn dynamic_field_ids(&self, parent: ObjectID) -> Result<Vec<ObjectID>> {
+            Ok(self.children.get(&parent).cloned().unwrap_or_default())
+        }
+
+        async fn object_bcs(&self, id: ObjectID) -> Result<Option<Vec<u8>>> {
+            Ok(self.objects.get(&id).cloned())
+        }
+    }
+}
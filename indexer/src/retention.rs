@@ -0,0 +1,415 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archival and pruning of old events.
+//!
+//! Events of executed or removed transactions that are older than the retention period are
+//! written to gzip compressed JSON-lines files, one per account and month, and deleted from the
+//! database. The archives can be imported again with [`import_archives`].
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use diesel::{Connection, SqliteConnection, connection::SimpleConnection};
+use flate2::{Compression, read::MultiGzDecoder, write::GzEncoder};
+use iota_types::{base_types::IotaAddress, digests::TransactionDigest};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    db::{
+        models::{Status, StoredEvent},
+        pool::{DbConnectionPool, parse_duration},
+        queries,
+    },
+    events::IsafeEvent,
+};
+
+/// Event types that belong to a proposed transaction and can therefore be archived once the
+/// transaction was executed or removed.
+const TRANSACTION_EVENT_TYPES: &[&str] = &[
+    "TransactionProposedEvent",
+    "TransactionApprovedEvent",
+    "TransactionApprovalThresholdReachedEvent",
+    "TransactionApprovalThresholdLostEvent",
+    "TransactionExecutedEvent",
+    "TransactionRemovedEvent",
+];
+
+const ARCHIVE_EXTENSION: &str = "jsonl.gz";
+
+#[derive(Args, Debug, Clone)]
+pub struct RetentionConfig {
+    /// Archive and prune events of executed or removed transactions that are older than this
+    /// many days. Events are kept forever if not set.
+    #[arg(long)]
+    pub event_retention_days: Option<u64>,
+    /// The directory the archived events are written to.
+    #[arg(long, default_value = "./data/archive")]
+    pub archive_dir: PathBuf,
+    /// How often the retention runs, in seconds.
+    #[arg(long, value_parser = parse_duration, default_value = "3600")]
+    pub retention_interval_secs: Duration,
+}
+
+/// Periodically archives and prunes expired events until cancelled.
+pub(crate) async fn run_retention(
+    pool: DbConnectionPool,
+    config: RetentionConfig,
+    retention: Duration,
+    token: CancellationToken,
+) -> Result<()> {
+    info!(
+        "Archiving events older than {} days to {}",
+        retention.as_secs() / 86400,
+        config.archive_dir.display()
+    );
+    loop {
+        let pool = pool.clone();
+        let archive_dir = config.archive_dir.clone();
+        let cutoff =
+            (Utc::now().timestamp_millis() as u64).saturating_sub(retention.as_millis() as u64);
+        match tokio::task::spawn_blocking(move || archive_events(&pool, &archive_dir, cutoff))
+            .await?
+        {
+            Ok(0) => {}
+            Ok(archived) => info!("Archived and pruned {archived} events"),
+            // retention is best effort, a failed run is retried in the next interval
+            Err(e) => warn!("archiving events failed: {e}"),
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            _ = tokio::time::sleep(config.retention_interval_secs) => {}
+        }
+    }
+}
+
+/// Archives and prunes the events of executed or removed transactions older than `cutoff` (in ms).
+///
+/// Returns the number of archived events.
+pub(crate) fn archive_events(
+    pool: &DbConnectionPool,
+    archive_dir: &Path,
+    cutoff: u64,
+) -> Result<usize> {
+    let mut conn = pool.get_connection()?;
+
+    conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let mut statuses = HashMap::new();
+        let mut batches: BTreeMap<(String, String), Vec<StoredEvent>> = BTreeMap::new();
+
+        for event in queries::get_events_before(conn, cutoff, TRANSACTION_EVENT_TYPES)? {
+            let decoded = IsafeEvent::from_stored(&event.event_type, &event.content)?;
+            let Some(digest) = decoded.transaction_digest() else {
+                continue;
+            };
+            let digest = TransactionDigest::from(
+                <[u8; 32]>::try_from(digest)
+                    .map_err(|_| anyhow!("invalid transaction digest length"))?,
+            )
+            .to_string();
+
+            let key = (event.account_address.clone(), digest);
+            if !statuses.contains_key(&key) {
+                let status = queries::get_transaction_status(conn, &key.0, &key.1)?;
+                statuses.insert(key.clone(), status);
+            }
+            if !matches!(statuses[&key], Some(Status::Executed | Status::Removed)) {
+                continue;
+            }
+
+            batches
+                .entry((
+                    event.account_address.clone(),
+                    archive_month(event.timestamp),
+                ))
+                .or_default()
+                .push(event);
+        }
+
+        let mut archived = 0;
+        for ((account, month), events) in batches {
+            // write first, so that a failed write rolls back the deletion; events written by a
+            // run that was rolled back afterwards are skipped, so they aren't archived twice
+            write_archive(archive_dir, &account, &month, &events)?;
+            let ids: Vec<i32> = events.iter().filter_map(|e| e.id).collect();
+            archived += queries::delete_events(conn, &ids)?;
+        }
+
+        Ok(archived)
+    })
+}
+
+/// Imports all archives found in `archive_dir`.
+///
+/// Foreign keys are not enforced during the import, so archives can be loaded into a fresh
+/// database before the accounts are indexed again. Events that are already present are skipped,
+/// and the import fails without importing anything if an archived event's id belongs to a
+/// different event. Returns the number of imported events.
+pub(crate) fn import_archives(pool: &DbConnectionPool, archive_dir: &Path) -> Result<usize> {
+    let mut conn = pool.get_connection()?;
+    conn.batch_execute("PRAGMA foreign_keys = OFF;")?;
+
+    let result = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let mut imported = 0;
+        for path in archive_files(archive_dir)? {
+            for event in read_archive(&path)? {
+                imported += queries::insert_archived_event(conn, &event)?;
+            }
+            info!("Imported archive {}", path.display());
+        }
+        Ok(imported)
+    });
+
+    conn.batch_execute("PRAGMA foreign_keys = ON;")?;
+    result
+}
+
+fn archive_month(timestamp_ms: i64) -> String {
+    DateTime::<Utc>::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .format("%Y-%m")
+        .to_string()
+}
+
+fn archive_path(archive_dir: &Path, account: &str, month: &str) -> PathBuf {
+    archive_dir
+        .join(account)
+        .join(format!("{month}.{ARCHIVE_EXTENSION}"))
+}
+
+/// Appends the events that are not archived yet to the archive of the account and month.
+///
+/// Every call appends a new gzip member, which is why the archives are read with a
+/// [`MultiGzDecoder`].
+fn write_archive(
+    archive_dir: &Path,
+    account: &str,
+    month: &str,
+    events: &[StoredEvent],
+) -> Result<()> {
+    let path = archive_path(archive_dir, account, month);
+    let archived = archived_ids(&path)?;
+    let events: Vec<_> = events
+        .iter()
+        .filter(|event| event.id.is_none_or(|id| !archived.contains(&id)))
+        .collect();
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(path.parent().expect("archive path has a parent"))?;
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    for event in events {
+        serde_json::to_writer(&mut encoder, event)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()?.sync_all()?;
+    Ok(())
+}
+
+/// Reads the events of the archive, none if it doesn't exist.
+fn read_archive(path: &Path) -> Result<Vec<StoredEvent>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let reader = BufReader::new(MultiGzDecoder::new(File::open(path)?));
+    let mut events = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        events.push(
+            serde_json::from_str(&line)
+                .map_err(|e| anyhow!("invalid archive entry in {}: {e}", path.display()))?,
+        );
+    }
+    Ok(events)
+}
+
+/// The ids of the events in the archive.
+fn archived_ids(path: &Path) -> Result<HashSet<i32>> {
+    Ok(read_archive(path)?
+        .into_iter()
+        .filter_map(|event| event.id)
+        .collect())
+}
+
+/// Reads the events of an account from the archive and the database, oldest first.
+///
+/// Imported archives are in both places, so database events already read from the archive are
+/// skipped. Also returns how many of the events come from the archive.
+pub(crate) fn account_events(
+    conn: &mut SqliteConnection,
+    archive_dir: &Path,
+    account: &IotaAddress,
+) -> Result<(Vec<StoredEvent>, usize)> {
+    let mut events = account_archive(archive_dir, account)?;
+    let archived_ids: HashSet<i32> = events.iter().filter_map(|event| event.id).collect();
+    let archived = events.len();
+    events.extend(
+        queries::get_events_for_account(conn, account)?
+            .into_iter()
+            .filter(|event| event.id.is_none_or(|id| !archived_ids.contains(&id))),
+    );
+    events.sort_by_key(|event| (event.timestamp, event.id));
+    Ok((events, archived))
+}
+
+/// Reads the archived events of an account, oldest month first.
+fn account_archive(archive_dir: &Path, account: &IotaAddress) -> Result<Vec<StoredEvent>> {
+    let account_dir = archive_dir.join(account.to_string());
+    if !account_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut events = Vec::new();
+    for path in account_archive_files(&account_dir)? {
+        events.extend(read_archive(&path)?);
+    }
+    Ok(events)
+}
+
+/// Lists all archive files, ordered by account and month.
+fn archive_files(archive_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for account_dir in fs::read_dir(archive_dir)? {
+        let account_dir = account_dir?.path();
+        if account_dir.is_dir() {
+            files.extend(account_archive_files(&account_dir)?);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Lists the archive files of one account, ordered by month.
+fn account_archive_files(account_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for file in fs::read_dir(account_dir)? {
+        let file = file?.path();
+        if file.to_string_lossy().ends_with(ARCHIVE_EXTENSION) {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use fastcrypto::encoding::{Base64, Encoding};
+
+    use super::*;
+    use crate::{db::pool::DbConnectionPoolConfig, events::TransactionProposedEvent};
+
+    #[test]
+    fn test_archive_events_of_finished_transactions() {
+        let dir = std::env::temp_dir().join(format!("isafe-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+
+        let account = IotaAddress::random_for_testing_only();
+        let [executed, removed, pending] = [(); 3].map(|_| TransactionDigest::random());
+        let mut conn = pool.get_connection().unwrap();
+        queries::insert_new_account_entry(&mut conn, account, 1, String::new(), 0).unwrap();
+        for (digest, status) in [
+            (executed, Status::Executed),
+            (removed, Status::Removed),
+            (pending, Status::Proposed),
+        ] {
+            queries::insert_transaction_entry(
+                &mut conn,
+                digest.to_string(),
+                &account,
+                &account,
+                status.into(),
+                0,
+            )
+            .unwrap();
+            let event = TransactionProposedEvent {
+                account_id: account,
+                transaction_digest: digest.into_inner().to_vec(),
+                proposer: account,
+            };
+            queries::insert_event_entry(
+                &mut conn,
+                account.to_string(),
+                digest.to_string(),
+                1,
+                "TransactionProposedEvent".to_string(),
+                1_000,
+                Base64::encode(bcs::to_bytes(&event).unwrap()),
+            )
+            .unwrap();
+        }
+        drop(conn);
+
+        // the events are younger than the cutoff
+        assert_eq!(archive_events(&pool, &dir, 1_000).unwrap(), 0);
+        assert_eq!(archive_events(&pool, &dir, 2_000).unwrap(), 2);
+        assert_eq!(archive_events(&pool, &dir, 2_000).unwrap(), 0);
+
+        let mut conn = pool.get_connection().unwrap();
+        let kept = queries::get_events_for_account(&mut conn, &account).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].firing_tx_digest, pending.to_string());
+        drop(conn);
+
+        let path = archive_path(&dir, &account.to_string(), "1970-01");
+        let archived = read_archive(&path).unwrap();
+        let mut digests: Vec<_> = archived
+            .iter()
+            .map(|event| event.firing_tx_digest.clone())
+            .collect();
+        digests.sort();
+        let mut expected = vec![executed.to_string(), removed.to_string()];
+        expected.sort();
+        assert_eq!(digests, expected);
+
+        // writing the same events again, as after a rolled back run, doesn't duplicate them
+        write_archive(&dir, &account.to_string(), "1970-01", &archived).unwrap();
+        assert_eq!(read_archive(&path).unwrap().len(), 2);
+
+        assert_eq!(import_archives(&pool, &dir).unwrap(), 2);
+        let mut conn = pool.get_connection().unwrap();
+        assert_eq!(
+            queries::get_events_for_account(&mut conn, &account)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // an archived event whose id now belongs to a different event is refused
+        let mut other = archived[0].clone();
+        other.firing_tx_digest = TransactionDigest::random().to_string();
+        write_archive(&dir, &account.to_string(), "1970-02", &[other]).unwrap();
+        drop(conn);
+        assert!(import_archives(&pool, &dir).is_err());
+        let mut conn = pool.get_connection().unwrap();
+        assert_eq!(
+            queries::get_events_for_account(&mut conn, &account)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+}
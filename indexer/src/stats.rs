@@ -0,0 +1,453 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Participation and latency analytics of an account.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Utc};
+use diesel::{Connection, SqliteConnection};
+use iota_types::{base_types::IotaAddress, digests::TransactionDigest};
+use serde::Serialize;
+
+use crate::{
+    db::{
+        models::{Status, StoredTransaction},
+        queries,
+    },
+    events::IsafeEvent,
+    retention,
+};
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCounts {
+    pub proposed: u64,
+    pub approved: u64,
+    pub executed: u64,
+    pub rejected: u64,
+    pub removed: u64,
+}
+
+/// Median and 90th percentile of a duration in milliseconds.
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationStats {
+    pub samples: usize,
+    pub median_ms: Option<u64>,
+    pub p90_ms: Option<u64>,
+}
+
+impl DurationStats {
+    fn from_samples(mut samples: Vec<u64>) -> Self {
+        samples.sort_unstable();
+        Self {
+            samples: samples.len(),
+            median_ms: percentile(&samples, 50),
+            p90_ms: percentile(&samples, 90),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberParticipation {
+    pub member_address: IotaAddress,
+    pub weight: u64,
+    pub proposed: u64,
+    pub approved: u64,
+    /// Share of the account's proposals the member approved, between 0 and 1.
+    pub participation_rate: f64,
+    /// Average time between a proposal and the member's approval, without the member's own
+    /// proposals.
+    pub average_approval_latency_ms: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyProposals {
+    /// ISO week, e.g. `2025-W50`.
+    pub week: String,
+    pub proposals: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStats {
+    pub account_address: IotaAddress,
+    pub total_transactions: u64,
+    pub status_counts: StatusCounts,
+    pub proposal_to_threshold: DurationStats,
+    pub threshold_to_execution: DurationStats,
+    pub members: Vec<MemberParticipation>,
+    pub proposals_per_week: Vec<WeeklyProposals>,
+}
+
+/// The raw data the statistics are computed from.
+struct StatsInput {
+    members: Vec<(IotaAddress, u64)>,
+    transactions: Vec<StoredTransaction>,
+    /// (transaction digest, approver, approved at)
+    approvals: Vec<(String, IotaAddress, i64)>,
+    /// First time a transaction reached its threshold, by digest.
+    threshold_reached_at: HashMap<String, i64>,
+    /// Time a transaction was executed, by digest.
+    executed_at: HashMap<String, i64>,
+}
+
+/// Computes the statistics of an account from the `transactions`, `approvals` and `events`
+/// tables and the archived events, or `None` if the account is not indexed.
+pub(crate) fn account_stats(
+    conn: &mut SqliteConnection,
+    archive_dir: &Path,
+    account: &IotaAddress,
+) -> Result<Option<AccountStats>> {
+    let Some(input) = conn.transaction::<_, anyhow::Error, _>(|conn| {
+        if !queries::account_exists(conn, account)? {
+            return Ok(None);
+        }
+        let mut threshold_reached_at = HashMap::new();
+        let mut executed_at = HashMap::new();
+        // the events of finished transactions may be archived already
+        let (events, _) = retention::account_events(conn, archive_dir, account)?;
+        for stored in events.iter().filter(|stored| {
+            matches!(
+                stored.event_type.as_str(),
+                "TransactionApprovalThresholdReachedEvent" | "TransactionExecutedEvent"
+            )
+        }) {
+            let event = IsafeEvent::from_stored(&stored.event_type, &stored.content)?;
+            let digest = event
+                .transaction_digest()
+                .ok_or_else(|| anyhow!("transaction event without digest"))?;
+            let digest = TransactionDigest::from(
+                <[u8; 32]>::try_from(digest)
+                    .map_err(|_| anyhow!("invalid transaction digest length"))?,
+            )
+            .to_string();
+            let times = match event {
+                IsafeEvent::TransactionExecuted(_) => &mut executed_at,
+                _ => &mut threshold_reached_at,
+            };
+            // events are ordered, keep the first occurrence
+            times.entry(digest).or_insert(stored.timestamp);
+        }
+
+        Ok(Some(StatsInput {
+            members: queries::get_members_for_account(conn, account)?,
+            transactions: queries::get_stored_transactions_for_account(conn, account)?,
+            approvals: queries::get_approvals_for_account(conn, account)?
+                .into_iter()
+                .filter_map(|(digest, approver, at)| {
+                    IotaAddress::from_str(&approver)
+                        .ok()
+                        .map(|approver| (digest, approver, at))
+                })
+                .collect(),
+            threshold_reached_at,
+            executed_at,
+        }))
+    })?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(compute_stats(*account, input)))
+}
+
+fn compute_stats(account_address: IotaAddress, input: StatsInput) -> AccountStats {
+    let mut status_counts = StatusCounts::default();
+    let mut proposal_to_threshold = Vec::new();
+    let mut threshold_to_execution = Vec::new();
+    let mut weeks: BTreeMap<String, u64> = BTreeMap::new();
+    let mut created_at = HashMap::new();
+    let mut proposers = HashMap::new();
+
+    for tx in &input.transactions {
+        match tx.status {
+            Status::Proposed => status_counts.proposed += 1,
+            Status::Approved => status_counts.approved += 1,
+            Status::Executed => status_counts.executed += 1,
+            Status::Rejected => status_counts.rejected += 1,
+            Status::Removed => status_counts.removed += 1,
+        }
+
+        let threshold_at = input.threshold_reached_at.get(&tx.transaction_digest);
+        if let Some(&threshold_at) = threshold_at {
+            proposal_to_threshold.push(threshold_at.saturating_sub(tx.created_at).max(0) as u64);
+        }
+        if let (Some(&threshold_at), Some(&executed_at)) =
+            (threshold_at, input.executed_at.get(&tx.transaction_digest))
+        {
+            threshold_to_execution.push(executed_at.saturating_sub(threshold_at).max(0) as u64);
+        }
+
+        *weeks.entry(iso_week(tx.created_at)).or_default() += 1;
+        created_at.insert(tx.transaction_digest.as_str(), tx.created_at);
+        proposers.insert(tx.transaction_digest.as_str(), tx.proposer_address.as_str());
+    }
+
+    let total_transactions = input.transactions.len() as u64;
+    let members = input
+        .members
+        .iter()
+        .map(|&(member_address, weight)| {
+            let member_str = member_address.to_string();
+            let proposed = input
+                .transactions
+                .iter()
+                .filter(|tx| tx.proposer_address == member_str)
+                .count() as u64;
+
+            let mut approved = 0;
+            let mut latencies = Vec::new();
+            for (digest, approver, approved_at) in &input.approvals {
+                if *approver != member_address {
+                    continue;
+                }
+                approved += 1;
+                if proposers.get(digest.as_str()) == Some(&member_str.as_str()) {
+                    continue;
+                }
+                if let Some(&created_at) = created_at.get(digest.as_str()) {
+                    latencies.push(approved_at.saturating_sub(created_at).max(0) as u64);
+                }
+            }
+
+            MemberParticipation {
+                member_address,
+                weight,
+                proposed,
+                approved,
+                participation_rate: if total_transactions == 0 {
+                    0.0
+                } else {
+                    approved as f64 / total_transactions as f64
+                },
+                average_approval_latency_ms: (!latencies.is_empty())
+                    .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+            }
+        })
+        .collect();
+
+    AccountStats {
+        account_address,
+        total_transactions,
+        status_counts,
+        proposal_to_threshold: DurationStats::from_samples(proposal_to_threshold),
+        threshold_to_execution: DurationStats::from_samples(threshold_to_execution),
+        members,
+        proposals_per_week: weeks
+            .into_iter()
+            .map(|(week, proposals)| WeeklyProposals { week, proposals })
+            .collect(),
+    }
+}
+
+/// Nearest-rank percentile of sorted samples.
+fn percentile(sorted: &[u64], percentile: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+fn iso_week(timestamp_ms: i64) -> String {
+    let week = DateTime::<Utc>::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+#[cfg(test)]
+mod tests {
+    use fastcrypto::encoding::{Base64, Encoding};
+
+    use super::*;
+    use crate::{
+        db::pool::{DbConnectionPool, DbConnectionPoolConfig},
+        events::{TransactionApprovalThresholdReachedEvent, TransactionExecutedEvent},
+        retention::archive_events,
+    };
+
+    #[test]
+    fn test_account_stats_after_prune() {
+        let dir = std::env::temp_dir().join(format!("isafe-stats-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+
+        let account = IotaAddress::random_for_testing_only();
+        let digest = TransactionDigest::random();
+        let mut conn = pool.get_connection().unwrap();
+        queries::insert_new_account_entry(&mut conn, account, 1, String::new(), 0).unwrap();
+        queries::insert_transaction_entry(
+            &mut conn,
+            digest.to_string(),
+            &account,
+            &account,
+            Status::Executed.into(),
+            1_000,
+        )
+        .unwrap();
+        let threshold_reached = TransactionApprovalThresholdReachedEvent {
+            account_id: account,
+            transaction_digest: digest.into_inner().to_vec(),
+            total_approved_weight: 1,
+            threshold: 1,
+        };
+        let executed = TransactionExecutedEvent {
+            account_id: account,
+            transaction_digest: digest.into_inner().to_vec(),
+            total_member_weight: 1,
+            approvers: vec![account],
+            approver_weights: vec![1],
+            threshold: 1,
+        };
+        for (event_type, timestamp, content) in [
+            (
+                "TransactionApprovalThresholdReachedEvent",
+                3_000,
+                bcs::to_bytes(&threshold_reached).unwrap(),
+            ),
+            (
+                "TransactionExecutedEvent",
+                10_000,
+                bcs::to_bytes(&executed).unwrap(),
+            ),
+        ] {
+            queries::insert_event_entry(
+                &mut conn,
+                account.to_string(),
+                TransactionDigest::random().to_string(),
+                1,
+                event_type.to_string(),
+                timestamp,
+                Base64::encode(content),
+            )
+            .unwrap();
+        }
+
+        let before = account_stats(&mut conn, &dir, &account).unwrap().unwrap();
+        assert_eq!(before.proposal_to_threshold.median_ms, Some(2_000));
+        assert_eq!(before.threshold_to_execution.median_ms, Some(7_000));
+        drop(conn);
+
+        assert_eq!(archive_events(&pool, &dir, 20_000).unwrap(), 2);
+        let mut conn = pool.get_connection().unwrap();
+        assert!(
+            queries::get_events_for_account(&mut conn, &account)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            account_stats(&mut conn, &dir, &account).unwrap().unwrap(),
+            before
+        );
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let account = IotaAddress::random_for_testing_only();
+        let alice = IotaAddress::random_for_testing_only();
+        let bob = IotaAddress::random_for_testing_only();
+        // Monday, 2025-12-08 00:00:00 UTC
+        let monday = 1_765_152_000_000;
+        let tx = |digest: &str, proposer: IotaAddress, status: Status, created_at: i64| {
+            StoredTransaction {
+                transaction_digest: digest.to_string(),
+                account_address: account.to_string(),
+                proposer_address: proposer.to_string(),
+                status,
+                created_at,
+            }
+        };
+
+        let stats = compute_stats(
+            account,
+            StatsInput {
+                members: vec![(alice, 1), (bob, 1)],
+                transactions: vec![
+                    tx("a", alice, Status::Executed, monday),
+                    tx("b", alice, Status::Approved, monday + 1_000),
+                    tx("c", bob, Status::Proposed, monday + 7 * 86_400_000),
+                ],
+                approvals: vec![
+                    ("a".to_string(), alice, monday),
+                    ("a".to_string(), bob, monday + 4_000),
+                    ("b".to_string(), alice, monday + 1_000),
+                    ("b".to_string(), bob, monday + 3_000),
+                    ("c".to_string(), bob, monday + 7 * 86_400_000),
+                ],
+                threshold_reached_at: HashMap::from([
+                    ("a".to_string(), monday + 4_000),
+                    ("b".to_string(), monday + 3_000),
+                ]),
+                executed_at: HashMap::from([("a".to_string(), monday + 10_000)]),
+            },
+        );
+
+        assert_eq!(
+            stats.status_counts,
+            StatusCounts {
+                proposed: 1,
+                approved: 1,
+                executed: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            stats.proposal_to_threshold,
+            DurationStats {
+                samples: 2,
+                median_ms: Some(2_000),
+                p90_ms: Some(4_000),
+            }
+        );
+        assert_eq!(stats.threshold_to_execution.median_ms, Some(6_000));
+
+        let bob_stats = stats
+            .members
+            .iter()
+            .find(|m| m.member_address == bob)
+            .unwrap();
+        assert_eq!(bob_stats.approved, 3);
+        assert_eq!(bob_stats.proposed, 1);
+        assert_eq!(bob_stats.participation_rate, 1.0);
+        assert_eq!(bob_stats.average_approval_latency_ms, Some(3_000));
+
+        let alice_stats = stats
+            .members
+            .iter()
+            .find(|m| m.member_address == alice)
+            .unwrap();
+        assert_eq!(alice_stats.average_approval_latency_ms, None);
+
+        assert_eq!(
+            stats.proposals_per_week,
+            vec![
+                WeeklyProposals {
+                    week: "2025-W50".to_string(),
+                    proposals: 2,
+                },
+                WeeklyProposals {
+                    week: "2025-W51".to_string(),
+                    proposals: 1,
+                },
+            ]
+        );
+    }
+}
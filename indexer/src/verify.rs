@@ -0,0 +1,512 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Consistency verification between the indexed state and the on-chain state of iSafe accounts.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use anyhow::{Result, anyhow};
+use diesel::SqliteConnection;
+use fastcrypto::encoding::{Encoding, Hex};
+use iota_types::{
+    TypeTag,
+    base_types::{IotaAddress, ObjectID},
+    digests::TransactionDigest,
+    dynamic_field::DynamicFieldName,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::IsafeIndexerConfig, db::queries, events::Member, node::Node};
+
+// On-chain layouts of the `dynamic_auth` data, used to decode the dynamic fields' BCS.
+
+/// `iota::dynamic_field::Field<Name, Value>`
+#[derive(Debug, Serialize, Deserialize)]
+struct Field<N, V> {
+    id: ObjectID,
+    name: N,
+    value: V,
+}
+
+/// Any of the field-less `dynamic_auth` keys, e.g. `MembersKey`.
+#[derive(Debug, Serialize, Deserialize)]
+struct EmptyKey {
+    dummy_field: bool,
+}
+
+/// `isafe::members::Members`
+#[derive(Debug, Serialize, Deserialize)]
+struct Members {
+    list: Vec<Member>,
+}
+
+/// `isafe::transactions::Transactions`
+#[derive(Debug, Serialize, Deserialize)]
+struct Transactions {
+    table: Table,
+}
+
+/// `iota::table::Table`
+#[derive(Debug, Serialize, Deserialize)]
+struct Table {
+    id: ObjectID,
+    size: u64,
+}
+
+/// `isafe::transactions::Transaction`
+#[derive(Debug, Serialize, Deserialize)]
+struct Transaction {
+    digest: Vec<u8>,
+    approves: Vec<IotaAddress>,
+}
+
+/// State of an account as stored in the `dynamic_auth` dynamic fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OnChainAccountState {
+    pub members: BTreeMap<IotaAddress, u64>,
+    pub threshold: u64,
+    pub guardian: Option<Vec<u8>>,
+    pub transactions: BTreeMap<String, BTreeSet<IotaAddress>>,
+}
+
+/// State of an account as stored in the indexer database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IndexedAccountState {
+    pub members: BTreeMap<IotaAddress, u64>,
+    pub threshold: u64,
+    pub transactions: BTreeMap<String, BTreeSet<IotaAddress>>,
+}
+
+/// Reads the `dynamic_auth` state of an account from chain.
+///
+/// Returns `None` if the account has no `MembersKey` field, i.e. it is not an iSafe account.
+pub(crate) async fn read_on_chain_state(
+    node: &dyn Node,
+    config: &IsafeIndexerConfig,
+    account: &IotaAddress,
+) -> Result<Option<OnChainAccountState>> {
+    let parent = ObjectID::from(*account);
+
+    let Some(members) = node
+        .dynamic_field_bcs(parent, key_name(config, "MembersKey")?)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let members: Field<EmptyKey, Members> = bcs::from_bytes(&members)?;
+
+    let threshold = node
+        .dynamic_field_bcs(parent, key_name(config, "ThresholdKey")?)
+        .await?
+        .ok_or_else(|| anyhow!("account {account} has members but no threshold"))?;
+    let threshold: Field<EmptyKey, u64> = bcs::from_bytes(&threshold)?;
+
+    let guardian = node
+        .dynamic_field_bcs(parent, key_name(config, "GuardianKey")?)
+        .await?
+        .map(|bytes| bcs::from_bytes::<Field<EmptyKey, Vec<u8>>>(&bytes))
+        .transpose()?
+        .map(|field| field.value);
+
+    let table = node
+        .dynamic_field_bcs(parent, key_name(config, "TransactionsKey")?)
+        .await?
+        .ok_or_else(|| anyhow!("account {account} has members but no transactions"))?;
+    let table: Field<EmptyKey, Transactions> = bcs::from_bytes(&table)?;
+
+    let mut transactions = BTreeMap::new();
+    for id in node.dynamic_field_ids(table.value.table.id).await? {
+        let Some(bytes) = node.object_bcs(id).await? else {
+            continue;
+        };
+        let entry: Field<Vec<u8>, Transaction> = bcs::from_bytes(&bytes)?;
+        let digest: [u8; 32] = entry
+            .value
+            .digest
+            .try_into()
+            .map_err(|_| anyhow!("invalid transaction digest length in account {account}"))?;
+        transactions.insert(
+            TransactionDigest::from(digest).to_string(),
+            entry.value.approves.into_iter().collect(),
+        );
+    }
+
+    Ok(Some(OnChainAccountState {
+        members: members
+            .value
+            .list
+            .into_iter()
+            .map(|m| (m.member_address, m.weight))
+            .collect(),
+        threshold: threshold.value,
+        guardian,
+        transactions,
+    }))
+}
+
+fn key_name(config: &IsafeIndexerConfig, key: &str) -> Result<DynamicFieldName> {
+    Ok(DynamicFieldName {
+        type_: TypeTag::from_str(&format!("{}::dynamic_auth::{key}", config.package_address))?,
+        value: serde_json::json!({ "dummy_field": false }),
+    })
+}
+
+/// Reads the state of an account from the indexer database.
+///
+/// Removed transactions are left out, as removing a proposal deletes it on-chain.
+pub(crate) fn read_indexed_state(
+    conn: &mut SqliteConnection,
+    account: &IotaAddress,
+) -> Result<Option<IndexedAccountState>> {
+    let Some(entry) = queries::get_account_entry(conn, account)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(IndexedAccountState {
+        members: queries::get_members_for_account(conn, account)?
+            .into_iter()
+            .collect(),
+        threshold: entry.threshold as u64,
+        transactions: queries::get_transaction_approvers_for_account(conn, account)?
+            .into_iter()
+            .map(|(digest, approvers)| (digest, approvers.into_iter().collect()))
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueDrift<T> {
+    pub indexed: T,
+    pub on_chain: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberWeightDrift {
+    pub member_address: IotaAddress,
+    pub indexed: u64,
+    pub on_chain: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalDrift {
+    pub transaction_digest: String,
+    pub missing_in_index: Vec<IotaAddress>,
+    pub missing_on_chain: Vec<IotaAddress>,
+}
+
+/// The differences found between the indexed and the on-chain state of one account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDriftReport {
+    pub account_address: IotaAddress,
+    pub missing_in_index: bool,
+    pub missing_on_chain: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<ValueDrift<u64>>,
+    pub members_missing_in_index: Vec<Member>,
+    pub members_missing_on_chain: Vec<Member>,
+    pub member_weight_mismatches: Vec<MemberWeightDrift>,
+    pub transactions_missing_in_index: Vec<String>,
+    pub transactions_missing_on_chain: Vec<String>,
+    pub approval_mismatches: Vec<ApprovalDrift>,
+    /// Hex encoded guardian found on chain. The indexer doesn't track guardians, so this is
+    /// informational only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_chain_guardian: Option<String>,
+}
+
+impl AccountDriftReport {
+    /// Whether the indexed state matches the on-chain state.
+    pub fn is_consistent(&self) -> bool {
+        !self.missing_in_index
+            && !self.missing_on_chain
+            && self.threshold.is_none()
+            && self.members_missing_in_index.is_empty()
+            && self.members_missing_on_chain.is_empty()
+            && self.member_weight_mismatches.is_empty()
+            && self.transactions_missing_in_index.is_empty()
+            && self.transactions_missing_on_chain.is_empty()
+            && self.approval_mismatches.is_empty()
+    }
+}
+
+/// Computes the drift between the indexed and the on-chain state of an account.
+pub(crate) fn diff_account(
+    account_address: IotaAddress,
+    indexed: Option<IndexedAccountState>,
+    on_chain: Option<OnChainAccountState>,
+) -> AccountDriftReport {
+    let mut report = AccountDriftReport {
+        account_address,
+        ..Default::default()
+    };
+
+    let (indexed, on_chain) = match (indexed, on_chain) {
+        (Some(indexed), Some(on_chain)) => (indexed, on_chain),
+        (indexed, on_chain) => {
+            report.missing_in_index = indexed.is_none();
+            report.missing_on_chain = on_chain.is_none();
+            return report;
+        }
+    };
+
+    report.on_chain_guardian = on_chain.guardian.as_ref().map(Hex::encode);
+
+    if indexed.threshold != on_chain.threshold {
+        report.threshold = Some(ValueDrift {
+            indexed: indexed.threshold,
+            on_chain: on_chain.threshold,
+        });
+    }
+
+    for (member_address, &on_chain_weight) in &on_chain.members {
+        match indexed.members.get(member_address) {
+            None => report.members_missing_in_index.push(Member {
+                member_address: *member_address,
+                weight: on_chain_weight,
+            }),
+            Some(&indexed_weight) if indexed_weight != on_chain_weight => {
+                report.member_weight_mismatches.push(MemberWeightDrift {
+                    member_address: *member_address,
+                    indexed: indexed_weight,
+                    on_chain: on_chain_weight,
+                })
+            }
+            _ => {}
+        }
+    }
+    for (member_address, &weight) in &indexed.members {
+        if !on_chain.members.contains_key(member_address) {
+            report.members_missing_on_chain.push(Member {
+                member_address: *member_address,
+                weight,
+            });
+        }
+    }
+
+    for (digest, on_chain_approvers) in &on_chain.transactions {
+        let Some(indexed_approvers) = indexed.transactions.get(digest) else {
+            report.transactions_missing_in_index.push(digest.clone());
+            continue;
+        };
+        // Approvals of removed members stay on chain but are dropped by the indexer, and only
+        // current members count towards the threshold anyways.
+        let is_member = |addr: &&IotaAddress| on_chain.members.contains_key(addr);
+        let missing_in_index: Vec<_> = on_chain_approvers
+            .difference(indexed_approvers)
+            .filter(is_member)
+            .copied()
+            .collect();
+        let missing_on_chain: Vec<_> = indexed_approvers
+            .difference(on_chain_approvers)
+            .filter(is_member)
+            .copied()
+            .collect();
+        if !missing_in_index.is_empty() || !missing_on_chain.is_empty() {
+            report.approval_mismatches.push(ApprovalDrift {
+                transaction_digest: digest.clone(),
+                missing_in_index,
+                missing_on_chain,
+            });
+        }
+    }
+    for digest in indexed.transactions.keys() {
+        if !on_chain.transactions.contains_key(digest) {
+            report.transactions_missing_on_chain.push(digest.clone());
+        }
+    }
+
+    report
+}
+
+/// Verifies a single account against the chain.
+pub(crate) async fn verify_account(
+    conn: &mut SqliteConnection,
+    node: &dyn Node,
+    config: &IsafeIndexerConfig,
+    account: &IotaAddress,
+) -> Result<AccountDriftReport> {
+    let indexed = read_indexed_state(conn, account)?;
+    let on_chain = read_on_chain_state(node, config, account).await?;
+    Ok(diff_account(*account, indexed, on_chain))
+}
+
+/// Verifies all indexed accounts against the chain.
+pub(crate) async fn verify_all_accounts(
+    conn: &mut SqliteConnection,
+    node: &dyn Node,
+    config: &IsafeIndexerConfig,
+) -> Result<Vec<AccountDriftReport>> {
+    let mut reports = Vec::new();
+    for account in queries::get_all_accounts(conn)? {
+        reports.push(verify_account(conn, node, config, &account).await?);
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            models::Status,
+            pool::{DbConnectionPool, DbConnectionPoolConfig},
+        },
+        node::tests::MockNode,
+    };
+
+    fn add_key<V: Serialize>(
+        node: &mut MockNode,
+        config: &IsafeIndexerConfig,
+        account: IotaAddress,
+        key: &str,
+        value: V,
+    ) {
+        let field = Field {
+            id: ObjectID::random(),
+            name: EmptyKey { dummy_field: false },
+            value,
+        };
+        let name = key_name(config, key).unwrap();
+        node.fields.insert(
+            (account.into(), name.type_.to_string()),
+            bcs::to_bytes(&field).unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_account_reports_drift() {
+        let config = IsafeIndexerConfig::new(IotaAddress::random_for_testing_only());
+        let account = IotaAddress::random_for_testing_only();
+        let alice = IotaAddress::random_for_testing_only();
+        let bob = IotaAddress::random_for_testing_only();
+        let carol = IotaAddress::random_for_testing_only();
+        let digest = TransactionDigest::random();
+
+        // On chain: alice(1), bob(2), threshold 2, one transaction approved by alice and bob.
+        let mut node = MockNode::default();
+        let table_id = ObjectID::random();
+        let entry_id = ObjectID::random();
+        add_key(
+            &mut node,
+            &config,
+            account,
+            "MembersKey",
+            Members {
+                list: vec![
+                    Member {
+                        member_address: alice,
+                        weight: 1,
+                    },
+                    Member {
+                        member_address: bob,
+                        weight: 2,
+                    },
+                ],
+            },
+        );
+        add_key(&mut node, &config, account, "ThresholdKey", 2u64);
+        add_key(
+            &mut node,
+            &config,
+            account,
+            "TransactionsKey",
+            Transactions {
+                table: Table {
+                    id: table_id,
+                    size: 1,
+                },
+            },
+        );
+        node.children.insert(table_id, vec![entry_id]);
+        node.objects.insert(
+            entry_id,
+            bcs::to_bytes(&Field {
+                id: entry_id,
+                name: digest.into_inner().to_vec(),
+                value: Transaction {
+                    digest: digest.into_inner().to_vec(),
+                    approves: vec![alice, bob],
+                },
+            })
+            .unwrap(),
+        );
+
+        // Indexed: alice(1), bob(1), carol(1), threshold 3, the transaction only approved by alice.
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        let mut conn = pool.get_connection().unwrap();
+        queries::insert_new_account_entry(&mut conn, account, 3, String::new(), 0).unwrap();
+        for member in [alice, bob, carol] {
+            queries::insert_member_entry(&mut conn, account, member, 1, 0).unwrap();
+        }
+        queries::insert_transaction_entry(
+            &mut conn,
+            digest.to_string(),
+            &account,
+            &alice,
+            Status::Proposed.into(),
+            0,
+        )
+        .unwrap();
+        queries::insert_approval_entry(&mut conn, digest.to_string(), &account, &alice, 1, 0)
+            .unwrap();
+        // a removed proposal is no longer on-chain
+        queries::insert_transaction_entry(
+            &mut conn,
+            TransactionDigest::random().to_string(),
+            &account,
+            &bob,
+            Status::Removed.into(),
+            0,
+        )
+        .unwrap();
+
+        let report = verify_account(&mut conn, &node, &config, &account)
+            .await
+            .unwrap();
+
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.threshold,
+            Some(ValueDrift {
+                indexed: 3,
+                on_chain: 2
+            })
+        );
+        assert_eq!(
+            report.member_weight_mismatches,
+            vec![MemberWeightDrift {
+                member_address: bob,
+                indexed: 1,
+                on_chain: 2
+            }]
+        );
+        assert_eq!(report.members_missing_on_chain.len(), 1);
+        assert_eq!(report.members_missing_on_chain[0].member_address, carol);
+        assert!(report.members_missing_in_index.is_empty());
+        assert!(report.transactions_missing_in_index.is_empty());
+        assert!(report.transactions_missing_on_chain.is_empty());
+        assert_eq!(
+            report.approval_mismatches,
+            vec![ApprovalDrift {
+                transaction_digest: digest.to_string(),
+                missing_in_index: vec![bob],
+                missing_on_chain: vec![],
+            }]
+        );
+    }
+}
@@ -1,6 +1,13 @@
 use fastcrypto::encoding::{Base64, Encoding};
-use futures::FutureExt;
-use std::{ panic::AssertUnwindSafe, path::PathBuf, sync::Arc};
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use iota_data_ingestion_core::{
     DataIngestionMetrics, FileProgressStore, IndexerExecutor, ReaderOptions, Worker, WorkerPool,
@@ -16,28 +23,119 @@ use iota_types::{
     full_checkpoint_content::{CheckpointData},
 };
 
-use diesel::Connection;
+use chrono::Utc;
+use diesel::{Connection, SqliteConnection};
 
 use crate::db::models::Status;
-use crate::db::{pool::DbConnectionPool, queries};
-use crate::{config::IsafeIndexerConfig, events::IsafeEvent};
+use crate::db::{pool::{DbConnectionPool, parse_duration}, queries};
+use crate::{
+    config::IsafeIndexerConfig,
+    events::IsafeEvent,
+    ingestion::{ErrorPolicy, IngestionHealth, is_transient},
+};
 use anyhow::{Result, anyhow, bail};
 use async_trait::async_trait;
+use clap::{Args, ValueEnum};
 use prometheus::Registry;
+use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
+
+/// The name of the reader task in the progress store.
+const TASK_NAME: &str = "isafe_reader";
+/// The extension of checkpoint files in the checkpoint directory.
+const CHECKPOINT_FILE_EXTENSION: &str = "chk";
+
+/// Where the checkpoints are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CheckpointSource {
+    /// The REST API of the node at `checkpoint-url`, e.g. of a localnet.
+    Rest,
+    /// The historical and live checkpoint stores at `checkpoint-url`.
+    Remote,
+    /// The checkpoint files in `checkpoint-dir`, fully offline. The files are kept, so recorded
+    /// checkpoints can be replayed.
+    Local,
+}
+
+/// Where checkpoints are read from and where the reader keeps its state.
+#[derive(Args, Debug, Clone)]
+pub struct CheckpointConfig {
+    #[arg(long, value_enum, default_value = "rest")]
+    pub checkpoint_source: CheckpointSource,
+    /// The URL of an IOTA node with REST API enabled or a historical store.
+    #[arg(long, default_value = "http://localhost:9000")]
+    pub checkpoint_url: String,
+    /// The file the reader keeps its progress in.
+    #[arg(long, default_value = "./data/progress_store")]
+    pub progress_store_path: PathBuf,
+    /// The directory checkpoints are downloaded to, or read from by the local source.
+    #[arg(long, default_value = "./data/chk")]
+    pub checkpoint_dir: PathBuf,
+    /// The timeout of checkpoint requests, in seconds.
+    #[arg(long, value_parser = parse_duration, default_value = "60")]
+    pub reader_timeout_secs: Duration,
+    /// Process from this checkpoint on if no progress is stored yet.
+    #[arg(long)]
+    pub start_checkpoint: Option<u64>,
+    /// Replace the stored progress with `start-checkpoint`.
+    #[arg(long, requires = "start_checkpoint")]
+    pub force_start_checkpoint: bool,
+    /// Exit once all checkpoints up to and including this one are processed.
+    #[arg(long)]
+    pub stop_checkpoint: Option<u64>,
+}
 
 pub(crate) async fn run_isafe_reader(
-    worker: IsafeWorker,
+    mut worker: IsafeWorker,
     node_url: &str,
-    checkpoint_url: &str,
+    checkpoint_config: &CheckpointConfig,
     registry: &Registry,
     concurrency: usize,
 ) -> anyhow::Result<()> {
-    let progress_store_path = "./data/progress_store";
-    initialize_progress_store(&worker, node_url, progress_store_path).await?;
+    let progress_store_path = &checkpoint_config.progress_store_path;
+    let stored = read_progress(progress_store_path)?;
+    let start = match configured_start(checkpoint_config, stored) {
+        Some(start) => {
+            if stored != Some(start) {
+                write_progress(progress_store_path, start)?;
+            }
+            start
+        }
+        None => {
+            info!("Progress store file not found, creating with initial checkpoint");
+            let initial = match checkpoint_config.checkpoint_source {
+                CheckpointSource::Local => {
+                    first_local_checkpoint(&checkpoint_config.checkpoint_dir)?
+                }
+                CheckpointSource::Rest | CheckpointSource::Remote => {
+                    initial_checkpoint(&worker, node_url).await?
+                }
+            };
+            write_progress(progress_store_path, initial)?;
+            initial
+        }
+    };
+    worker.start_at(start);
+    // the checkpoints before the start were processed by earlier runs, so the readiness doesn't
+    // wait for the first checkpoint of this run
+    let resumed = match stored {
+        Some(_) => start.checked_sub(1),
+        None => queries::get_latest_event_checkpoint(&mut worker.pool.get_connection()?)?,
+    };
+    if let Some(last) = resumed {
+        worker.health.resumed(last);
+    }
+    if let Some(stop) = checkpoint_config.stop_checkpoint {
+        if start > stop {
+            info!("Already processed all checkpoints up to the stop checkpoint {stop}");
+            return Ok(());
+        }
+        info!("Processing checkpoints {start} to {stop}");
+        worker.stop_after(start, stop);
+    }
 
-    let progress_store = FileProgressStore::new(progress_store_path).await?;
+    let progress_store = FileProgressStore::new(progress_store_path.clone()).await?;
 
     let mut executor = IndexerExecutor::new(
         progress_store,
@@ -47,39 +145,53 @@ pub(crate) async fn run_isafe_reader(
     );
     let worker_pool = WorkerPool::new(
         worker,
-        "isafe_reader".to_string(),
+        TASK_NAME.to_string(),
         concurrency,
         Default::default(),
     );
     executor.register(worker_pool).await?;
 
-    info!("Connecting to {checkpoint_url} to sync checkpoints");
+    let checkpoint_url = &checkpoint_config.checkpoint_url;
+    let checkpoint_dir = checkpoint_config.checkpoint_dir.clone();
     let reader_options = ReaderOptions {
-        timeout_secs: 60,
+        timeout_secs: checkpoint_config.reader_timeout_secs.as_secs(),
+        upper_limit: checkpoint_config.stop_checkpoint,
+        // recorded checkpoints are replayed, not consumed
+        gc_checkpoint_files: checkpoint_config.checkpoint_source != CheckpointSource::Local,
         ..Default::default()
     };
-    // Localnet does not support remote store, so we use the REST API
-    if checkpoint_url.contains("localhost") || checkpoint_url.contains("host.docker.internal") {
-        executor
-            .run(
-                // path to a local directory where checkpoints are stored.
-                PathBuf::from("./data/chk"),
-                Some(format!("{checkpoint_url}/api/v1")),
-                // optional remote store access options.
-                vec![],
+    match checkpoint_config.checkpoint_source {
+        CheckpointSource::Rest => {
+            info!("Connecting to the REST API of {checkpoint_url} to sync checkpoints");
+            executor
+                .run(
+                    // path to a local directory where checkpoints are stored.
+                    checkpoint_dir,
+                    Some(format!("{checkpoint_url}/api/v1")),
+                    // optional remote store access options.
+                    vec![],
+                    reader_options,
+                )
+                .await?;
+        }
+        CheckpointSource::Remote => {
+            info!("Connecting to the checkpoint stores at {checkpoint_url} to sync checkpoints");
+            let config = CheckpointReaderConfig {
+                remote_store_url: Some(RemoteUrl::HybridHistoricalStore {
+                    historical_url: format!("{checkpoint_url}/ingestion/historical"),
+                    live_url: Some(format!("{checkpoint_url}/ingestion/live")),
+                }),
+                ingestion_path: Some(checkpoint_dir),
                 reader_options,
-            )
-            .await?;
-    } else {
-        let config = CheckpointReaderConfig {
-            remote_store_url: Some(RemoteUrl::HybridHistoricalStore {
-                historical_url: format!("{checkpoint_url}/ingestion/historical"),
-                live_url: Some(format!("{checkpoint_url}/ingestion/live")),
-            }),
-            ingestion_path: Some(PathBuf::from("./data/chk")),
-            reader_options,
-        };
-        executor.run_with_config(config).await?;
+            };
+            executor.run_with_config(config).await?;
+        }
+        CheckpointSource::Local => {
+            info!("Reading checkpoints from {}", checkpoint_dir.display());
+            executor
+                .run(checkpoint_dir, None, vec![], reader_options)
+                .await?;
+        }
     }
     Ok(())
 }
@@ -88,6 +200,13 @@ pub(crate) struct IsafeWorker {
     pool: DbConnectionPool,
     config: IsafeIndexerConfig,
     token: CancellationToken,
+    policy: ErrorPolicy,
+    health: IngestionHealth,
+    sequencer: CommitSequencer,
+    /// Checkpoints after this one are skipped.
+    stop_checkpoint: Option<u64>,
+    /// The number of checkpoints left until the stop checkpoint.
+    remaining: AtomicU64,
 }
 
 impl IsafeWorker {
@@ -95,25 +214,57 @@ impl IsafeWorker {
         pool: DbConnectionPool,
         config: IsafeIndexerConfig,
         token: CancellationToken,
+        policy: ErrorPolicy,
+        health: IngestionHealth,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             pool,
             config,
             token,
+            policy,
+            health,
+            sequencer: CommitSequencer::new(0),
+            stop_checkpoint: None,
+            remaining: AtomicU64::new(0),
         })
     }
 
-    async fn process_checkpoint(&self, checkpoint: &CheckpointData) -> anyhow::Result<()> {
-        debug!(
-            "Processing checkpoint: {}",
-            checkpoint.checkpoint_summary.sequence_number
-        );
-        // events shall have a unique timestamp to signal ordering (per account), therfore we use a mutable timestamp here
-        // which is always incremented by 1 ms after processing each event
-        // note: checkpoint interval is 200-300ms, so we should be safe for ~200 events per account per checkpoint
-        // even if this number is exceeded,
-        let mut event_timestamp = checkpoint.checkpoint_summary.timestamp_ms;
+    /// Sets the first checkpoint to commit.
+    fn start_at(&mut self, start: u64) {
+        self.sequencer = CommitSequencer::new(start);
+    }
+
+    /// Makes the worker cancel its token once all checkpoints from `start` to `stop` are
+    /// processed.
+    fn stop_after(&mut self, start: u64, stop: u64) {
+        self.stop_checkpoint = Some(stop);
+        self.remaining = AtomicU64::new(stop - start + 1);
+    }
+
+    /// Records a checkpoint that kept failing, so the reader can move on.
+    fn quarantine(
+        &self,
+        checkpoint: u64,
+        err: &anyhow::Error,
+        attempts: u32,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.pool.get_connection()?;
+        queries::insert_quarantined_checkpoint(
+            &mut conn,
+            checkpoint,
+            &format!("{err:#}"),
+            attempts,
+            Utc::now().timestamp_millis() as u64,
+        )?;
+        self.health.quarantined(checkpoint);
+        error!("Quarantined checkpoint {checkpoint} after {attempts} failed attempts, skipping it");
+        Ok(())
+    }
 
+    /// Decodes the iSafe events of the successful transactions of a checkpoint, together with
+    /// the digest of the transaction that emitted them.
+    fn decode_checkpoint(&self, checkpoint: &CheckpointData) -> Vec<(IsafeEvent, String)> {
+        let mut decoded = Vec::new();
         for transaction in &checkpoint.transactions {
             let TransactionEffects::V1(effects) = &transaction.effects;
 
@@ -121,36 +272,123 @@ impl IsafeWorker {
                 continue;
             }
 
-            let timestamp = checkpoint.checkpoint_summary.timestamp_ms;
-
             if let Some(events) = &transaction.events {
                 for event in events.data.iter() {
                     match IsafeEvent::try_from_event(event, &self.config) {
-                        Ok(Some(event)) => self.process_event(
-                            event,
-                            timestamp,
-                            &mut event_timestamp,
-                            &transaction.transaction.digest().to_string(),
-                        )?,
+                        Ok(Some(event)) => {
+                            decoded.push((event, transaction.transaction.digest().to_string()))
+                        }
                         Err(e) => warn!("parsing event failed: {e}"),
                         _ => {}
                     }
                 }
             }
         }
+        decoded
+    }
 
+    /// Waits until all earlier checkpoints are committed, then commits the events of this one,
+    /// retrying according to the error policy.
+    ///
+    /// The turn is only passed on once the checkpoint is committed or quarantined, so later
+    /// checkpoints stay blocked while this one fails or is cancelled.
+    async fn ingest(
+        &self,
+        sequence_number: u64,
+        timestamp: u64,
+        events: &[(IsafeEvent, String)],
+    ) -> anyhow::Result<()> {
+        tokio::select! {
+            _ = self.sequencer.turn(sequence_number) => {}
+            _ = self.token.cancelled() => {
+                bail!("cancelled while waiting to commit checkpoint {sequence_number}")
+            }
+        };
+
+        let mut retries = 0;
+        let mut attempts = 0;
+        loop {
+            let Err(e) = catch_unwind(AssertUnwindSafe(|| {
+                self.commit_checkpoint(sequence_number, timestamp, events)
+            }))
+            .map_err(map_panic)
+            .and_then(|res| res) else {
+                break;
+            };
+            if is_transient(&e) {
+                warn!("Checkpoint {sequence_number} hit a busy database, retrying: {e}");
+            } else {
+                attempts += 1;
+                error!(
+                    "Processing checkpoint {sequence_number} failed (attempt {attempts} of {}): {e}",
+                    self.policy.checkpoint_max_attempts
+                );
+                if attempts >= self.policy.checkpoint_max_attempts {
+                    // a checkpoint is only skipped once its quarantine is recorded
+                    match self.quarantine(sequence_number, &e, attempts) {
+                        Ok(()) => break,
+                        Err(e) => error!(
+                            "Quarantining checkpoint {sequence_number} failed, retrying: {e}"
+                        ),
+                    }
+                }
+            }
+            self.health.retrying(sequence_number, &e);
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.policy.backoff(retries)) => {}
+                _ = self.token.cancelled() => {
+                    bail!("cancelled while retrying checkpoint {sequence_number}")
+                }
+            }
+            retries += 1;
+        }
+
+        self.sequencer.committed(sequence_number);
+        self.health.processed(sequence_number, timestamp);
         Ok(())
     }
 
+    fn commit_checkpoint(
+        &self,
+        checkpoint: u64,
+        timestamp: u64,
+        events: &[(IsafeEvent, String)],
+    ) -> anyhow::Result<()> {
+        debug!("Processing checkpoint: {checkpoint}");
+        // events shall have a unique timestamp to signal ordering (per account), therfore we use a mutable timestamp here
+        // which is always incremented by 1 ms after processing each event
+        // note: checkpoint interval is 200-300ms, so we should be safe for ~200 events per account per checkpoint
+        // even if this number is exceeded,
+        let mut event_timestamp = timestamp;
+
+        // a checkpoint is committed as a whole, so a failed attempt can be retried
+        let mut conn = self.pool.get_connection()?;
+        conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+            for (event, tx_digest) in events {
+                self.process_event(
+                    conn,
+                    event,
+                    checkpoint,
+                    timestamp,
+                    &mut event_timestamp,
+                    tx_digest,
+                )?;
+            }
+            Ok(())
+        })
+    }
+
     fn process_event(
         &self,
-        event: IsafeEvent,
+        conn: &mut SqliteConnection,
+        event: &IsafeEvent,
+        checkpoint: u64,
         timestamp: u64,
         event_timestamp: &mut u64,
         tx_digest_str: &String,
     ) -> anyhow::Result<()> {
-        let mut conn = self.pool.get_connection()?;
-        match &event {
+        match event {
             IsafeEvent::AccountCreated(acct_event) => {
                 info!(
                     "Processing AccountCreated event for account: {}",
@@ -192,6 +430,7 @@ impl IsafeWorker {
                         conn,
                         acct_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&acct_event)?),
@@ -212,6 +451,7 @@ impl IsafeWorker {
                         conn,
                         _acct_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&_acct_event)?),
@@ -233,6 +473,7 @@ impl IsafeWorker {
                         conn,
                         member_added_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&member_added_event)?),
@@ -264,12 +505,14 @@ impl IsafeWorker {
                     queries::recheck_account_transactions_status(
                         conn,
                         &member_removed_event.account_id,
+                        checkpoint,
                         event_timestamp,
                     )?;
                     queries::insert_event_entry(
                         conn,
                         member_removed_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&member_removed_event)?),
@@ -295,12 +538,14 @@ impl IsafeWorker {
                     queries::recheck_account_transactions_status(
                         conn,
                         &member_updated_event.account_id,
+                        checkpoint,
                         event_timestamp,
                     )?;
                     queries::insert_event_entry(
                         conn,
                         member_updated_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&member_updated_event)?),
@@ -326,12 +571,14 @@ impl IsafeWorker {
                     queries::recheck_account_transactions_status(
                         conn,
                         &th_changed_event.account_id,
+                        checkpoint,
                         event_timestamp,
                     )?;
                     queries::insert_event_entry(
                         conn,
                         th_changed_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&th_changed_event)?),
@@ -347,6 +594,7 @@ impl IsafeWorker {
                         conn,
                         guardian_changed_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&guardian_changed_event)?),
@@ -356,13 +604,7 @@ impl IsafeWorker {
                 *event_timestamp += 1;
             }
             IsafeEvent::TransactionProposed(tx_event) => {
-                // on-chain type shall always be 32 bytes
-                let tx_digest_bytes: [u8; 32] = tx_event
-                    .transaction_digest
-                    .clone()
-                    .try_into()
-                    .expect("Invalid transaction digest length");
-                let tx_digest = TransactionDigest::from(tx_digest_bytes);
+                let tx_digest = transaction_digest(&tx_event.transaction_digest)?;
                 info!(
                     "Processing TransactionProposed event for transaction: {:?}",
                     tx_digest
@@ -380,6 +622,7 @@ impl IsafeWorker {
                         conn,
                         tx_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&tx_event)?),
@@ -394,12 +637,7 @@ impl IsafeWorker {
                 );
             }
             IsafeEvent::TransactionApproved(tx_event) => {
-                let tx_digest_bytes: [u8; 32] = tx_event
-                    .transaction_digest
-                    .clone()
-                    .try_into()
-                    .expect("Invalid transaction digest length");
-                let tx_digest = TransactionDigest::from(tx_digest_bytes);
+                let tx_digest = transaction_digest(&tx_event.transaction_digest)?;
                 info!(
                     "Processing TransactionApproved event for transaction: {:?}",
                     tx_digest
@@ -417,6 +655,7 @@ impl IsafeWorker {
                         conn,
                         tx_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&tx_event)?),
@@ -431,12 +670,7 @@ impl IsafeWorker {
                 );
             }
             IsafeEvent::TransactionApprovalThresholdReached(tx_event) => {
-                let tx_digest_bytes: [u8; 32] = tx_event
-                    .transaction_digest
-                    .clone()
-                    .try_into()
-                    .expect("Invalid transaction digest length");
-                let tx_digest = TransactionDigest::from(tx_digest_bytes);
+                let tx_digest = transaction_digest(&tx_event.transaction_digest)?;
                 info!(
                     "Processing TransactionApprovalThresholdReached event for transaction: {:?}",
                     tx_digest
@@ -451,6 +685,7 @@ impl IsafeWorker {
                         conn,
                         tx_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&tx_event)?),
@@ -464,12 +699,7 @@ impl IsafeWorker {
                 );
             }
             IsafeEvent::TransactionExecuted(tx_executed_event) => {
-                let tx_digest_bytes: [u8; 32] = tx_executed_event
-                    .transaction_digest
-                    .clone()
-                    .try_into()
-                    .expect("Invalid transaction digest length");
-                let tx_digest = TransactionDigest::from(tx_digest_bytes);
+                let tx_digest = transaction_digest(&tx_executed_event.transaction_digest)?;
                 info!(
                     "Processing TransactionExecuted event for transaction: {:?}",
                     tx_digest
@@ -484,6 +714,7 @@ impl IsafeWorker {
                         conn,
                         tx_executed_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&tx_executed_event)?),
@@ -498,12 +729,19 @@ impl IsafeWorker {
                 );
             }
             IsafeEvent::TransactionRemoved(tx_removed_event) => {
-                // TODO: remove the transaction from the transactions table?
+                let tx_digest = transaction_digest(&tx_removed_event.transaction_digest)?;
                 conn.transaction::<_, anyhow::Error, _>(|conn| {
+                    // keep the transaction for the history, but mark it as removed
+                    queries::update_transaction_status(
+                        conn,
+                        tx_digest.to_string(),
+                        Status::Removed.into(),
+                    )?;
                     queries::insert_event_entry(
                         conn,
                         tx_removed_event.account_id.to_string(),
                         tx_digest_str.clone(),
+                        checkpoint,
                         event.type_().to_string(),
                         *event_timestamp,
                         Base64::encode(bcs::to_bytes(&tx_removed_event)?),
@@ -534,33 +772,75 @@ impl Worker for IsafeWorker {
         &self,
         checkpoint: Arc<CheckpointData>,
     ) -> Result<Self::Message, Self::Error> {
-        let res = AssertUnwindSafe(self.process_checkpoint(&checkpoint))
-            .catch_unwind()
-            .await
-            .map_err(map_panic);
-        if let Err(e) | Ok(Err(e)) = &res {
-            tracing::error!("{e}");
+        let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+        if self
+            .stop_checkpoint
+            .is_some_and(|stop| sequence_number > stop)
+        {
+            return Ok(());
+        }
+
+        // decoding runs on all workers in parallel, but checkpoints are committed in order, as
+        // the events build on the state the earlier ones left
+        let events = self.decode_checkpoint(&checkpoint);
+        self.ingest(
+            sequence_number,
+            checkpoint.checkpoint_summary.timestamp_ms,
+            &events,
+        )
+        .await?;
+
+        if self.stop_checkpoint.is_some() && self.remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+            info!("Processed all checkpoints up to the stop checkpoint, stopping");
             self.token.cancel();
         }
-        res?
+        Ok(())
     }
 }
 
-async fn initialize_progress_store(
-    worker: &IsafeWorker,
-    node_url: &str,
-    progress_store_path: &str,
-) -> anyhow::Result<()> {
-    if std::path::Path::new(progress_store_path).exists() {
-        return Ok(());
+/// Hands out the turns to commit checkpoints, in sequence order.
+struct CommitSequencer(watch::Sender<u64>);
+
+impl CommitSequencer {
+    /// Starts with the turn of `next`.
+    fn new(next: u64) -> Self {
+        Self(watch::Sender::new(next))
+    }
+
+    /// Waits until all checkpoints before `checkpoint` are committed.
+    async fn turn(&self, checkpoint: u64) {
+        // the sender lives as long as self, so this can't fail
+        let _ = self
+            .0
+            .subscribe()
+            .wait_for(|next| *next >= checkpoint)
+            .await;
     }
 
-    info!("Progress store file not found, creating with initial checkpoint");
+    /// Passes the turn on to the checkpoint after `checkpoint`.
+    fn committed(&self, checkpoint: u64) {
+        self.0.send_if_modified(|next| {
+            let modified = *next <= checkpoint;
+            if modified {
+                *next = checkpoint + 1;
+            }
+            modified
+        });
+    }
+}
+
+/// Parses the digest of a transaction event, which shall always be 32 bytes on-chain.
+fn transaction_digest(digest: &[u8]) -> anyhow::Result<TransactionDigest> {
+    let digest = <[u8; 32]>::try_from(digest)
+        .map_err(|_| anyhow!("invalid transaction digest length {}", digest.len()))?;
+    Ok(TransactionDigest::from(digest))
+}
+
+/// Returns the checkpoint the package was deployed at, or the latest one if that is unknown.
+async fn initial_checkpoint(worker: &IsafeWorker, node_url: &str) -> anyhow::Result<u64> {
     let client = IotaClientBuilder::default().build(node_url).await?;
 
     let package_id = &worker.config.package_address;
-    let mut checkpoint: u64 = 0;
-
     match get_package_deployment_checkpoint(&client, package_id).await {
         Err(e) => {
             // If we can't get the deployment checkpoint, we default to the last known checkpoint
@@ -571,25 +851,76 @@ async fn initialize_progress_store(
             warn!(
                 "Failed to get package deployment checkpoint: {e}, defaulting to current checkpoint of {current_checkpoint}"
             );
-            checkpoint = current_checkpoint;
+            Ok(current_checkpoint)
         }
         Ok(deployed_at) => {
             info!("Package deployed at checkpoint: {deployed_at}");
-            checkpoint = deployed_at;
+            Ok(deployed_at)
         }
     }
+}
+
+/// Returns the checkpoint to start from given the `stored` progress, `None` if there is neither
+/// a stored nor a configured one.
+///
+/// The configured start checkpoint only replaces stored progress when forced, so a restart with
+/// the same arguments resumes where the last run stopped.
+fn configured_start(config: &CheckpointConfig, stored: Option<u64>) -> Option<u64> {
+    match (config.start_checkpoint, stored) {
+        (Some(start), Some(stored)) if !config.force_start_checkpoint && start != stored => {
+            info!(
+                "Resuming from the stored checkpoint {stored} instead of the start checkpoint \
+                 {start}, use --force-start-checkpoint to replace it"
+            );
+            Some(stored)
+        }
+        (Some(start), _) => Some(start),
+        (None, stored) => stored,
+    }
+}
+
+/// Returns the lowest checkpoint of the checkpoint files in `dir`.
+fn first_local_checkpoint(dir: &Path) -> anyhow::Result<u64> {
+    let mut first = None;
+    for entry in std::fs::read_dir(dir)
+        .map_err(|e| anyhow!("failed to read checkpoint directory {}: {e}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != CHECKPOINT_FILE_EXTENSION) {
+            continue;
+        }
+        if let Some(checkpoint) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            first = Some(first.map_or(checkpoint, |first: u64| first.min(checkpoint)));
+        }
+    }
+    first.ok_or_else(|| anyhow!("no checkpoint files in {}", dir.display()))
+}
 
-    // Create the data directory if it doesn't exist
-    std::fs::create_dir_all("./data")?;
+/// Reads the next checkpoint to process from the progress store, if it exists.
+fn read_progress(path: &Path) -> anyhow::Result<Option<u64>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let progress: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(progress.get(TASK_NAME).and_then(|checkpoint| checkpoint.as_u64()))
+}
+
+/// Sets the next checkpoint to process in the progress store.
+fn write_progress(path: &Path, checkpoint: u64) -> anyhow::Result<()> {
+    // Create the directory of the progress store if it doesn't exist
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
 
     let progress_content = serde_json::json!({
-        "isafe_reader": checkpoint
+        TASK_NAME: checkpoint
     });
     info!("Setting progress store checkpoint to: {checkpoint}");
-    std::fs::write(
-        progress_store_path,
-        serde_json::to_string_pretty(&progress_content)?,
-    )?;
+    std::fs::write(path, serde_json::to_string_pretty(&progress_content)?)?;
 
     Ok(())
 }
@@ -644,3 +975,419 @@ async fn get_package_deployment_checkpoint(
         .checkpoint
         .ok_or_else(|| anyhow::anyhow!("Missing checkpoint"))
 }
+
+#[cfg(test)]
+mod tests {
+    use iota_types::base_types::IotaAddress;
+    use tokio::task::JoinSet;
+
+    use super::*;
+    use crate::{
+        db::pool::DbConnectionPoolConfig,
+        events::{
+            AccountCreatedEvent, AuthenticatorFunctionRefV1, Member, MemberRemovedEvent,
+            ThresholdChangedEvent, TransactionApprovalThresholdReachedEvent,
+            TransactionApprovedEvent, TransactionExecutedEvent, TransactionProposedEvent,
+            TransactionRemovedEvent,
+        },
+        ingestion::IngestionState,
+    };
+
+    const FIRST_CHECKPOINT: u64 = 10;
+
+    type Snapshot = (
+        Vec<(IotaAddress, u64)>,
+        Vec<(String, String)>,
+        Vec<(String, String, i64)>,
+        Vec<(String, i64, Option<i64>, String)>,
+    );
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_first_local_checkpoint() {
+        let dir = temp_dir("isafe-local-checkpoints");
+        assert!(first_local_checkpoint(&dir).is_err());
+
+        for name in ["120.chk", "17.chk", "9.tmp", "notes.chk"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        assert_eq!(first_local_checkpoint(&dir).unwrap(), 17);
+    }
+
+    #[test]
+    fn test_progress_roundtrip() {
+        let path = temp_dir("isafe-progress").join("nested").join("progress_store");
+        assert_eq!(read_progress(&path).unwrap(), None);
+
+        write_progress(&path, 42).unwrap();
+        assert_eq!(read_progress(&path).unwrap(), Some(42));
+    }
+
+    fn local_config(dir: &Path) -> CheckpointConfig {
+        CheckpointConfig {
+            checkpoint_source: CheckpointSource::Local,
+            checkpoint_url: String::new(),
+            progress_store_path: dir.join("progress_store"),
+            checkpoint_dir: dir.join("chk"),
+            reader_timeout_secs: Duration::from_secs(5),
+            start_checkpoint: None,
+            stop_checkpoint: None,
+            force_start_checkpoint: false,
+        }
+    }
+
+    #[test]
+    fn test_configured_start() {
+        let mut config = local_config(Path::new("."));
+        assert_eq!(configured_start(&config, None), None);
+        assert_eq!(configured_start(&config, Some(7)), Some(7));
+
+        config.start_checkpoint = Some(3);
+        assert_eq!(configured_start(&config, None), Some(3));
+        // a restart with the same arguments resumes
+        assert_eq!(configured_start(&config, Some(7)), Some(7));
+
+        config.force_start_checkpoint = true;
+        assert_eq!(configured_start(&config, Some(7)), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_local_replay_stops_at_stop_checkpoint() {
+        use iota_storage::blob::{Blob, BlobEncoding};
+        use iota_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+        let dir = temp_dir("isafe-local-replay");
+        let mut config = local_config(&dir);
+        std::fs::create_dir_all(&config.checkpoint_dir).unwrap();
+        for checkpoint in FIRST_CHECKPOINT..FIRST_CHECKPOINT + 5 {
+            let data = TestCheckpointDataBuilder::new(checkpoint).build_checkpoint();
+            std::fs::write(
+                config
+                    .checkpoint_dir
+                    .join(format!("{checkpoint}.{CHECKPOINT_FILE_EXTENSION}")),
+                Blob::encode(&data, BlobEncoding::Bcs).unwrap().to_bytes(),
+            )
+            .unwrap();
+        }
+        config.stop_checkpoint = Some(FIRST_CHECKPOINT + 2);
+
+        let (worker, _pool) = test_worker("isafe-local-replay-db");
+        let health = worker.health.clone();
+        let token = worker.token.clone();
+        tokio::time::timeout(
+            Duration::from_secs(30),
+            run_isafe_reader(worker, "", &config, &Registry::new(), 1),
+        )
+        .await
+        .expect("the reader stops after the stop checkpoint")
+        .unwrap();
+
+        assert!(token.is_cancelled());
+        assert_eq!(health.status().last_checkpoint, Some(FIRST_CHECKPOINT + 2));
+        // the checkpoint files of a replay are kept
+        assert_eq!(first_local_checkpoint(&config.checkpoint_dir).unwrap(), FIRST_CHECKPOINT);
+    }
+
+    #[test]
+    fn test_transaction_digest() {
+        let digest = TransactionDigest::random();
+        assert_eq!(transaction_digest(&digest.into_inner()).unwrap(), digest);
+        assert!(transaction_digest(&[0; 31]).is_err());
+    }
+
+    fn test_worker(name: &str) -> (IsafeWorker, DbConnectionPool) {
+        let path = temp_dir(name).join("isafe.db");
+        let pool = DbConnectionPool::new_with_path(
+            path.to_str().unwrap(),
+            DbConnectionPoolConfig {
+                pool_size: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        let mut worker = IsafeWorker::new(
+            pool.clone(),
+            IsafeIndexerConfig::new(IotaAddress::random_for_testing_only()),
+            CancellationToken::new(),
+            ErrorPolicy::default(),
+            IngestionHealth::default(),
+        )
+        .unwrap();
+        worker.start_at(FIRST_CHECKPOINT);
+        (worker, pool)
+    }
+
+    fn checkpoint_timestamp(checkpoint: u64) -> u64 {
+        1_700_000_000_000 + checkpoint * 1_000
+    }
+
+    /// An account of three members with weight 1 and threshold 2: two proposals, a member
+    /// removal and a threshold change that flip the approval status, an execution and a removal.
+    fn account_history(
+        account: IotaAddress,
+        [alice, bob, carol]: [IotaAddress; 3],
+        [d1, d2]: [TransactionDigest; 2],
+    ) -> Vec<(u64, Vec<(IsafeEvent, String)>)> {
+        let member = |member_address| Member {
+            member_address,
+            weight: 1,
+        };
+        let proposed = |digest: TransactionDigest, proposer| {
+            IsafeEvent::TransactionProposed(TransactionProposedEvent {
+                account_id: account,
+                transaction_digest: digest.into_inner().to_vec(),
+                proposer,
+            })
+        };
+        let approved = |digest: TransactionDigest, approver, total_approved_weight| {
+            IsafeEvent::TransactionApproved(TransactionApprovedEvent {
+                account_id: account,
+                transaction_digest: digest.into_inner().to_vec(),
+                approver,
+                approver_weight: 1,
+                total_approved_weight,
+            })
+        };
+        let checkpoints = vec![
+            vec![IsafeEvent::AccountCreated(AccountCreatedEvent {
+                account_id: account,
+                members: vec![member(alice), member(bob), member(carol)],
+                threshold: 2,
+                guardian: vec![],
+                authenticator: AuthenticatorFunctionRefV1 {
+                    package: IotaAddress::ZERO,
+                    module_name: "isafe".to_string(),
+                    function_name: "authenticate".to_string(),
+                },
+            })],
+            vec![proposed(d1, alice), approved(d1, alice, 1)],
+            vec![
+                approved(d1, bob, 2),
+                IsafeEvent::TransactionApprovalThresholdReached(
+                    TransactionApprovalThresholdReachedEvent {
+                        account_id: account,
+                        transaction_digest: d1.into_inner().to_vec(),
+                        total_approved_weight: 2,
+                        threshold: 2,
+                    },
+                ),
+            ],
+            vec![proposed(d2, bob), approved(d2, bob, 1)],
+            vec![IsafeEvent::MemberRemoved(MemberRemovedEvent {
+                account_id: account,
+                member: member(bob),
+            })],
+            vec![IsafeEvent::ThresholdChanged(ThresholdChangedEvent {
+                account_id: account,
+                old_threshold: 2,
+                new_threshold: 1,
+            })],
+            vec![IsafeEvent::TransactionExecuted(TransactionExecutedEvent {
+                account_id: account,
+                transaction_digest: d1.into_inner().to_vec(),
+                total_member_weight: 2,
+                approvers: vec![alice],
+                approver_weights: vec![1],
+                threshold: 1,
+            })],
+            vec![IsafeEvent::TransactionRemoved(TransactionRemovedEvent {
+                account_id: account,
+                transaction_digest: d2.into_inner().to_vec(),
+            })],
+        ];
+        checkpoints
+            .into_iter()
+            .zip(FIRST_CHECKPOINT..)
+            .map(|(events, checkpoint)| {
+                let firing_tx = TransactionDigest::random().to_string();
+                let events = events
+                    .into_iter()
+                    .map(|event| (event, firing_tx.clone()))
+                    .collect();
+                (checkpoint, events)
+            })
+            .collect()
+    }
+
+    /// The indexed members, transaction statuses, approvals and events of an account.
+    fn snapshot(pool: &DbConnectionPool, account: &IotaAddress) -> Snapshot {
+        let mut conn = pool.get_connection().unwrap();
+        let mut members = queries::get_members_for_account(&mut conn, account).unwrap();
+        members.sort();
+        let transactions = queries::get_stored_transactions_for_account(&mut conn, account)
+            .unwrap()
+            .into_iter()
+            .map(|tx| (tx.transaction_digest, String::from(tx.status)))
+            .collect();
+        let mut approvals = queries::get_approvals_for_account(&mut conn, account).unwrap();
+        approvals.sort();
+        let events = queries::get_events_for_account(&mut conn, account)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.event_type, e.timestamp, e.checkpoint, e.content))
+            .collect();
+        (members, transactions, approvals, events)
+    }
+
+    /// Shuffles with a xorshift generator, so a failing order can be reproduced from the seed.
+    fn shuffle<T>(items: &mut [T], mut seed: u64) {
+        for i in (1..items.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            items.swap(i, (seed % (i as u64 + 1)) as usize);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_checkpoint_waits_until_committed() {
+        let account = IotaAddress::random_for_testing_only();
+        let members = [(); 3].map(|_| IotaAddress::random_for_testing_only());
+        let digests = [TransactionDigest::random(), TransactionDigest::random()];
+        let history = account_history(account, members, digests);
+        let poisoned = vec![(
+            IsafeEvent::TransactionProposed(TransactionProposedEvent {
+                account_id: account,
+                transaction_digest: vec![0; 31],
+                proposer: members[0],
+            }),
+            TransactionDigest::random().to_string(),
+        )];
+
+        let (mut worker, pool) = test_worker("isafe-commit-turn");
+        worker.policy = ErrorPolicy {
+            checkpoint_max_attempts: u32::MAX,
+            retry_backoff_ms: 1,
+            max_retry_backoff_ms: 1,
+        };
+        let (first, events) = &history[0];
+        let (second, next_events) = &history[1];
+        let mut next =
+            std::pin::pin!(worker.ingest(*second, checkpoint_timestamp(*second), next_events));
+        assert!(futures::poll!(next.as_mut()).is_pending());
+
+        // the failing checkpoint is abandoned while it retries, without landing
+        let failing = worker.ingest(*first, checkpoint_timestamp(*first), &poisoned);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), failing)
+                .await
+                .is_err()
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(futures::poll!(next.as_mut()).is_pending());
+        assert!(snapshot(&pool, &account).1.is_empty());
+
+        worker
+            .ingest(*first, checkpoint_timestamp(*first), events)
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(1), next)
+            .await
+            .expect("the next checkpoint gets its turn")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_poisoned_checkpoint_is_quarantined() {
+        let account = IotaAddress::random_for_testing_only();
+        let members = [(); 3].map(|_| IotaAddress::random_for_testing_only());
+        let digests = [TransactionDigest::random(), TransactionDigest::random()];
+        let mut history = account_history(account, members, digests);
+        // a digest of the wrong length fails the checkpoint on every attempt
+        history[1].1 = vec![(
+            IsafeEvent::TransactionProposed(TransactionProposedEvent {
+                account_id: account,
+                transaction_digest: vec![0; 31],
+                proposer: members[0],
+            }),
+            TransactionDigest::random().to_string(),
+        )];
+        // the approvals of the never proposed transaction are left out
+        history[2].1.clear();
+
+        let (mut worker, pool) = test_worker("isafe-poisoned-checkpoint");
+        worker.policy = ErrorPolicy {
+            checkpoint_max_attempts: 3,
+            retry_backoff_ms: 1,
+            max_retry_backoff_ms: 1,
+        };
+        for (checkpoint, events) in &history[..4] {
+            worker
+                .ingest(*checkpoint, checkpoint_timestamp(*checkpoint), events)
+                .await
+                .unwrap();
+        }
+
+        let mut conn = pool.get_connection().unwrap();
+        let quarantined = queries::get_quarantined_checkpoints(&mut conn).unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].checkpoint, FIRST_CHECKPOINT as i64 + 1);
+        assert_eq!(quarantined[0].attempts, 3);
+        assert!(quarantined[0].error.contains("invalid transaction digest length"));
+
+        let status = worker.health.status();
+        assert_eq!(status.state, IngestionState::Degraded);
+        assert_eq!(status.quarantined, 1);
+        assert_eq!(status.last_checkpoint, Some(FIRST_CHECKPOINT + 3));
+
+        // the checkpoints after the quarantined one are ingested
+        let (_, transactions, ..) = snapshot(&pool, &account);
+        assert_eq!(
+            transactions,
+            vec![(digests[1].to_string(), "Proposed".to_string())]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shuffled_delivery_is_deterministic() {
+        let account = IotaAddress::random_for_testing_only();
+        let members = [(); 3].map(|_| IotaAddress::random_for_testing_only());
+        let digests = [TransactionDigest::random(), TransactionDigest::random()];
+        let history = account_history(account, members, digests);
+
+        let (worker, pool) = test_worker("isafe-sequential-delivery");
+        for (checkpoint, events) in &history {
+            worker
+                .ingest(*checkpoint, checkpoint_timestamp(*checkpoint), events)
+                .await
+                .unwrap();
+        }
+        let expected = snapshot(&pool, &account);
+        assert_eq!(
+            expected.1,
+            vec![
+                (digests[0].to_string(), "Executed".to_string()),
+                (digests[1].to_string(), "Removed".to_string()),
+            ]
+        );
+        assert!(!expected.0.iter().any(|(member, _)| *member == members[1]));
+
+        for seed in 1..=8 {
+            let (worker, pool) = test_worker(&format!("isafe-shuffled-delivery-{seed}"));
+            let worker = Arc::new(worker);
+            let mut delivery = history.clone();
+            shuffle(&mut delivery, seed);
+
+            let mut tasks = JoinSet::new();
+            for (checkpoint, events) in delivery {
+                let worker = worker.clone();
+                tasks.spawn(async move {
+                    worker
+                        .ingest(checkpoint, checkpoint_timestamp(checkpoint), &events)
+                        .await
+                });
+            }
+            while let Some(res) = tasks.join_next().await {
+                res.unwrap().unwrap();
+            }
+            assert_eq!(snapshot(&pool, &account), expected, "seed {seed}");
+        }
+    }
+}
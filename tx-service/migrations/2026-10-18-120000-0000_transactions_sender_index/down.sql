@@ -0,0 +1,2 @@
+DROP INDEX idx_transactions_sender_added_at;
+CREATE INDEX idx_transactions_sender ON transactions(sender);
@@ -0,0 +1,4 @@
+-- Listing the transactions of an account filters by sender and orders by time, the composite
+-- index covers both and makes the single column sender index redundant.
+CREATE INDEX idx_transactions_sender_added_at ON transactions(sender, added_at);
+DROP INDEX IF EXISTS idx_transactions_sender;
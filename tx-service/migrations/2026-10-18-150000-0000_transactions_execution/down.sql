@@ -0,0 +1,4 @@
+ALTER TABLE transactions DROP COLUMN executed_at;
+ALTER TABLE transactions DROP COLUMN execution_error;
+ALTER TABLE transactions DROP COLUMN effects_digest;
+ALTER TABLE transactions DROP COLUMN execution_status;
@@ -0,0 +1,5 @@
+-- Outcome of the last execution relayed by the service.
+ALTER TABLE transactions ADD COLUMN execution_status TEXT;
+ALTER TABLE transactions ADD COLUMN effects_digest TEXT;
+ALTER TABLE transactions ADD COLUMN execution_error TEXT;
+ALTER TABLE transactions ADD COLUMN executed_at INTEGER;
@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP COLUMN stale_objects;
+ALTER TABLE transactions DROP COLUMN stale_at;
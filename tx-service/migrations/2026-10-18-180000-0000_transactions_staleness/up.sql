@@ -0,0 +1,4 @@
+-- Set once an owned input or gas coin of the transaction moved to another version.
+ALTER TABLE transactions ADD COLUMN stale_at INTEGER;
+-- JSON array of the IDs of the moved objects.
+ALTER TABLE transactions ADD COLUMN stale_objects TEXT;
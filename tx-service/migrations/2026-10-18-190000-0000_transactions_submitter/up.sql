@@ -0,0 +1,2 @@
+-- The address that submitted the transaction, if known.
+ALTER TABLE transactions ADD COLUMN submitter TEXT;
@@ -0,0 +1,14 @@
+-- Comments of members on stored transactions, signed by their author.
+CREATE TABLE comments (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    digest TEXT NOT NULL REFERENCES transactions(digest),
+    author TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- Whether the author flags a concern about the transaction.
+    concern BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Signatures are deterministic, posting the same comment again yields the same signature.
+    signature TEXT NOT NULL UNIQUE,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_comments_digest_created_at ON comments(digest, created_at);
@@ -0,0 +1,17 @@
+-- Named groups of transactions submitted together, e.g. a payroll run.
+CREATE TABLE batches (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- The transactions of a batch, including ones that were already stored before.
+CREATE TABLE batch_transactions (
+    batch_id INTEGER NOT NULL REFERENCES batches(id),
+    digest TEXT NOT NULL REFERENCES transactions(digest),
+    -- The index of the item in the submitted batch.
+    position INTEGER NOT NULL,
+    PRIMARY KEY (batch_id, digest)
+);
+
+CREATE INDEX idx_batches_created_at ON batches(created_at);
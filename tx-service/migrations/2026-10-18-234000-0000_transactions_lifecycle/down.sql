@@ -0,0 +1,5 @@
+DROP TABLE service_state;
+DROP INDEX idx_transactions_lifecycle;
+ALTER TABLE transactions DROP COLUMN pruned_at;
+ALTER TABLE transactions DROP COLUMN lifecycle_at;
+ALTER TABLE transactions DROP COLUMN lifecycle;
@@ -0,0 +1,17 @@
+-- Where the transaction is in its on-chain lifecycle: pending, executed or removed.
+ALTER TABLE transactions ADD COLUMN lifecycle TEXT NOT NULL DEFAULT 'pending';
+-- When the lifecycle last changed.
+ALTER TABLE transactions ADD COLUMN lifecycle_at INTEGER;
+-- Set once the payload was pruned, the row is kept as a tombstone.
+ALTER TABLE transactions ADD COLUMN pruned_at INTEGER;
+
+UPDATE transactions SET lifecycle = 'executed', lifecycle_at = executed_at
+    WHERE execution_status = 'success';
+
+CREATE INDEX idx_transactions_lifecycle ON transactions(lifecycle, lifecycle_at);
+
+-- Progress of background tasks that must survive restarts, e.g. event cursors.
+CREATE TABLE service_state (
+    key TEXT NOT NULL PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,21 @@
+-- Structured metadata of stored transactions, signed along with the description.
+CREATE TABLE transaction_metadata (
+    digest TEXT NOT NULL PRIMARY KEY REFERENCES transactions(digest),
+    category TEXT,
+    invoice_ref TEXT,
+    ticket_id TEXT,
+    -- As YYYY-MM-DD.
+    due_date TEXT
+);
+
+CREATE INDEX idx_transaction_metadata_category ON transaction_metadata(category);
+
+CREATE TABLE transaction_tags (
+    digest TEXT NOT NULL REFERENCES transactions(digest),
+    tag TEXT NOT NULL,
+    -- The index of the tag in the submitted metadata.
+    position INTEGER NOT NULL,
+    PRIMARY KEY (digest, tag)
+);
+
+CREATE INDEX idx_transaction_tags_tag ON transaction_tags(tag);
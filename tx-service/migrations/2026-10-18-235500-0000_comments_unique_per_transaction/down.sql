@@ -0,0 +1,16 @@
+CREATE TABLE comments_old (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    digest TEXT NOT NULL REFERENCES transactions(digest),
+    author TEXT NOT NULL,
+    body TEXT NOT NULL,
+    concern BOOLEAN NOT NULL DEFAULT FALSE,
+    signature TEXT NOT NULL UNIQUE,
+    created_at INTEGER NOT NULL
+);
+
+INSERT OR IGNORE INTO comments_old (id, digest, author, body, concern, signature, created_at)
+    SELECT id, digest, author, body, concern, signature, created_at FROM comments;
+DROP TABLE comments;
+ALTER TABLE comments_old RENAME TO comments;
+
+CREATE INDEX idx_comments_digest_created_at ON comments(digest, created_at);
@@ -0,0 +1,21 @@
+-- Signed comments are deduplicated per transaction and author, SQLite can't drop the unique
+-- constraint of a column, so the table is rebuilt.
+CREATE TABLE comments_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    digest TEXT NOT NULL REFERENCES transactions(digest),
+    author TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- Whether the author flags a concern about the transaction.
+    concern BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Signatures are deterministic, posting the same comment again yields the same signature.
+    signature TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (digest, author, signature)
+);
+
+INSERT INTO comments_new (id, digest, author, body, concern, signature, created_at)
+    SELECT id, digest, author, body, concern, signature, created_at FROM comments;
+DROP TABLE comments;
+ALTER TABLE comments_new RENAME TO comments;
+
+CREATE INDEX idx_comments_digest_created_at ON comments(digest, created_at);
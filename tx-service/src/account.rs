@@ -0,0 +1,76 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The dynamic fields that hold the members and threshold of an iSafe account, as rendered by
+//! the JSON-RPC.
+
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use iota_types::base_types::IotaAddress;
+
+/// Type of the dynamic field value that holds the members of an account.
+pub(crate) const MEMBERS_TYPE: &str = "::members::Members";
+/// Type of the dynamic field name under which the threshold of an account is stored.
+pub(crate) const THRESHOLD_KEY_TYPE: &str = "::dynamic_auth::ThresholdKey";
+
+/// Parses the JSON content of the `Field<MembersKey, Members>` dynamic field into the addresses
+/// and weights of the members.
+pub(crate) fn parse_members_field(field: &serde_json::Value) -> Result<Vec<(IotaAddress, u64)>> {
+    field["value"]["list"]
+        .as_array()
+        .ok_or_else(|| anyhow!("members field without `list`"))?
+        .iter()
+        .map(|member| {
+            let addr = member["addr"]
+                .as_str()
+                .ok_or_else(|| anyhow!("member without `addr`"))?;
+            Ok((IotaAddress::from_str(addr)?, parse_u64(&member["weight"])?))
+        })
+        .collect()
+}
+
+/// Parses a `u64` of a Move value, which the JSON-RPC renders as string.
+pub(crate) fn parse_u64(value: &serde_json::Value) -> Result<u64> {
+    match value {
+        serde_json::Value::String(value) => Ok(value.parse()?),
+        value => value.as_u64().ok_or_else(|| anyhow!("invalid u64 {value}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_members_field() {
+        let field = serde_json::json!({
+            "id": "0x1",
+            "name": { "dummy_field": false },
+            "value": {
+                "list": [
+                    { "addr": "0x2", "weight": "1" },
+                    { "addr": "0x3", "weight": "2" },
+                ]
+            }
+        });
+        assert_eq!(
+            parse_members_field(&field).unwrap(),
+            vec![
+                (IotaAddress::from_str("0x2").unwrap(), 1),
+                (IotaAddress::from_str("0x3").unwrap(), 2)
+            ]
+        );
+        assert!(parse_members_field(&serde_json::json!({ "value": {} })).is_err());
+    }
+
+    #[test]
+    fn test_parse_u64() {
+        assert_eq!(
+            parse_u64(&serde_json::json!("18446744073709551615")).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(parse_u64(&serde_json::json!(7)).unwrap(), 7);
+        assert!(parse_u64(&serde_json::json!("-1")).is_err());
+    }
+}
@@ -7,10 +7,32 @@ use axum::{
 };
 use reqwest::StatusCode;
 
+use crate::{
+    api::responses::TransactionResponse, auth::ObjectError, builder::BuildError,
+    execute::ExecuteError, submission::SubmissionError,
+};
+
 #[derive(Debug)]
 pub enum ApiError {
     // Invalid input data (e.g., malformed address)
     BadRequest(String),
+    // The requested resource does not exist
+    NotFound(String),
+    // The request is valid, but can't be processed in the current state
+    UnprocessableEntity(String),
+    // The request lacks a valid signature
+    Unauthorized(String),
+    // The request is valid, but not accepted (e.g., the sender is not an iSafe account)
+    Forbidden(String),
+    // The request conflicts with the stored state, which is returned alongside
+    Conflict {
+        message: String,
+        stored: Box<TransactionResponse>,
+    },
+    // The node failed to process a request it received
+    BadGateway(String),
+    // The node can't be reached or didn't answer in time
+    ServiceUnavailable(String),
     // Database connection or query errors
     Database(anyhow::Error),
     // Internal server errors
@@ -29,6 +51,56 @@ impl IntoResponse for ApiError {
                     "message": msg
                 })),
             ),
+            ApiError::NotFound(msg) => (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Not Found",
+                    "message": msg
+                })),
+            ),
+            ApiError::UnprocessableEntity(msg) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "error": "Unprocessable Entity",
+                    "message": msg
+                })),
+            ),
+            ApiError::Unauthorized(msg) => (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "error": "Unauthorized",
+                    "message": msg
+                })),
+            ),
+            ApiError::Forbidden(msg) => (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": "Forbidden",
+                    "message": msg
+                })),
+            ),
+            ApiError::Conflict { message, stored } => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": "Conflict",
+                    "message": message,
+                    "stored": stored
+                })),
+            ),
+            ApiError::BadGateway(msg) => (
+                StatusCode::BAD_GATEWAY,
+                Json(serde_json::json!({
+                    "error": "Bad Gateway",
+                    "message": msg
+                })),
+            ),
+            ApiError::ServiceUnavailable(msg) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({
+                    "error": "Service Unavailable",
+                    "message": msg
+                })),
+            ),
             ApiError::Database(err) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({
@@ -56,6 +128,49 @@ impl From<anyhow::Error> for ApiError {
     }
 }
 
+impl From<ObjectError> for ApiError {
+    fn from(err: ObjectError) -> Self {
+        match err {
+            ObjectError::NotFound(_) => ApiError::NotFound(err.to_string()),
+            ObjectError::NotShared(_) | ObjectError::InvalidArgument(_) => {
+                ApiError::BadRequest(err.to_string())
+            }
+            ObjectError::Node(err) => ApiError::Internal(err),
+        }
+    }
+}
+
+impl From<ExecuteError> for ApiError {
+    fn from(err: ExecuteError) -> Self {
+        match err {
+            ExecuteError::Object(err) => err.into(),
+            ExecuteError::Unavailable(_) => ApiError::ServiceUnavailable(err.to_string()),
+            ExecuteError::Node(_) => ApiError::BadGateway(err.to_string()),
+        }
+    }
+}
+
+impl From<SubmissionError> for ApiError {
+    fn from(err: SubmissionError) -> Self {
+        match err {
+            SubmissionError::InvalidSignature(_) => ApiError::Unauthorized(err.to_string()),
+            SubmissionError::NotMember { .. } => ApiError::Forbidden(err.to_string()),
+            SubmissionError::Node(err) => ApiError::Internal(err),
+        }
+    }
+}
+
+impl From<BuildError> for ApiError {
+    fn from(err: BuildError) -> Self {
+        match err {
+            BuildError::InvalidRequest(_) => ApiError::BadRequest(err.to_string()),
+            BuildError::InsufficientBalance(_) => ApiError::UnprocessableEntity(err.to_string()),
+            BuildError::Object(err) => err.into(),
+            BuildError::Node(err) => ApiError::Internal(err),
+        }
+    }
+}
+
 // Convert database errors specifically
 impl From<diesel::result::Error> for ApiError {
     fn from(err: diesel::result::Error) -> Self {
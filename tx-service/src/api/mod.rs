@@ -1,11 +1,16 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
+use iota_types::base_types::IotaAddress;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-use crate::db::pool::DbConnectionPool;
+use crate::{
+    auth::ObjectResolver, builder::PaymentBuilder, db::pool::DbConnectionPool,
+    governance::GovernanceBuilder, node::Node, sender_check::SenderCheck,
+    simulate::Simulator, submission::SubmissionVerifier,
+};
 
 mod error;
 mod responses;
@@ -14,16 +19,47 @@ mod routes;
 #[derive(Clone)]
 pub struct ApiState {
     pub pool: DbConnectionPool,
-    pub node_url: String,
+    pub node: Arc<dyn Node>,
+    /// Resolves authenticator arguments, shared with the execution relay.
+    pub objects: Arc<ObjectResolver>,
+    /// Rejects transactions whose sender is not an iSafe account, `None` if disabled.
+    pub sender_check: Option<Arc<SenderCheck>>,
+    pub simulator: Arc<Simulator>,
+    /// Verifies that submissions are signed by a member of the sender account.
+    pub submissions: Arc<SubmissionVerifier>,
+    pub payments: Arc<PaymentBuilder>,
+    /// Builds `dynamic_auth` governance calls, `None` if the isafe package address is unknown.
+    pub governance: Option<Arc<GovernanceBuilder>>,
 }
 
 pub async fn start_api_server(
     pool: DbConnectionPool,
     port: u16,
     token: CancellationToken,
-    node_url: String,
+    node: Arc<dyn Node>,
+    sender_check: Option<Arc<SenderCheck>>,
+    isafe_package: Option<IotaAddress>,
 ) -> anyhow::Result<()> {
-    let state = ApiState { pool, node_url};
+    let objects = Arc::new(ObjectResolver::new(node.clone()));
+    let payments = Arc::new(PaymentBuilder::new(node.clone()));
+    let governance = isafe_package.map(|package| {
+        Arc::new(GovernanceBuilder::new(
+            node.clone(),
+            objects.clone(),
+            payments.clone(),
+            package,
+        ))
+    });
+    let state = ApiState {
+        pool,
+        objects,
+        simulator: Arc::new(Simulator::new(node.clone())),
+        submissions: Arc::new(SubmissionVerifier::new(node.clone())),
+        payments,
+        governance,
+        node,
+        sender_check,
+    };
 
     let app = routes::routes().with_state(state);
 
@@ -1,17 +1,46 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use iota_types::base_types::IotaAddress;
+use iota_types::base_types::{IotaAddress, ObjectID};
+use isafe_common::status::DependencyCheck;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    auth::AuthCallArg,
+    builder::{Payment, deserialize_amount},
+    execute::ExecutionStatus,
+    governance::GovernanceOp,
+    lifecycle::Lifecycle,
+    metadata::ProposalMetadata,
+};
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionResponse {
+    /// The base64 encoded BCS bytes, empty once pruned.
     pub bcs: String,
     pub sender: IotaAddress,
     pub added_at: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// The member that submitted the transaction, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitter: Option<IotaAddress>,
+    /// The outcome of the last execution relayed by the service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ExecutionResponse>,
+    /// Set once an input the transaction pins moved on, it can't be executed anymore.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale: Option<StaleResponse>,
+    /// The members that flagged a concern in a comment on the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub concerns: Vec<IotaAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ProposalMetadata>,
+    pub lifecycle: Lifecycle,
+    /// Set once the payload was pruned, only a tombstone of the transaction is kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pruned_at: Option<u64>,
 }
 
 impl axum::response::IntoResponse for TransactionResponse {
@@ -25,11 +54,317 @@ impl axum::response::IntoResponse for TransactionResponse {
 pub struct AddTxRequest {
     pub tx_bytes: String,
     pub description: Option<String>,
+    /// Signed along with the description.
+    pub metadata: Option<ProposalMetadata>,
+    /// Base64 encoded personal message signature of a member over the digest, metadata and
+    /// description.
+    pub signature: Option<String>,
+}
+
+/// Transactions submitted together, each signed like a single submission.
+#[derive(Deserialize)]
+pub struct AddTxBatchRequest {
+    /// Groups the stored transactions under a batch with this name.
+    pub name: Option<String>,
+    pub transactions: Vec<AddTxRequest>,
+}
+
+/// Arguments passed to the authenticator after the account object.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveAuthSignatureRequest {
+    #[serde(default)]
+    pub call_args: Vec<AuthCallArg>,
+}
+
+#[derive(Deserialize)]
+pub struct AddCommentRequest {
+    pub body: String,
+    /// Flags a concern about the transaction.
+    #[serde(default)]
+    pub concern: bool,
+    /// Base64 encoded personal message signature of a member over the comment.
+    pub signature: String,
+}
+
+/// Options shared by the transaction builders.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildOptions {
+    /// The account the transaction is built for.
+    pub sender: IotaAddress,
+    pub gas_budget: Option<u64>,
+    /// Signature of a member over the digest and generated description of the built
+    /// transaction, like for `/add_transaction`. The transaction is stored as a proposal if set,
+    /// so it is usually passed when building the same transaction a second time.
+    pub signature: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferCoinRequest {
+    #[serde(flatten)]
+    pub options: BuildOptions,
+    /// The coin type, IOTA if not set.
+    pub coin_type: Option<String>,
+    pub recipient: IotaAddress,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoutRequest {
+    #[serde(flatten)]
+    pub options: BuildOptions,
+    /// The coin type, IOTA if not set.
+    pub coin_type: Option<String>,
+    pub payments: Vec<Payment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferObjectsRequest {
+    #[serde(flatten)]
+    pub options: BuildOptions,
+    pub recipient: IotaAddress,
+    pub object_ids: Vec<ObjectID>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceRequest {
+    #[serde(flatten)]
+    pub options: BuildOptions,
+    /// Composed into one transaction, in an order that keeps the threshold reachable.
+    pub operations: Vec<GovernanceOp>,
 }
 
 // Response body
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 pub struct AddTxResponse {
     pub digest: String,
     pub added_at: u64,
+    /// Whether the transaction was already stored by an earlier submission.
+    pub existing: bool,
+}
+
+/// A stored transaction in the listing of an account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummary {
+    pub digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub added_at: u64,
+    /// The address that submitted the transaction, if known.
+    pub submitter: Option<IotaAddress>,
+    /// The base64 encoded BCS bytes, only if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bcs: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale: Option<StaleResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ProposalMetadata>,
+    pub lifecycle: Lifecycle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pruned_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionsResponse {
+    pub transactions: Vec<TransactionSummary>,
+    /// The number of transactions matching the filter.
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+impl axum::response::IntoResponse for GetTransactionsResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResponse {
+    pub digest: String,
+    pub status: ExecutionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effects_digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub executed_at: u64,
+}
+
+impl axum::response::IntoResponse for ExecutionResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleResponse {
+    /// When the checker noticed the transaction is stale.
+    pub detected_at: u64,
+    /// The owned inputs and gas coins that are no longer at the pinned version.
+    pub moved_objects: Vec<ObjectID>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentResponse {
+    pub id: u64,
+    pub author: IotaAddress,
+    pub body: String,
+    pub concern: bool,
+    pub signature: String,
+    pub created_at: u64,
+}
+
+impl axum::response::IntoResponse for CommentResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCommentsResponse {
+    pub comments: Vec<CommentResponse>,
+    /// The number of comments on the transaction.
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+impl axum::response::IntoResponse for GetCommentsResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuiltTransactionResponse {
+    /// The base64 encoded BCS bytes of the `TransactionData`.
+    pub tx_bytes: String,
+    pub digest: String,
+    pub description: String,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    /// Whether the transaction was stored as a proposal.
+    pub stored: bool,
+}
+
+impl axum::response::IntoResponse for BuiltTransactionResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+/// What happened to an item of a batch submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Stored,
+    /// The same transaction with the same description was already stored.
+    Duplicate,
+    /// The transaction is already stored with a different description.
+    Conflict,
+    /// The item failed validation and was not stored.
+    Invalid,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemResult {
+    /// The index of the item in the request.
+    pub index: usize,
+    /// The digest, if the transaction bytes could be decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    pub status: BatchItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub id: u64,
+    pub name: String,
+    pub created_at: u64,
+    /// The number of transactions of the batch.
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTxBatchResponse {
+    /// The created batch, only if a name was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch: Option<BatchSummary>,
+    /// One result per submitted transaction, in request order.
+    pub results: Vec<BatchItemResult>,
+}
+
+impl axum::response::IntoResponse for AddTxBatchResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBatchesResponse {
+    pub batches: Vec<BatchSummary>,
+    /// The number of batches.
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+impl axum::response::IntoResponse for GetBatchesResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResponse {
+    #[serde(flatten)]
+    pub batch: BatchSummary,
+    /// The transactions in the order they were submitted.
+    pub transactions: Vec<TransactionSummary>,
+}
+
+impl axum::response::IntoResponse for BatchResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusResponse {
+    pub version: &'static str,
+    pub git_revision: &'static str,
+    pub database: DependencyCheck,
+    pub node: DependencyCheck,
+    /// The latest checkpoint of the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_tip: Option<u64>,
+    /// Whether the database and the node are reachable.
+    pub ready: bool,
+}
+
+impl axum::response::IntoResponse for StatusResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
 }
@@ -1,47 +1,92 @@
 // Copyright (c) 2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use axum::{
     Router,
-    extract::{Json, Path, State},
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
     routing::get,
     routing::post,
 };
 use chrono::Utc;
+use diesel::SqliteConnection;
 use fastcrypto::encoding::{Base64, Encoding};
-use iota_json_rpc_types::IotaObjectDataOptions;
-use iota_sdk::IotaClientBuilder;
+use futures::{StreamExt, stream};
 use iota_types::{
-    base_types::{IotaAddress},
+    base_types::IotaAddress,
     digests::TransactionDigest,
-    move_authenticator::MoveAuthenticator,
-    object::Owner::Shared,
-    signature::GenericSignature,
-    transaction::CallArg,
+    transaction::{TransactionData, TransactionDataAPI},
 };
+use isafe_common::status::{DependencyCheck, ping};
+use serde::Deserialize;
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::{
     api::{
         ApiState,
         error::ApiError,
-        responses::{AddTxRequest, AddTxResponse, TransactionResponse},
+        responses::{
+            AddCommentRequest, AddTxBatchRequest, AddTxBatchResponse, AddTxRequest, AddTxResponse,
+            BatchItemResult, BatchItemStatus, BatchResponse, BatchSummary, BuildOptions,
+            BuiltTransactionResponse, CommentResponse, DeriveAuthSignatureRequest,
+            ExecutionResponse, GetBatchesResponse, GetCommentsResponse, GetTransactionsResponse,
+            GovernanceRequest, PayoutRequest, StaleResponse, StatusResponse, TransactionResponse,
+            TransactionSummary, TransferCoinRequest, TransferObjectsRequest,
+        },
     },
+    builder::{BuiltTransaction, DEFAULT_GAS_BUDGET, MAX_GAS_BUDGET, MIN_GAS_BUDGET, Payment},
     db::{
-        queries
+        queries::{self, BatchItem, InsertOutcome, TransactionFilter},
+        schema::{StoredBatch, StoredComment, StoredTransaction},
     },
+    decode::{self, DecodedTransaction},
+    execute::{self, ExecutionStatus},
+    lifecycle::Lifecycle,
+    metadata::{Category, MAX_DESCRIPTION_LENGTH, ProposalMetadata},
+    simulate::SimulationReport,
+    submission,
 };
 
 pub fn routes() -> Router<ApiState> {
     Router::new()
         .route("/health", get(health_check))
+        .route("/status", get(get_status))
+        .route("/ready", get(get_readiness))
         .route("/transaction/{tx_digest}", get(get_transaction_by_digest))
+        .route(
+            "/transaction/{tx_digest}/decoded",
+            get(get_decoded_transaction),
+        )
+        .route(
+            "/transaction/{tx_digest}/simulate",
+            post(simulate_transaction),
+        )
+        .route(
+            "/transaction/{tx_digest}/execute",
+            post(execute_transaction),
+        )
+        .route(
+            "/transaction/{tx_digest}/comments",
+            get(get_comments).post(add_comment),
+        )
+        .route(
+            "/transactions/{account_address}",
+            get(get_transactions_by_account),
+        )
+        .route("/stale", get(get_stale_transactions))
         .route("/add_transaction", post(add_transaction))
+        .route("/add_transactions", post(add_transactions))
+        .route("/batches", get(get_batches))
+        .route("/batch/{batch_id}", get(get_batch))
+        .route("/build/transfer_coin", post(build_coin_transfer))
+        .route("/build/payout", post(build_payout))
+        .route("/build/transfer_objects", post(build_object_transfer))
+        .route("/build/governance", post(build_governance))
         .route(
             "/derive_auth_signature/{address}",
-            get(derive_auth_signature),
+            get(derive_auth_signature).post(derive_auth_signature_with_args),
         )
         .layer(
             CorsLayer::new()
@@ -55,6 +100,45 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+/// How long the status waits for the node.
+const NODE_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn status(state: &ApiState) -> StatusResponse {
+    let database = state
+        .pool
+        .get_connection()
+        .and_then(|mut conn| ping(&mut conn));
+    let chain_tip = tokio::time::timeout(NODE_STATUS_TIMEOUT, state.node.latest_checkpoint())
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("node did not answer in time")));
+
+    StatusResponse {
+        version: crate::VERSION,
+        git_revision: crate::GIT_REVISION,
+        ready: database.is_ok() && chain_tip.is_ok(),
+        database: DependencyCheck::from(&database),
+        node: DependencyCheck::from(&chain_tip),
+        chain_tip: chain_tip.ok(),
+    }
+}
+
+/// The state of the database and the node.
+async fn get_status(State(state): State<ApiState>) -> StatusResponse {
+    status(&state).await
+}
+
+/// Like the status, but `503 Service Unavailable` unless the database and the node are
+/// reachable.
+async fn get_readiness(State(state): State<ApiState>) -> (StatusCode, StatusResponse) {
+    let status = status(&state).await;
+    let code = if status.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, status)
+}
+
 async fn get_transaction_by_digest(
     State(state): State<ApiState>,
     Path(tx_digest): Path<String>,
@@ -67,28 +151,420 @@ async fn get_transaction_by_digest(
         .get_connection()
         .map_err(|err| ApiError::Database(err))?;
 
-    let tx = queries::get_transaction_by_digest(&mut conn, &tx_digest)
+    let tx = stored_transaction(&mut conn, &tx_digest)?;
+
+    transaction_response(&mut conn, tx)
+}
+
+/// Renders a stored transaction as structured JSON.
+async fn get_decoded_transaction(
+    State(state): State<ApiState>,
+    Path(tx_digest): Path<String>,
+) -> Result<Json<DecodedTransaction>, ApiError> {
+    let _ = TransactionDigest::from_str(&tx_digest)
+        .map_err(|_| ApiError::BadRequest("Invalid IOTA transaction digest".to_string()))?;
+
+    let tx_data = stored_transaction_data(&state, &tx_digest)?;
+
+    let decoded = decode::decode_transaction(&tx_data, state.node.as_ref())
+        .await
+        .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+    Ok(Json(decoded))
+}
+
+/// Dry-runs a stored transaction against the current on-chain state.
+async fn simulate_transaction(
+    State(state): State<ApiState>,
+    Path(tx_digest): Path<String>,
+) -> Result<Json<SimulationReport>, ApiError> {
+    let _ = TransactionDigest::from_str(&tx_digest)
+        .map_err(|_| ApiError::BadRequest("Invalid IOTA transaction digest".to_string()))?;
+
+    let tx_data = stored_transaction_data(&state, &tx_digest)?;
+    let report = state.simulator.simulate(&tx_data).await?;
+
+    Ok(Json(report))
+}
+
+/// Executes a stored transaction on behalf of its account and records the outcome.
+///
+/// Transactions that were already executed successfully are not submitted again.
+async fn execute_transaction(
+    State(state): State<ApiState>,
+    Path(tx_digest): Path<String>,
+) -> Result<ExecutionResponse, ApiError> {
+    let _ = TransactionDigest::from_str(&tx_digest)
+        .map_err(|_| ApiError::BadRequest("Invalid IOTA transaction digest".to_string()))?;
+
+    let stored = {
+        let mut conn = state
+            .pool
+            .get_connection()
+            .map_err(|err| ApiError::Database(err))?;
+        stored_transaction(&mut conn, &tx_digest)?
+    };
+    if let Some(execution) = execution_response(&stored)? {
+        if execution.status == ExecutionStatus::Success {
+            return Ok(execution);
+        }
+    }
+    let tx_data = stored_transaction_data(&state, &tx_digest)?;
+
+    let outcome =
+        execute::execute_transaction(state.node.as_ref(), &state.objects, tx_data).await?;
+    let now = Utc::now().timestamp() as u64;
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+    queries::set_execution_outcome(&mut conn, &tx_digest, &outcome, now)
+        .map_err(|err| ApiError::Database(err))?;
+
+    if outcome.insufficient_approvals() {
+        return Err(ApiError::UnprocessableEntity(format!(
+            "The account's authenticator rejected transaction {tx_digest}: the approvals don't \
+             reach the threshold yet"
+        )));
+    }
+    if outcome.status == ExecutionStatus::Rejected {
+        return Err(ApiError::UnprocessableEntity(format!(
+            "The node rejected transaction {tx_digest}: {}",
+            outcome.error.unwrap_or_default()
+        )));
+    }
+
+    Ok(ExecutionResponse {
+        digest: tx_digest,
+        status: outcome.status,
+        effects_digest: outcome.effects_digest,
+        error: outcome.error,
+        executed_at: now,
+    })
+}
+
+fn execution_response(tx: &StoredTransaction) -> Result<Option<ExecutionResponse>, ApiError> {
+    let (Some(status), Some(executed_at)) = (&tx.execution_status, tx.executed_at) else {
+        return Ok(None);
+    };
+    Ok(Some(ExecutionResponse {
+        digest: tx.digest.clone(),
+        status: status.parse().map_err(|err| ApiError::Internal(err))?,
+        effects_digest: tx.effects_digest.clone(),
+        error: tx.execution_error.clone(),
+        executed_at: executed_at as u64,
+    }))
+}
+
+fn stale_response(tx: &StoredTransaction) -> Result<Option<StaleResponse>, ApiError> {
+    let (Some(detected_at), Some(moved_objects)) = (tx.stale_at, &tx.stale_objects) else {
+        return Ok(None);
+    };
+    Ok(Some(StaleResponse {
+        detected_at: detected_at as u64,
+        moved_objects: serde_json::from_str(moved_objects)
+            .map_err(|err| ApiError::Internal(err.into()))?,
+    }))
+}
+
+/// Loads a stored transaction, answering `404` if it isn't stored.
+fn stored_transaction(
+    conn: &mut SqliteConnection,
+    tx_digest: &str,
+) -> Result<StoredTransaction, ApiError> {
+    queries::get_transaction_by_digest(conn, tx_digest)
+        .map_err(|err| ApiError::Database(err))?
+        .ok_or_else(|| ApiError::NotFound(format!("Transaction {tx_digest} not found")))
+}
+
+/// Loads and deserializes a stored transaction.
+fn stored_transaction_data(state: &ApiState, tx_digest: &str) -> Result<TransactionData, ApiError> {
+    let mut conn = state
+        .pool
+        .get_connection()
         .map_err(|err| ApiError::Database(err))?;
+    let tx = stored_transaction(&mut conn, tx_digest)?;
+    if tx.pruned_at.is_some() {
+        return Err(ApiError::UnprocessableEntity(format!(
+            "The payload of transaction {tx_digest} was pruned after it was {}",
+            tx.lifecycle
+        )));
+    }
 
+    bcs::from_bytes::<TransactionData>(
+        &Base64::decode(&tx.tx_data).map_err(|err| ApiError::Internal(anyhow::anyhow!(err)))?,
+    )
+    .map_err(|err| ApiError::Internal(err.into()))
+}
+
+fn transaction_response(
+    conn: &mut SqliteConnection,
+    tx: StoredTransaction,
+) -> Result<TransactionResponse, ApiError> {
+    let concerns = queries::get_concerned_members(conn, &tx.digest)
+        .map_err(|err| ApiError::Database(err))?
+        .iter()
+        .map(|author| IotaAddress::from_str(author))
+        .collect::<Result<_, _>>()
+        .map_err(|err| ApiError::Internal(err))?;
+    let metadata =
+        queries::get_metadata(conn, &tx.digest).map_err(|err| ApiError::Database(err))?;
     Ok(TransactionResponse {
+        execution: execution_response(&tx)?,
+        stale: stale_response(&tx)?,
+        concerns,
+        metadata,
+        lifecycle: tx
+            .lifecycle
+            .parse()
+            .map_err(|err| ApiError::Internal(err))?,
+        pruned_at: tx.pruned_at.map(|at| at as u64),
         bcs: tx.tx_data,
         sender: IotaAddress::from_str(&tx.sender).map_err(|err| ApiError::Internal(err))?,
         added_at: tx.added_at as u64,
         description: tx.description,
+        submitter: tx
+            .submitter
+            .and_then(|submitter| IotaAddress::from_str(&submitter).ok()),
     })
 }
 
-async fn add_transaction(
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Deserialize)]
+struct TransactionsQuery {
+    limit: Option<u64>,
+    #[serde(default)]
+    offset: u64,
+    /// Only transactions added at or after this unix timestamp, in seconds.
+    from: Option<u64>,
+    /// Only transactions added at or before this unix timestamp, in seconds.
+    to: Option<u64>,
+    /// Only transactions in this lifecycle, e.g. `pending`.
+    lifecycle: Option<Lifecycle>,
+    /// Only transactions with metadata of this category, e.g. `payroll`.
+    category: Option<Category>,
+    /// Only transactions with metadata carrying this tag.
+    tag: Option<String>,
+    /// Include the base64 encoded transaction bytes.
+    #[serde(default)]
+    include_bytes: bool,
+}
+
+async fn get_transactions_by_account(
     State(state): State<ApiState>,
-    Json(payload): Json<AddTxRequest>,
-) -> Result<Json<AddTxResponse>, ApiError> {
-    let tx_data = bcs::from_bytes::<iota_types::transaction::TransactionData>(
+    Path(account_address): Path<String>,
+    Query(query): Query<TransactionsQuery>,
+) -> Result<GetTransactionsResponse, ApiError> {
+    let address = IotaAddress::from_str(&account_address)
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "Limit must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(ApiError::BadRequest(
+                "`from` must not be after `to`".to_string(),
+            ));
+        }
+    }
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let (transactions, total) = queries::get_transactions_by_sender(
+        &mut conn,
+        &address,
+        &TransactionFilter {
+            from: query.from,
+            to: query.to,
+            lifecycle: query.lifecycle,
+            category: query.category,
+            tag: query.tag.clone(),
+            limit: limit as i64,
+            offset: query.offset.min(i64::MAX as u64) as i64,
+        },
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    Ok(GetTransactionsResponse {
+        transactions: transaction_summaries(&mut conn, transactions, query.include_bytes)?,
+        total: total as u64,
+        limit,
+        offset: query.offset,
+    })
+}
+
+/// Summarizes stored transactions along with their metadata.
+fn transaction_summaries(
+    conn: &mut SqliteConnection,
+    transactions: Vec<StoredTransaction>,
+    include_bytes: bool,
+) -> Result<Vec<TransactionSummary>, ApiError> {
+    let digests = transactions
+        .iter()
+        .map(|tx| tx.digest.clone())
+        .collect::<Vec<_>>();
+    let mut metadata =
+        queries::get_metadata_of(conn, &digests).map_err(|err| ApiError::Database(err))?;
+    transactions
+        .into_iter()
+        .map(|tx| {
+            let metadata = metadata.remove(&tx.digest);
+            transaction_summary(tx, metadata, include_bytes)
+        })
+        .collect()
+}
+
+fn transaction_summary(
+    tx: StoredTransaction,
+    metadata: Option<ProposalMetadata>,
+    include_bytes: bool,
+) -> Result<TransactionSummary, ApiError> {
+    Ok(TransactionSummary {
+        stale: stale_response(&tx)?,
+        metadata,
+        lifecycle: tx
+            .lifecycle
+            .parse()
+            .map_err(|err| ApiError::Internal(err))?,
+        pruned_at: tx.pruned_at.map(|at| at as u64),
+        digest: tx.digest,
+        description: tx.description,
+        added_at: tx.added_at as u64,
+        submitter: tx
+            .submitter
+            .and_then(|submitter| IotaAddress::from_str(&submitter).ok()),
+        bcs: include_bytes.then_some(tx.tx_data),
+    })
+}
+
+#[derive(Deserialize)]
+struct StaleQuery {
+    /// Only transactions of this account.
+    sender: Option<String>,
+    limit: Option<u64>,
+    #[serde(default)]
+    offset: u64,
+    /// Include the base64 encoded transaction bytes.
+    #[serde(default)]
+    include_bytes: bool,
+}
+
+/// Lists the transactions that can't be executed anymore because their inputs moved on, most
+/// recently detected first.
+async fn get_stale_transactions(
+    State(state): State<ApiState>,
+    Query(query): Query<StaleQuery>,
+) -> Result<GetTransactionsResponse, ApiError> {
+    let sender = query
+        .sender
+        .as_deref()
+        .map(IotaAddress::from_str)
+        .transpose()
+        .map_err(|_| ApiError::BadRequest("Invalid account address".to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "Limit must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let (transactions, total) = queries::get_stale_transactions(
+        &mut conn,
+        sender.as_ref(),
+        limit as i64,
+        query.offset.min(i64::MAX as u64) as i64,
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    Ok(GetTransactionsResponse {
+        transactions: transaction_summaries(&mut conn, transactions, query.include_bytes)?,
+        total: total as u64,
+        limit,
+        offset: query.offset,
+    })
+}
+
+/// Checks the description and metadata of a submission and decodes its transaction.
+fn decode_submission(payload: &AddTxRequest) -> Result<TransactionData, ApiError> {
+    if payload
+        .description
+        .as_ref()
+        .is_some_and(|description| description.len() > MAX_DESCRIPTION_LENGTH)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "A description must be at most {MAX_DESCRIPTION_LENGTH} bytes"
+        )));
+    }
+    if let Some(metadata) = &payload.metadata {
+        metadata.validate().map_err(ApiError::BadRequest)?;
+    }
+
+    bcs::from_bytes::<TransactionData>(
         &Base64::decode(&payload.tx_bytes)
             .map_err(|_| ApiError::BadRequest("Invalid base64 transaction bytes".to_string()))?,
     )
-    .map_err(|_| ApiError::BadRequest("Invalid transaction data".to_string()))?;
+    .map_err(|_| ApiError::BadRequest("Invalid transaction data".to_string()))
+}
+
+/// Checks the sender of a submitted transaction and the signature of the submitter, and returns
+/// the submitter.
+async fn verify_submission(
+    state: &ApiState,
+    tx_data: &TransactionData,
+    payload: &AddTxRequest,
+) -> Result<IotaAddress, ApiError> {
+    if let Some(sender_check) = &state.sender_check {
+        if let Some(rejection) = sender_check.rejection(tx_data.sender()).await? {
+            return Err(ApiError::Forbidden(rejection));
+        }
+    }
+
+    let Some(signature) = &payload.signature else {
+        return Err(ApiError::Unauthorized(
+            "Submissions must be signed by a member of the sender account".to_string(),
+        ));
+    };
+    Ok(state
+        .submissions
+        .verify(
+            tx_data.sender(),
+            &tx_data.digest(),
+            payload.description.as_deref(),
+            payload.metadata.as_ref(),
+            signature,
+        )
+        .await?)
+}
+
+async fn add_transaction(
+    State(state): State<ApiState>,
+    Json(payload): Json<AddTxRequest>,
+) -> Result<Json<AddTxResponse>, ApiError> {
+    Ok(Json(store_submission(&state, payload).await?))
+}
+
+/// Validates a signed submission and stores its transaction as a proposal.
+async fn store_submission(
+    state: &ApiState,
+    payload: AddTxRequest,
+) -> Result<AddTxResponse, ApiError> {
+    let tx_data = decode_submission(&payload)?;
+    let submitter = verify_submission(state, &tx_data, &payload).await?;
 
-    let tx_digest = tx_data.digest();
     let now = Utc::now().timestamp() as u64;
 
     let mut conn = state
@@ -96,56 +572,1252 @@ async fn add_transaction(
         .get_connection()
         .map_err(|err| ApiError::Database(err))?;
 
-    queries::insert_transaction(&mut conn, &tx_data, payload.description, now)
+    let (stored, existing) = match queries::insert_or_get_transaction(
+        &mut conn,
+        &tx_data,
+        payload.description,
+        payload.metadata.as_ref(),
+        Some(&submitter),
+        now,
+    )
+    .map_err(|err| ApiError::Database(err))?
+    {
+        InsertOutcome::Inserted(stored) => (stored, false),
+        InsertOutcome::Existing(stored) => (stored, true),
+        InsertOutcome::Conflict(stored) => {
+            return Err(ApiError::Conflict {
+                message: format!(
+                    "Transaction {} is already stored with a different description or metadata",
+                    stored.digest
+                ),
+                stored: Box::new(transaction_response(&mut conn, stored)?),
+            });
+        }
+    };
+
+    Ok(AddTxResponse {
+        digest: stored.digest,
+        added_at: stored.added_at as u64,
+        existing,
+    })
+}
+
+const MAX_BATCH_SIZE: usize = 200;
+const MAX_BATCH_NAME_LENGTH: usize = 200;
+/// Number of batch items verified against the node at the same time.
+const BATCH_VERIFY_CONCURRENCY: usize = 16;
+
+/// Validates all transactions of a batch and stores the valid ones in one database transaction.
+///
+/// Items failing validation are reported as invalid, while node or database failures fail the
+/// whole request so nothing is stored partially.
+async fn add_transactions(
+    State(state): State<ApiState>,
+    Json(payload): Json<AddTxBatchRequest>,
+) -> Result<AddTxBatchResponse, ApiError> {
+    if payload.transactions.is_empty() || payload.transactions.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "A batch must contain between 1 and {MAX_BATCH_SIZE} transactions"
+        )));
+    }
+    if let Some(name) = &payload.name {
+        if name.trim().is_empty() || name.len() > MAX_BATCH_NAME_LENGTH {
+            return Err(ApiError::BadRequest(format!(
+                "A batch name must be between 1 and {MAX_BATCH_NAME_LENGTH} bytes"
+            )));
+        }
+    }
+
+    let invalid = |index: usize, digest: Option<String>, msg: String| BatchItemResult {
+        index,
+        digest,
+        status: BatchItemStatus::Invalid,
+        error: Some(msg),
+    };
+    let mut results = Vec::with_capacity(payload.transactions.len());
+    let mut decoded = Vec::with_capacity(payload.transactions.len());
+    for (index, item) in payload.transactions.into_iter().enumerate() {
+        match decode_submission(&item) {
+            Ok(tx_data) => decoded.push((index, tx_data, item)),
+            Err(ApiError::BadRequest(msg)) => results.push(invalid(index, None, msg)),
+            Err(err) => return Err(err),
+        }
+    }
+
+    // the node lookups of the items are independent, so they run concurrently
+    let verified = stream::iter(decoded)
+        .map(|(index, tx_data, item)| {
+            let state = &state;
+            async move {
+                let verified = verify_submission(state, &tx_data, &item).await;
+                (index, tx_data, item, verified)
+            }
+        })
+        .buffered(BATCH_VERIFY_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut items = Vec::with_capacity(verified.len());
+    for (index, tx_data, item, verified) in verified {
+        match verified {
+            Ok(submitter) => items.push(BatchItem {
+                position: index,
+                tx_data,
+                description: item.description,
+                metadata: item.metadata,
+                submitter,
+            }),
+            Err(
+                ApiError::BadRequest(msg) | ApiError::Unauthorized(msg) | ApiError::Forbidden(msg),
+            ) => results.push(invalid(index, Some(tx_data.digest().to_string()), msg)),
+            Err(err) => return Err(err),
+        }
+    }
+
+    let mut conn = state
+        .pool
+        .get_connection()
         .map_err(|err| ApiError::Database(err))?;
-    Ok(Json(AddTxResponse {
-        digest: tx_digest.to_string(),
-        added_at: now,
-    }))
+    let (batch, outcomes) = queries::insert_batch(
+        &mut conn,
+        payload.name.as_deref(),
+        &items,
+        Utc::now().timestamp() as u64,
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    for (item, outcome) in items.iter().zip(outcomes) {
+        let (status, error) = match outcome {
+            InsertOutcome::Inserted(_) => (BatchItemStatus::Stored, None),
+            InsertOutcome::Existing(_) => (BatchItemStatus::Duplicate, None),
+            InsertOutcome::Conflict(_) => (
+                BatchItemStatus::Conflict,
+                Some("Already stored with a different description or metadata".to_string()),
+            ),
+        };
+        results.push(BatchItemResult {
+            index: item.position,
+            digest: Some(item.tx_data.digest().to_string()),
+            status,
+            error,
+        });
+    }
+    results.sort_by_key(|result| result.index);
+
+    let batch = match batch {
+        Some(batch) => {
+            let size = queries::get_batch_transactions(&mut conn, batch.id)
+                .map_err(|err| ApiError::Database(err))?
+                .len();
+            Some(batch_summary(batch, size as u64))
+        }
+        None => None,
+    };
+    Ok(AddTxBatchResponse { batch, results })
 }
 
-// Only temporary implementation for testing purposes
+fn batch_summary(batch: StoredBatch, size: u64) -> BatchSummary {
+    BatchSummary {
+        id: batch.id as u64,
+        name: batch.name,
+        created_at: batch.created_at as u64,
+        size,
+    }
+}
+
+#[derive(Deserialize)]
+struct BatchesQuery {
+    limit: Option<u64>,
+    #[serde(default)]
+    offset: u64,
+}
+
+/// Lists the named batches, newest first.
+async fn get_batches(
+    State(state): State<ApiState>,
+    Query(query): Query<BatchesQuery>,
+) -> Result<GetBatchesResponse, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "Limit must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+    let (batches, total) = queries::get_batches(
+        &mut conn,
+        limit as i64,
+        query.offset.min(i64::MAX as u64) as i64,
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    Ok(GetBatchesResponse {
+        batches: batches
+            .into_iter()
+            .map(|(batch, size)| batch_summary(batch, size as u64))
+            .collect(),
+        total: total as u64,
+        limit,
+        offset: query.offset,
+    })
+}
+
+#[derive(Deserialize)]
+struct BatchQuery {
+    /// Include the base64 encoded transaction bytes.
+    #[serde(default)]
+    include_bytes: bool,
+}
+
+/// Returns a batch with its transactions.
+async fn get_batch(
+    State(state): State<ApiState>,
+    Path(batch_id): Path<i32>,
+    Query(query): Query<BatchQuery>,
+) -> Result<BatchResponse, ApiError> {
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+    let batch = queries::get_batch(&mut conn, batch_id)
+        .map_err(|err| ApiError::Database(err))?
+        .ok_or_else(|| ApiError::NotFound(format!("Batch {batch_id} not found")))?;
+    let transactions = queries::get_batch_transactions(&mut conn, batch_id)
+        .map_err(|err| ApiError::Database(err))?;
+
+    Ok(BatchResponse {
+        batch: batch_summary(batch, transactions.len() as u64),
+        transactions: transaction_summaries(&mut conn, transactions, query.include_bytes)?,
+    })
+}
+
+async fn build_coin_transfer(
+    State(state): State<ApiState>,
+    Json(payload): Json<TransferCoinRequest>,
+) -> Result<BuiltTransactionResponse, ApiError> {
+    let gas_budget = checked_gas_budget(&payload.options)?;
+    let built = state
+        .payments
+        .pay(
+            payload.options.sender,
+            payload.coin_type.as_deref(),
+            &[Payment {
+                recipient: payload.recipient,
+                amount: payload.amount,
+            }],
+            gas_budget,
+        )
+        .await?;
+    built_transaction_response(&state, &payload.options, built).await
+}
+
+async fn build_payout(
+    State(state): State<ApiState>,
+    Json(payload): Json<PayoutRequest>,
+) -> Result<BuiltTransactionResponse, ApiError> {
+    let gas_budget = checked_gas_budget(&payload.options)?;
+    let built = state
+        .payments
+        .pay(
+            payload.options.sender,
+            payload.coin_type.as_deref(),
+            &payload.payments,
+            gas_budget,
+        )
+        .await?;
+    built_transaction_response(&state, &payload.options, built).await
+}
+
+async fn build_object_transfer(
+    State(state): State<ApiState>,
+    Json(payload): Json<TransferObjectsRequest>,
+) -> Result<BuiltTransactionResponse, ApiError> {
+    let gas_budget = checked_gas_budget(&payload.options)?;
+    let built = state
+        .payments
+        .transfer_objects(
+            payload.options.sender,
+            payload.recipient,
+            &payload.object_ids,
+            gas_budget,
+        )
+        .await?;
+    built_transaction_response(&state, &payload.options, built).await
+}
+
+async fn build_governance(
+    State(state): State<ApiState>,
+    Json(payload): Json<GovernanceRequest>,
+) -> Result<BuiltTransactionResponse, ApiError> {
+    let Some(governance) = &state.governance else {
+        return Err(ApiError::ServiceUnavailable(
+            "Governance transactions can't be built, the service runs without \
+             --package-address"
+                .to_string(),
+        ));
+    };
+    let gas_budget = checked_gas_budget(&payload.options)?;
+    let built = governance
+        .build(payload.options.sender, &payload.operations, gas_budget)
+        .await?;
+    built_transaction_response(&state, &payload.options, built).await
+}
+
+/// The gas budget of a build request, checked against the bounds of the protocol.
+fn checked_gas_budget(options: &BuildOptions) -> Result<u64, ApiError> {
+    let gas_budget = options.gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+    if !(MIN_GAS_BUDGET..=MAX_GAS_BUDGET).contains(&gas_budget) {
+        return Err(ApiError::BadRequest(format!(
+            "The gas budget must be between {MIN_GAS_BUDGET} and {MAX_GAS_BUDGET}"
+        )));
+    }
+    Ok(gas_budget)
+}
+
+/// Encodes a built transaction and stores it if the request is signed.
+///
+/// A member proposes a built transaction by signing the returned digest and description, and
+/// either submitting it to `/add_transaction` or building it again with the signature. Both
+/// store it the same way.
+async fn built_transaction_response(
+    state: &ApiState,
+    options: &BuildOptions,
+    built: BuiltTransaction,
+) -> Result<BuiltTransactionResponse, ApiError> {
+    let tx_data = built.tx_data;
+    let tx_bytes =
+        Base64::encode(bcs::to_bytes(&tx_data).map_err(|err| ApiError::Internal(err.into()))?);
+    let stored = if let Some(signature) = &options.signature {
+        store_submission(
+            state,
+            AddTxRequest {
+                tx_bytes: tx_bytes.clone(),
+                description: Some(built.description.clone()),
+                metadata: None,
+                signature: Some(signature.clone()),
+            },
+        )
+        .await?;
+        true
+    } else {
+        false
+    };
+    Ok(BuiltTransactionResponse {
+        tx_bytes,
+        digest: tx_data.digest().to_string(),
+        description: built.description,
+        gas_budget: tx_data.gas_data().budget,
+        gas_price: tx_data.gas_data().price,
+        stored,
+    })
+}
+
+/// Derives the signature that makes the node call the authenticator of an account with only the
+/// account object.
 async fn derive_auth_signature(
     State(state): State<ApiState>,
     Path(address): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    derive_auth_signature_with_args(
+        State(state),
+        Path(address),
+        Json(DeriveAuthSignatureRequest::default()),
+    )
+    .await
+}
+
+/// Derives the signature of an account whose authenticator takes call arguments after the
+/// account object.
+async fn derive_auth_signature_with_args(
+    State(state): State<ApiState>,
+    Path(address): Path<String>,
+    Json(payload): Json<DeriveAuthSignatureRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     let addr = IotaAddress::from_str(&address)
         .map_err(|_| ApiError::BadRequest("Invalid IOTA address".to_string()))?;
 
-    let iota_client = IotaClientBuilder::default()
-        .build(&state.node_url)
+    let signature = state
+        .objects
+        .account_signature(addr, &payload.call_args)
+        .await?;
+
+    Ok(Json(serde_json::json!({ "signature": vec![signature] })))
+}
+
+/// Maximum length of a comment, in bytes.
+const MAX_COMMENT_LENGTH: usize = 4_000;
+
+#[derive(Deserialize)]
+struct CommentsQuery {
+    limit: Option<u64>,
+    #[serde(default)]
+    offset: u64,
+}
+
+fn comment_response(comment: StoredComment) -> Result<CommentResponse, ApiError> {
+    Ok(CommentResponse {
+        id: comment.id as u64,
+        author: IotaAddress::from_str(&comment.author).map_err(|err| ApiError::Internal(err))?,
+        body: comment.body,
+        concern: comment.concern,
+        signature: comment.signature,
+        created_at: comment.created_at as u64,
+    })
+}
+
+/// Lists the comments on a stored transaction, oldest first.
+async fn get_comments(
+    State(state): State<ApiState>,
+    Path(tx_digest): Path<String>,
+    Query(query): Query<CommentsQuery>,
+) -> Result<GetCommentsResponse, ApiError> {
+    let _ = TransactionDigest::from_str(&tx_digest)
+        .map_err(|_| ApiError::BadRequest("Invalid IOTA transaction digest".to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "Limit must be between 1 and {MAX_PAGE_SIZE}"
+        )));
+    }
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+
+    let (comments, total) = queries::get_comments(
+        &mut conn,
+        &tx_digest,
+        limit as i64,
+        query.offset.min(i64::MAX as u64) as i64,
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    Ok(GetCommentsResponse {
+        comments: comments
+            .into_iter()
+            .map(comment_response)
+            .collect::<Result<_, _>>()?,
+        total: total as u64,
+        limit,
+        offset: query.offset,
+    })
+}
+
+/// Stores a comment of a member of the sender account on a stored transaction.
+///
+/// Posting the same signed comment again returns the stored comment.
+async fn add_comment(
+    State(state): State<ApiState>,
+    Path(tx_digest): Path<String>,
+    Json(payload): Json<AddCommentRequest>,
+) -> Result<CommentResponse, ApiError> {
+    let digest = TransactionDigest::from_str(&tx_digest)
+        .map_err(|_| ApiError::BadRequest("Invalid IOTA transaction digest".to_string()))?;
+    if payload.body.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Comment must not be empty".to_string(),
+        ));
+    }
+    if payload.body.len() > MAX_COMMENT_LENGTH {
+        return Err(ApiError::BadRequest(format!(
+            "Comment must not be longer than {MAX_COMMENT_LENGTH} bytes"
+        )));
+    }
+
+    let sender = {
+        let mut conn = state
+            .pool
+            .get_connection()
+            .map_err(|err| ApiError::Database(err))?;
+        let sender = queries::get_transaction_sender(&mut conn, &tx_digest)
+            .map_err(|err| ApiError::Database(err))?
+            .ok_or_else(|| ApiError::NotFound(format!("Transaction {tx_digest} not found")))?;
+        IotaAddress::from_str(&sender).map_err(|err| ApiError::Internal(err))?
+    };
+
+    let author = state
+        .submissions
+        .verify_message(
+            sender,
+            &submission::comment_message(&digest, &payload.body, payload.concern),
+            &payload.signature,
+        )
+        .await?;
+
+    let mut conn = state
+        .pool
+        .get_connection()
+        .map_err(|err| ApiError::Database(err))?;
+    let (comment, _) = queries::insert_or_get_comment(
+        &mut conn,
+        &tx_digest,
+        &author,
+        &payload.body,
+        payload.concern,
+        &payload.signature,
+        Utc::now().timestamp() as u64,
+    )
+    .map_err(|err| ApiError::Database(err))?;
+
+    comment_response(comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::response::IntoResponse;
+    use iota_types::{
+        base_types::{ObjectID, random_object_ref},
+        crypto::{AccountKeyPair, get_key_pair},
+        gas_coin::GAS,
+    };
+
+    use super::*;
+    use crate::{
+        auth::ObjectResolver,
+        builder::PaymentBuilder,
+        db::pool::{DbConnectionPool, DbConnectionPoolConfig},
+        execute::ExecutionOutcome,
+        node::tests::MockNode,
+        simulate::Simulator,
+        submission::{
+            SubmissionVerifier, comment_message,
+            tests::{sign_message, sign_submission},
+        },
+    };
+
+    /// State in which `member` is the only member of the account at the zero address.
+    fn test_state(member: IotaAddress) -> ApiState {
+        test_state_with(MockNode::default().with_members(IotaAddress::ZERO, vec![member]))
+    }
+
+    fn test_state_with(node: MockNode) -> ApiState {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        let node = Arc::new(node);
+        ApiState {
+            pool,
+            payments: Arc::new(PaymentBuilder::new(node.clone())),
+            governance: None,
+            objects: Arc::new(ObjectResolver::new(node.clone())),
+            simulator: Arc::new(Simulator::new(node.clone())),
+            submissions: Arc::new(SubmissionVerifier::new(node.clone())),
+            node,
+            sender_check: None,
+        }
+    }
+
+    fn tx_data() -> TransactionData {
+        TransactionData::new_transfer_iota(
+            IotaAddress::ZERO,
+            IotaAddress::ZERO,
+            Some(1),
+            random_object_ref(),
+            1_000_000,
+            1_000,
+        )
+    }
+
+    fn request(
+        tx_data: &TransactionData,
+        description: Option<&str>,
+        keypair: &AccountKeyPair,
+    ) -> AddTxRequest {
+        AddTxRequest {
+            tx_bytes: Base64::encode(bcs::to_bytes(tx_data).unwrap()),
+            description: description.map(str::to_string),
+            metadata: None,
+            signature: Some(sign_submission(
+                keypair,
+                &tx_data.digest(),
+                description,
+                None,
+            )),
+        }
+    }
+
+    fn request_with_metadata(
+        tx_data: &TransactionData,
+        metadata: ProposalMetadata,
+        keypair: &AccountKeyPair,
+    ) -> AddTxRequest {
+        AddTxRequest {
+            signature: Some(sign_submission(
+                keypair,
+                &tx_data.digest(),
+                Some("payment"),
+                Some(&metadata),
+            )),
+            metadata: Some(metadata),
+            ..request(tx_data, Some("payment"), keypair)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness_without_node() {
+        let state = test_state(IotaAddress::ZERO);
+
+        let status = status(&state).await;
+        assert!(status.database.ok);
+        assert!(!status.node.ok);
+        assert!(status.node.error.is_some());
+        assert!(!status.ready);
+
+        let response = get_readiness(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_add_transaction_is_idempotent() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let tx_data = tx_data();
+
+        let Json(first) = add_transaction(
+            State(state.clone()),
+            Json(request(&tx_data, Some("pay rent"), &keypair)),
+        )
         .await
-        .map_err(|err| ApiError::Internal(anyhow::anyhow!(err)))?;
+        .unwrap();
+        assert!(!first.existing);
 
-    let resp = iota_client
-        .read_api()
-        .get_object_with_options(addr.into(), IotaObjectDataOptions::new().with_owner())
+        let Json(second) = add_transaction(
+            State(state),
+            Json(request(&tx_data, Some("pay rent"), &keypair)),
+        )
         .await
-        .map_err(|err| ApiError::Internal(anyhow::anyhow!(err)))?;
+        .unwrap();
+        assert!(second.existing);
+        assert_eq!(second.digest, first.digest);
+        assert_eq!(second.added_at, first.added_at);
+    }
 
-    let initial_shared_version = match resp.data.unwrap().owner {
-        Some(Shared {
-            initial_shared_version,
-            ..
-        }) => initial_shared_version,
-        _ => {
-            return Err(ApiError::BadRequest(
-                "The provided address is not a shared object id".to_string(),
+    #[tokio::test]
+    async fn test_add_transaction_conflicting_description() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let tx_data = tx_data();
+
+        add_transaction(
+            State(state.clone()),
+            Json(request(&tx_data, Some("pay rent"), &keypair)),
+        )
+        .await
+        .unwrap();
+
+        match add_transaction(
+            State(state),
+            Json(request(&tx_data, Some("pay more rent"), &keypair)),
+        )
+        .await
+        {
+            Err(ApiError::Conflict { stored, .. }) => {
+                assert_eq!(stored.description.as_deref(), Some("pay rent"));
+                assert_eq!(stored.sender, IotaAddress::ZERO);
+                assert_eq!(stored.submitter, Some(member));
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_transaction_requires_member_signature() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let (_, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let tx_data = tx_data();
+
+        let unsigned = AddTxRequest {
+            signature: None,
+            ..request(&tx_data, Some("pay rent"), &keypair)
+        };
+        assert!(matches!(
+            add_transaction(State(state.clone()), Json(unsigned)).await,
+            Err(ApiError::Unauthorized(_))
+        ));
+
+        // a valid signature over another description
+        let mut misleading = request(&tx_data, Some("pay rent"), &keypair);
+        misleading.description = Some("harmless cleanup".to_string());
+        assert!(matches!(
+            add_transaction(State(state.clone()), Json(misleading)).await,
+            Err(ApiError::Unauthorized(_))
+        ));
+
+        assert!(matches!(
+            add_transaction(
+                State(state),
+                Json(request(&tx_data, Some("pay rent"), &outsider_keypair))
+            )
+            .await,
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_governance_without_package_address() {
+        let request = GovernanceRequest {
+            options: BuildOptions {
+                sender: IotaAddress::ZERO,
+                gas_budget: None,
+                signature: None,
+            },
+            operations: vec![crate::governance::GovernanceOp::SetThreshold { threshold: 1 }],
+        };
+        assert!(matches!(
+            build_governance(State(test_state(IotaAddress::ZERO)), Json(request)).await,
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_built_transaction_is_proposed_like_any_other() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state_with(
+            MockNode::default()
+                .with_members(IotaAddress::ZERO, vec![member])
+                .with_coin(IotaAddress::ZERO, GAS::type_tag(), 1_000_000_000),
+        );
+        let transfer = |gas_budget| TransferCoinRequest {
+            options: BuildOptions {
+                sender: IotaAddress::ZERO,
+                gas_budget,
+                signature: None,
+            },
+            coin_type: None,
+            recipient: member,
+            amount: 1_000,
+        };
+
+        for gas_budget in [0, MAX_GAS_BUDGET + 1] {
+            assert!(matches!(
+                build_coin_transfer(State(state.clone()), Json(transfer(Some(gas_budget)))).await,
+                Err(ApiError::BadRequest(_))
             ));
         }
-    };
 
-    let sigs = vec![GenericSignature::MoveAuthenticator(
-        MoveAuthenticator::new_for_testing(
-            vec![],
-            vec![],
-            CallArg::Object(iota_types::transaction::ObjectArg::SharedObject {
-                id: addr.into(),
-                initial_shared_version: initial_shared_version,
-                mutable: false,
+        let built = build_coin_transfer(State(state.clone()), Json(transfer(None)))
+            .await
+            .unwrap();
+        assert_eq!(built.gas_budget, DEFAULT_GAS_BUDGET);
+        assert!(!built.stored);
+        // building doesn't store anything
+        assert!(
+            get_transaction_by_digest(State(state.clone()), Path(built.digest.clone()))
+                .await
+                .is_err()
+        );
+
+        let tx_data: TransactionData =
+            bcs::from_bytes(&Base64::decode(&built.tx_bytes).unwrap()).unwrap();
+        let unsigned = AddTxRequest {
+            tx_bytes: built.tx_bytes.clone(),
+            description: Some(built.description.clone()),
+            metadata: None,
+            signature: None,
+        };
+        assert!(matches!(
+            add_transaction(State(state.clone()), Json(unsigned)).await,
+            Err(ApiError::Unauthorized(_))
+        ));
+
+        let Json(added) = add_transaction(
+            State(state.clone()),
+            Json(request(&tx_data, Some(&built.description), &keypair)),
+        )
+        .await
+        .unwrap();
+        assert!(!added.existing);
+        assert_eq!(added.digest, built.digest);
+        let stored = get_transaction_by_digest(State(state), Path(built.digest))
+            .await
+            .unwrap();
+        assert_eq!(stored.submitter, Some(member));
+        assert_eq!(stored.description, Some(built.description));
+    }
+
+    #[tokio::test]
+    async fn test_built_transaction_is_stored_with_signature() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let (_, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state_with(
+            MockNode::default()
+                .with_members(IotaAddress::ZERO, vec![member])
+                .with_coin(IotaAddress::ZERO, GAS::type_tag(), 1_000_000_000),
+        );
+        let transfer = |signature| TransferCoinRequest {
+            options: BuildOptions {
+                sender: IotaAddress::ZERO,
+                gas_budget: None,
+                signature,
+            },
+            coin_type: None,
+            recipient: member,
+            amount: 1_000,
+        };
+
+        let built = build_coin_transfer(State(state.clone()), Json(transfer(None)))
+            .await
+            .unwrap();
+        let digest = TransactionDigest::from_str(&built.digest).unwrap();
+        let signature = |keypair: &AccountKeyPair| {
+            sign_submission(keypair, &digest, Some(built.description.as_str()), None)
+        };
+
+        assert!(matches!(
+            build_coin_transfer(
+                State(state.clone()),
+                Json(transfer(Some(signature(&outsider_keypair))))
+            )
+            .await,
+            Err(ApiError::Forbidden(_))
+        ));
+        assert!(
+            get_transaction_by_digest(State(state.clone()), Path(built.digest.clone()))
+                .await
+                .is_err()
+        );
+
+        let signed = build_coin_transfer(
+            State(state.clone()),
+            Json(transfer(Some(signature(&keypair)))),
+        )
+        .await
+        .unwrap();
+        assert!(signed.stored);
+        assert_eq!(signed.digest, built.digest);
+        let stored = get_transaction_by_digest(State(state), Path(built.digest))
+            .await
+            .unwrap();
+        assert_eq!(stored.submitter, Some(member));
+        assert_eq!(stored.description, Some(built.description));
+    }
+
+    /// State with a stored transaction of the account at the zero address, which the node
+    /// executes with `execution`, or can't be reached if `None`.
+    async fn executable(execution: Option<ExecutionOutcome>) -> (ApiState, String) {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state_with(MockNode {
+            execution,
+            ..MockNode::default()
+                .with_members(IotaAddress::ZERO, vec![member])
+                .with_shared(ObjectID::from(IotaAddress::ZERO), 1)
+        });
+        let Json(added) = add_transaction(
+            State(state.clone()),
+            Json(request(&tx_data(), None, &keypair)),
+        )
+        .await
+        .unwrap();
+        (state, added.digest)
+    }
+
+    #[tokio::test]
+    async fn test_execute_transaction() {
+        let (state, digest) = executable(Some(ExecutionOutcome {
+            status: ExecutionStatus::Success,
+            effects_digest: Some("effects".to_string()),
+            error: None,
+            abort: None,
+        }))
+        .await;
+
+        let execution = execute_transaction(State(state.clone()), Path(digest.clone()))
+            .await
+            .unwrap();
+        assert_eq!(execution.status, ExecutionStatus::Success);
+        let stored = get_transaction_by_digest(State(state), Path(digest))
+            .await
+            .unwrap();
+        assert_eq!(stored.execution.unwrap().status, ExecutionStatus::Success);
+        assert_eq!(stored.lifecycle, Lifecycle::Executed);
+    }
+
+    #[tokio::test]
+    async fn test_execute_transaction_without_approvals() {
+        let (state, digest) = executable(Some(ExecutionOutcome::rejected(
+            "MoveAbort(MoveLocation { module: ModuleId { address: 0x1, name: \
+             Identifier(\"dynamic_auth\") }, function: 12, instruction: 27, function_name: \
+             Some(\"authenticate\") }, 9223372054039560455)"
+                .to_string(),
+        )))
+        .await;
+
+        match execute_transaction(State(state.clone()), Path(digest.clone())).await {
+            Err(ApiError::UnprocessableEntity(message)) => {
+                assert!(message.contains("approvals don't reach the threshold"))
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        // the rejection is recorded, the transaction stays pending
+        let stored = get_transaction_by_digest(State(state), Path(digest))
+            .await
+            .unwrap();
+        assert_eq!(stored.execution.unwrap().status, ExecutionStatus::Rejected);
+        assert_eq!(stored.lifecycle, Lifecycle::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_execute_transaction_with_unreachable_node() {
+        let (state, digest) = executable(None).await;
+
+        assert!(matches!(
+            execute_transaction(State(state.clone()), Path(digest.clone())).await,
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+        // nothing is recorded without a verdict of the node
+        let stored = get_transaction_by_digest(State(state), Path(digest))
+            .await
+            .unwrap();
+        assert!(stored.execution.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_transaction_is_not_found() {
+        let state = test_state(IotaAddress::ZERO);
+        let digest = TransactionDigest::random().to_string();
+
+        assert!(matches!(
+            get_transaction_by_digest(State(state.clone()), Path(digest.clone())).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            get_decoded_transaction(State(state.clone()), Path(digest.clone())).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            simulate_transaction(State(state.clone()), Path(digest.clone())).await,
+            Err(ApiError::NotFound(_))
+        ));
+        assert!(matches!(
+            execute_transaction(State(state), Path(digest)).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_derive_auth_signature_of_unknown_account() {
+        let result = derive_auth_signature(
+            State(test_state(IotaAddress::ZERO)),
+            Path(IotaAddress::ZERO.to_string()),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_comments_with_concerns() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let (_, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let tx_data = tx_data();
+        let digest = tx_data.digest();
+
+        let Json(added) = add_transaction(
+            State(state.clone()),
+            Json(request(&tx_data, Some("pay rent"), &keypair)),
+        )
+        .await
+        .unwrap();
+
+        let comment = |body: &str, concern: bool, keypair: &AccountKeyPair| AddCommentRequest {
+            body: body.to_string(),
+            concern,
+            signature: sign_message(keypair, &comment_message(&digest, body, concern)),
+        };
+
+        add_comment(
+            State(state.clone()),
+            Path(added.digest.clone()),
+            Json(comment("looks good", false, &keypair)),
+        )
+        .await
+        .unwrap();
+        let concern = add_comment(
+            State(state.clone()),
+            Path(added.digest.clone()),
+            Json(comment("the amount is too high", true, &keypair)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(concern.author, member);
+
+        // posting the same comment again doesn't duplicate it
+        let again = add_comment(
+            State(state.clone()),
+            Path(added.digest.clone()),
+            Json(comment("the amount is too high", true, &keypair)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(again.id, concern.id);
+
+        assert!(matches!(
+            add_comment(
+                State(state.clone()),
+                Path(added.digest.clone()),
+                Json(comment("approve it now", false, &outsider_keypair)),
+            )
+            .await,
+            Err(ApiError::Forbidden(_))
+        ));
+        let unknown = TransactionDigest::random();
+        assert!(matches!(
+            add_comment(
+                State(state.clone()),
+                Path(unknown.to_string()),
+                Json(comment("looks good", false, &keypair)),
+            )
+            .await,
+            Err(ApiError::NotFound(_))
+        ));
+
+        let comments = get_comments(
+            State(state.clone()),
+            Path(added.digest.clone()),
+            Query(CommentsQuery {
+                limit: Some(1),
+                offset: 1,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(comments.total, 2);
+        assert_eq!(comments.comments.len(), 1);
+        assert_eq!(comments.comments[0].body, "the amount is too high");
+
+        let tx = get_transaction_by_digest(State(state), Path(added.digest))
+            .await
+            .unwrap();
+        assert_eq!(tx.concerns, vec![member]);
+    }
+
+    #[tokio::test]
+    async fn test_add_transactions_reports_per_item_results() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let (_, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let (rent, salary, other) = (tx_data(), tx_data(), tx_data());
+
+        add_transaction(
+            State(state.clone()),
+            Json(request(&rent, Some("rent"), &keypair)),
+        )
+        .await
+        .unwrap();
+
+        let response = add_transactions(
+            State(state.clone()),
+            Json(AddTxBatchRequest {
+                name: Some("payroll".to_string()),
+                transactions: vec![
+                    request(&salary, Some("salary"), &keypair),
+                    request(&rent, Some("rent"), &keypair),
+                    AddTxRequest {
+                        tx_bytes: "not base64!".to_string(),
+                        description: None,
+                        metadata: None,
+                        signature: None,
+                    },
+                    request(&other, Some("other"), &outsider_keypair),
+                    request(&rent, Some("more rent"), &keypair),
+                ],
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            response
+                .results
+                .iter()
+                .map(|result| (result.index, result.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, BatchItemStatus::Stored),
+                (1, BatchItemStatus::Duplicate),
+                (2, BatchItemStatus::Invalid),
+                (3, BatchItemStatus::Invalid),
+                (4, BatchItemStatus::Conflict),
+            ]
+        );
+        assert_eq!(response.results[3].digest, Some(other.digest().to_string()));
+
+        // the conflicting and invalid items are not part of the batch
+        let batch = response.batch.unwrap();
+        assert_eq!((batch.name.as_str(), batch.size), ("payroll", 2));
+        let batches = get_batches(
+            State(state.clone()),
+            Query(BatchesQuery {
+                limit: None,
+                offset: 0,
             }),
-        ),
-    )];
+        )
+        .await
+        .unwrap();
+        assert_eq!(batches.total, 1);
+
+        let batch = get_batch(
+            State(state),
+            Path(batch.id as i32),
+            Query(BatchQuery {
+                include_bytes: false,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            batch
+                .transactions
+                .iter()
+                .map(|tx| tx.digest.clone())
+                .collect::<Vec<_>>(),
+            vec![salary.digest().to_string(), rent.digest().to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_transactions_without_stored_items_creates_no_batch() {
+        let (member, _): (_, AccountKeyPair) = get_key_pair();
+        let (_, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+
+        let response = add_transactions(
+            State(state.clone()),
+            Json(AddTxBatchRequest {
+                name: Some("payroll".to_string()),
+                transactions: vec![
+                    request(&tx_data(), Some("salary"), &outsider_keypair),
+                    request(&tx_data(), Some("bonus"), &outsider_keypair),
+                ],
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(response.batch.is_none());
+        assert!(
+            response
+                .results
+                .iter()
+                .all(|result| result.status == BatchItemStatus::Invalid)
+        );
+
+        let batches = get_batches(
+            State(state),
+            Query(BatchesQuery {
+                limit: None,
+                offset: 0,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(batches.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_transactions_by_metadata() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let state = test_state(member);
+        let (salary, invoice, untagged) = (tx_data(), tx_data(), tx_data());
+
+        for (tx, metadata) in [
+            (
+                &salary,
+                ProposalMetadata {
+                    category: Some(Category::Payroll),
+                    tags: vec!["q4".to_string()],
+                    ..Default::default()
+                },
+            ),
+            (
+                &invoice,
+                ProposalMetadata {
+                    category: Some(Category::VendorPayment),
+                    invoice_ref: Some("INV-42".to_string()),
+                    tags: vec!["q4".to_string(), "hosting".to_string()],
+                    ..Default::default()
+                },
+            ),
+        ] {
+            add_transaction(
+                State(state.clone()),
+                Json(request_with_metadata(tx, metadata, &keypair)),
+            )
+            .await
+            .unwrap();
+        }
+        add_transaction(
+            State(state.clone()),
+            Json(request(&untagged, None, &keypair)),
+        )
+        .await
+        .unwrap();
+
+        let list = |category: Option<Category>, tag: Option<&str>| {
+            get_transactions_by_account(
+                State(state.clone()),
+                Path(IotaAddress::ZERO.to_string()),
+                Query(TransactionsQuery {
+                    limit: None,
+                    offset: 0,
+                    from: None,
+                    to: None,
+                    lifecycle: None,
+                    category,
+                    tag: tag.map(str::to_string),
+                    include_bytes: false,
+                }),
+            )
+        };
+
+        assert_eq!(list(None, None).await.unwrap().total, 3);
+        assert_eq!(list(None, Some("q4")).await.unwrap().total, 2);
+        let vendor = list(Some(Category::VendorPayment), Some("q4"))
+            .await
+            .unwrap();
+        assert_eq!(vendor.total, 1);
+        assert_eq!(vendor.transactions[0].digest, invoice.digest().to_string());
+        let metadata = vendor.transactions[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata.invoice_ref.as_deref(), Some("INV-42"));
+        assert_eq!(metadata.tags, vec!["q4", "hosting"]);
+
+        // the same transaction with other metadata conflicts
+        assert!(matches!(
+            add_transaction(
+                State(state.clone()),
+                Json(request_with_metadata(
+                    &salary,
+                    ProposalMetadata::default(),
+                    &keypair
+                )),
+            )
+            .await,
+            Err(ApiError::Conflict { .. })
+        ));
 
-    Ok(Json(serde_json::json!({ "signature": sigs })))
+        let invalid = request_with_metadata(
+            &tx_data(),
+            ProposalMetadata {
+                tags: vec!["Q4".to_string()],
+                ..Default::default()
+            },
+            &keypair,
+        );
+        assert!(matches!(
+            add_transaction(State(state), Json(invalid)).await,
+            Err(ApiError::BadRequest(_))
+        ));
+    }
 }
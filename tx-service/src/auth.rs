@@ -0,0 +1,270 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derivation of the `MoveAuthenticator` signatures of iSafe accounts.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use fastcrypto::encoding::{Base64, Encoding};
+use iota_types::{
+    base_types::{IotaAddress, ObjectID, SequenceNumber},
+    move_authenticator::MoveAuthenticator,
+    object::Owner,
+    signature::GenericSignature,
+    transaction::{CallArg, ObjectArg},
+};
+use serde::Deserialize;
+
+use crate::node::Node;
+
+#[derive(Debug)]
+pub enum ObjectError {
+    NotFound(ObjectID),
+    NotShared(ObjectID),
+    InvalidArgument(String),
+    Node(anyhow::Error),
+}
+
+impl fmt::Display for ObjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectError::NotFound(id) => write!(f, "Object {id} not found"),
+            ObjectError::NotShared(id) => write!(f, "Object {id} is not a shared object"),
+            ObjectError::InvalidArgument(msg) => write!(f, "Invalid argument: {msg}"),
+            ObjectError::Node(err) => write!(f, "Node request failed: {err}"),
+        }
+    }
+}
+
+/// An argument of the authenticator function, passed after the account object.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AuthCallArg {
+    /// A pure value.
+    Pure {
+        /// Base64 encoded BCS bytes of the value.
+        bcs: String,
+    },
+    /// An object, passed as shared object if it is shared, as its latest version otherwise.
+    Object {
+        object_id: ObjectID,
+        #[serde(default)]
+        mutable: bool,
+    },
+}
+
+/// Resolves objects to call arguments and caches the versions at which objects were shared,
+/// as they never change.
+pub(crate) struct ObjectResolver {
+    reader: Arc<dyn Node>,
+    shared_versions: Mutex<HashMap<ObjectID, SequenceNumber>>,
+}
+
+impl ObjectResolver {
+    pub fn new(reader: Arc<dyn Node>) -> Self {
+        Self {
+            reader,
+            shared_versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the version at which the object was shared.
+    pub async fn initial_shared_version(
+        &self,
+        id: ObjectID,
+    ) -> Result<SequenceNumber, ObjectError> {
+        match self.object_arg(id, false).await? {
+            ObjectArg::SharedObject {
+                initial_shared_version,
+                ..
+            } => Ok(initial_shared_version),
+            _ => Err(ObjectError::NotShared(id)),
+        }
+    }
+
+    async fn object_arg(&self, id: ObjectID, mutable: bool) -> Result<ObjectArg, ObjectError> {
+        if let Some(&initial_shared_version) = self.shared_versions.lock().unwrap().get(&id) {
+            return Ok(ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable,
+            });
+        }
+
+        let (object_ref, owner) = self
+            .reader
+            .object(id)
+            .await
+            .map_err(ObjectError::Node)?
+            .ok_or(ObjectError::NotFound(id))?;
+        Ok(match owner {
+            Owner::Shared {
+                initial_shared_version,
+                ..
+            } => {
+                self.shared_versions
+                    .lock()
+                    .unwrap()
+                    .insert(id, initial_shared_version);
+                ObjectArg::SharedObject {
+                    id,
+                    initial_shared_version,
+                    mutable,
+                }
+            }
+            _ => ObjectArg::ImmOrOwnedObject(object_ref),
+        })
+    }
+
+    /// Builds the signature of an account, which makes the node call the account's authenticator
+    /// with the account object followed by `call_args`.
+    pub async fn account_signature(
+        &self,
+        account: IotaAddress,
+        call_args: &[AuthCallArg],
+    ) -> Result<GenericSignature, ObjectError> {
+        let account_id = ObjectID::from(account);
+        let initial_shared_version = self.initial_shared_version(account_id).await?;
+
+        let mut args = Vec::with_capacity(call_args.len());
+        for arg in call_args {
+            args.push(match arg {
+                AuthCallArg::Pure { bcs } => CallArg::Pure(Base64::decode(bcs).map_err(|_| {
+                    ObjectError::InvalidArgument("pure value is not valid base64".to_string())
+                })?),
+                AuthCallArg::Object { object_id, mutable } => {
+                    CallArg::Object(self.object_arg(*object_id, *mutable).await?)
+                }
+            });
+        }
+
+        Ok(GenericSignature::MoveAuthenticator(
+            MoveAuthenticator::new_v1(
+                args,
+                vec![],
+                CallArg::Object(ObjectArg::SharedObject {
+                    id: account_id,
+                    initial_shared_version,
+                    mutable: false,
+                }),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use iota_types::base_types::random_object_ref;
+
+    use super::*;
+    use crate::node::tests::MockNode;
+
+    #[tokio::test]
+    async fn test_initial_shared_version_is_cached() {
+        let account = ObjectID::random();
+        let node = Arc::new(MockNode::default().with_shared(account, 7));
+        let resolver = ObjectResolver::new(node.clone());
+
+        for _ in 0..3 {
+            assert_eq!(
+                resolver.initial_shared_version(account).await.unwrap(),
+                SequenceNumber::from_u64(7)
+            );
+        }
+        assert_eq!(node.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_owned_objects() {
+        let owned = random_object_ref();
+        let resolver = ObjectResolver::new(Arc::new(
+            MockNode::default().with_owned(owned, IotaAddress::ZERO),
+        ));
+
+        assert!(matches!(
+            resolver
+                .account_signature(IotaAddress::random_for_testing_only(), &[])
+                .await,
+            Err(ObjectError::NotFound(_))
+        ));
+        assert!(matches!(
+            resolver.initial_shared_version(owned.0).await,
+            Err(ObjectError::NotShared(id)) if id == owned.0
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_account_signature_with_call_args() {
+        let account = ObjectID::random();
+        let clock = ObjectID::from_single_byte(6);
+        let owned = random_object_ref();
+        let resolver = ObjectResolver::new(Arc::new(
+            MockNode::default()
+                .with_shared(account, 3)
+                .with_shared(clock, 1)
+                .with_owned(owned, IotaAddress::ZERO),
+        ));
+
+        let signature = resolver
+            .account_signature(
+                account.into(),
+                &[
+                    AuthCallArg::Pure {
+                        bcs: Base64::encode(bcs::to_bytes(&42u64).unwrap()),
+                    },
+                    AuthCallArg::Object {
+                        object_id: clock,
+                        mutable: false,
+                    },
+                    AuthCallArg::Object {
+                        object_id: owned.0,
+                        mutable: false,
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+
+        let expected = GenericSignature::MoveAuthenticator(MoveAuthenticator::new_v1(
+            vec![
+                CallArg::Pure(bcs::to_bytes(&42u64).unwrap()),
+                CallArg::Object(ObjectArg::SharedObject {
+                    id: clock,
+                    initial_shared_version: SequenceNumber::from_u64(1),
+                    mutable: false,
+                }),
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(owned)),
+            ],
+            vec![],
+            CallArg::Object(ObjectArg::SharedObject {
+                id: account,
+                initial_shared_version: SequenceNumber::from_u64(3),
+                mutable: false,
+            }),
+        ));
+        assert_eq!(signature, expected);
+
+        assert!(matches!(
+            resolver
+                .account_signature(
+                    account.into(),
+                    &[AuthCallArg::Pure {
+                        bcs: "not base64!".to_string()
+                    }]
+                )
+                .await,
+            Err(ObjectError::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,424 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Construction of common account payments, so clients don't have to assemble PTBs themselves.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::Result;
+use iota_types::{
+    base_types::{IotaAddress, ObjectID, ObjectRef},
+    gas_coin::GAS,
+    object::Owner,
+    parse_iota_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::TransactionData,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{auth::ObjectError, node::Node};
+
+/// Gas budget used unless the request sets one.
+pub const DEFAULT_GAS_BUDGET: u64 = 10_000_000;
+/// Lowest gas budget accepted, the fixed cost of a transaction at the reference gas price.
+pub const MIN_GAS_BUDGET: u64 = 1_000_000;
+/// Highest gas budget accepted by the protocol.
+pub const MAX_GAS_BUDGET: u64 = 50_000_000_000;
+/// Maximum number of coins used as gas payment.
+const MAX_GAS_OBJECTS: usize = 256;
+/// Maximum number of recipients of a payout or objects of a transfer.
+const MAX_TRANSFERS: usize = 500;
+
+/// A coin owned by the account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnedCoin {
+    pub object_ref: ObjectRef,
+    pub balance: u64,
+}
+
+/// Accepts amounts as JSON numbers or strings, as they may not fit a JSON number.
+pub fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Amount {
+        Number(u64),
+        String(String),
+    }
+    match Amount::deserialize(deserializer)? {
+        Amount::Number(amount) => Ok(amount),
+        Amount::String(amount) => amount.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Payment {
+    pub recipient: IotaAddress,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: u64,
+}
+
+#[derive(Debug)]
+pub enum BuildError {
+    InvalidRequest(String),
+    /// The account doesn't own enough of a coin to cover the payments or the gas budget.
+    InsufficientBalance(String),
+    Object(ObjectError),
+    Node(anyhow::Error),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidRequest(msg) => write!(f, "Invalid request: {msg}"),
+            BuildError::InsufficientBalance(msg) => write!(f, "Insufficient balance: {msg}"),
+            BuildError::Object(err) => err.fmt(f),
+            BuildError::Node(err) => write!(f, "Node request failed: {err}"),
+        }
+    }
+}
+
+impl From<ObjectError> for BuildError {
+    fn from(err: ObjectError) -> Self {
+        BuildError::Object(err)
+    }
+}
+
+/// A built transaction with a description of what it does.
+#[derive(Debug, Clone)]
+pub struct BuiltTransaction {
+    pub tx_data: TransactionData,
+    pub description: String,
+}
+
+/// Builds payments of accounts, paying gas with the IOTA coins of the account.
+pub(crate) struct PaymentBuilder {
+    node: Arc<dyn Node>,
+}
+
+impl PaymentBuilder {
+    pub fn new(node: Arc<dyn Node>) -> Self {
+        Self { node }
+    }
+
+    /// Pays each recipient the amount of `coin_type`, IOTA if `None`.
+    ///
+    /// IOTA is split off the gas coin, other coins are merged and split as needed.
+    pub async fn pay(
+        &self,
+        sender: IotaAddress,
+        coin_type: Option<&str>,
+        payments: &[Payment],
+        gas_budget: u64,
+    ) -> Result<BuiltTransaction, BuildError> {
+        if payments.is_empty() || payments.len() > MAX_TRANSFERS {
+            return Err(BuildError::InvalidRequest(format!(
+                "between 1 and {MAX_TRANSFERS} payments are required"
+            )));
+        }
+        if payments.iter().any(|payment| payment.amount == 0) {
+            return Err(BuildError::InvalidRequest(
+                "amounts must be positive".to_string(),
+            ));
+        }
+        let total = payments
+            .iter()
+            .try_fold(0u64, |total, payment| total.checked_add(payment.amount))
+            .ok_or_else(|| BuildError::InvalidRequest("total amount overflows".to_string()))?;
+        let coin_type = match coin_type {
+            Some(coin_type) => parse_iota_type_tag(coin_type).map_err(|err| {
+                BuildError::InvalidRequest(format!("invalid coin type {coin_type}: {err}"))
+            })?,
+            None => GAS::type_tag(),
+        };
+
+        let recipients = payments.iter().map(|payment| payment.recipient).collect();
+        let amounts = payments.iter().map(|payment| payment.amount).collect();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let gas_payment = if coin_type == GAS::type_tag() {
+            builder
+                .pay_iota(recipients, amounts)
+                .map_err(BuildError::Node)?;
+            let required = total
+                .checked_add(gas_budget)
+                .ok_or_else(|| BuildError::InvalidRequest("total amount overflows".to_string()))?;
+            self.gas_payment(sender, required, &[]).await?
+        } else {
+            let coins = self
+                .node
+                .coins(sender, &coin_type)
+                .await
+                .map_err(BuildError::Node)?;
+            let coins = select_coins(coins, total, usize::MAX).ok_or_else(|| {
+                BuildError::InsufficientBalance(format!(
+                    "{sender} owns less than {total} of {coin_type}"
+                ))
+            })?;
+            builder
+                .pay(coins, recipients, amounts)
+                .map_err(BuildError::Node)?;
+            self.gas_payment(sender, gas_budget, &[]).await?
+        };
+
+        let description = match payments {
+            [payment] => format!(
+                "Pay {} of {coin_type} to {}",
+                payment.amount, payment.recipient
+            ),
+            _ => format!(
+                "Pay {total} of {coin_type} to {} recipients",
+                payments.len()
+            ),
+        };
+        self.finish(sender, builder, gas_payment, gas_budget, description)
+            .await
+    }
+
+    /// Transfers objects owned by the account to the recipient.
+    pub async fn transfer_objects(
+        &self,
+        sender: IotaAddress,
+        recipient: IotaAddress,
+        object_ids: &[ObjectID],
+        gas_budget: u64,
+    ) -> Result<BuiltTransaction, BuildError> {
+        if object_ids.is_empty() || object_ids.len() > MAX_TRANSFERS {
+            return Err(BuildError::InvalidRequest(format!(
+                "between 1 and {MAX_TRANSFERS} objects are required"
+            )));
+        }
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        for &id in object_ids {
+            let (object_ref, owner) = self
+                .node
+                .object(id)
+                .await
+                .map_err(BuildError::Node)?
+                .ok_or(ObjectError::NotFound(id))?;
+            if owner != Owner::AddressOwner(sender) {
+                return Err(BuildError::InvalidRequest(format!(
+                    "object {id} is not owned by {sender}"
+                )));
+            }
+            builder
+                .transfer_object(recipient, object_ref)
+                .map_err(|err| BuildError::InvalidRequest(err.to_string()))?;
+        }
+
+        // transferred coins can't pay for gas
+        let gas_payment = self.gas_payment(sender, gas_budget, object_ids).await?;
+        let description = format!("Transfer {} objects to {recipient}", object_ids.len());
+        self.finish(sender, builder, gas_payment, gas_budget, description)
+            .await
+    }
+
+    /// Selects IOTA coins of the account covering `required`.
+    pub async fn gas_payment(
+        &self,
+        sender: IotaAddress,
+        required: u64,
+        exclude: &[ObjectID],
+    ) -> Result<Vec<ObjectRef>, BuildError> {
+        let coins = self
+            .node
+            .coins(sender, &GAS::type_tag())
+            .await
+            .map_err(BuildError::Node)?
+            .into_iter()
+            .filter(|coin| !exclude.contains(&coin.object_ref.0))
+            .collect();
+        select_coins(coins, required, MAX_GAS_OBJECTS).ok_or_else(|| {
+            BuildError::InsufficientBalance(format!(
+                "{sender} owns less than {required} IOTA in at most {MAX_GAS_OBJECTS} coins"
+            ))
+        })
+    }
+
+    /// Finishes the transaction at the reference gas price.
+    pub async fn finish(
+        &self,
+        sender: IotaAddress,
+        builder: ProgrammableTransactionBuilder,
+        gas_payment: Vec<ObjectRef>,
+        gas_budget: u64,
+        description: String,
+    ) -> Result<BuiltTransaction, BuildError> {
+        let gas_price = self
+            .node
+            .reference_gas_price()
+            .await
+            .map_err(BuildError::Node)?;
+        Ok(BuiltTransaction {
+            tx_data: TransactionData::new_programmable(
+                sender,
+                gas_payment,
+                builder.finish(),
+                gas_budget,
+                gas_price,
+            ),
+            description,
+        })
+    }
+}
+
+/// Selects the largest coins until their balance covers `required`, `None` if `max_coins` coins
+/// don't suffice.
+fn select_coins(
+    mut coins: Vec<OwnedCoin>,
+    required: u64,
+    max_coins: usize,
+) -> Option<Vec<ObjectRef>> {
+    coins.sort_by(|a, b| b.balance.cmp(&a.balance));
+
+    let mut selected = Vec::new();
+    let mut balance = 0u64;
+    for coin in coins.into_iter().take(max_coins) {
+        if balance >= required && !selected.is_empty() {
+            break;
+        }
+        balance = balance.saturating_add(coin.balance);
+        selected.push(coin.object_ref);
+    }
+    (balance >= required && !selected.is_empty()).then_some(selected)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use iota_types::{
+        base_types::random_object_ref,
+        transaction::{Command, TransactionDataAPI, TransactionKind},
+    };
+
+    use super::*;
+    use crate::node::tests::MockNode;
+
+    pub(crate) fn commands(tx_data: &TransactionData) -> Vec<Command> {
+        match tx_data.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => pt.commands.clone(),
+            _ => panic!("not a programmable transaction"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_iota_payout_is_split_off_the_gas_coins() {
+        let account = IotaAddress::random_for_testing_only();
+        let builder = PaymentBuilder::new(Arc::new(
+            MockNode::default()
+                .with_coin(account, GAS::type_tag(), 2_000_000_000)
+                .with_coin(account, GAS::type_tag(), 10),
+        ));
+        let payments = [
+            Payment {
+                recipient: IotaAddress::random_for_testing_only(),
+                amount: 1_000_000_000,
+            },
+            Payment {
+                recipient: IotaAddress::random_for_testing_only(),
+                amount: 500_000_000,
+            },
+        ];
+
+        let built = builder
+            .pay(account, None, &payments, DEFAULT_GAS_BUDGET)
+            .await
+            .unwrap();
+        assert_eq!(built.tx_data.sender(), account);
+        assert_eq!(built.tx_data.gas_data().payment.len(), 1);
+        assert_eq!(built.tx_data.gas_data().price, 1_000);
+        assert!(matches!(
+            commands(&built.tx_data)[0],
+            Command::SplitCoins(..)
+        ));
+        assert!(built.description.contains("2 recipients"));
+
+        assert!(matches!(
+            builder
+                .pay(account, None, &payments[..1], 2_000_000_000)
+                .await,
+            Err(BuildError::InsufficientBalance(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_coin_payment_merges_coins() {
+        let account = IotaAddress::random_for_testing_only();
+        let coin_type = parse_iota_type_tag("0x42::token::TOKEN").unwrap();
+        let builder = PaymentBuilder::new(Arc::new(
+            MockNode::default()
+                .with_coin(account, GAS::type_tag(), DEFAULT_GAS_BUDGET)
+                .with_coin(account, coin_type.clone(), 60)
+                .with_coin(account, coin_type.clone(), 50),
+        ));
+        let payment = Payment {
+            recipient: IotaAddress::random_for_testing_only(),
+            amount: 100,
+        };
+
+        let built = builder
+            .pay(
+                account,
+                Some("0x42::token::TOKEN"),
+                &[payment],
+                DEFAULT_GAS_BUDGET,
+            )
+            .await
+            .unwrap();
+        assert!(
+            commands(&built.tx_data)
+                .iter()
+                .any(|command| matches!(command, Command::MergeCoins(..)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transfer_objects_requires_ownership() {
+        let account = IotaAddress::random_for_testing_only();
+        let owned = random_object_ref();
+        let foreign = random_object_ref();
+        let mut node = MockNode::default().with_coin(account, GAS::type_tag(), DEFAULT_GAS_BUDGET);
+        node.objects
+            .insert(owned.0, (owned, Owner::AddressOwner(account)));
+        node.objects
+            .insert(foreign.0, (foreign, Owner::AddressOwner(IotaAddress::ZERO)));
+        let builder = PaymentBuilder::new(Arc::new(node));
+        let recipient = IotaAddress::random_for_testing_only();
+
+        let built = builder
+            .transfer_objects(account, recipient, &[owned.0], DEFAULT_GAS_BUDGET)
+            .await
+            .unwrap();
+        assert_eq!(commands(&built.tx_data).len(), 1);
+
+        assert!(matches!(
+            builder
+                .transfer_objects(account, recipient, &[foreign.0], DEFAULT_GAS_BUDGET)
+                .await,
+            Err(BuildError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            builder
+                .transfer_objects(
+                    account,
+                    recipient,
+                    &[ObjectID::random()],
+                    DEFAULT_GAS_BUDGET
+                )
+                .await,
+            Err(BuildError::Object(ObjectError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_select_coins() {
+        let coin = |balance| OwnedCoin {
+            object_ref: random_object_ref(),
+            balance,
+        };
+        let coins = vec![coin(1), coin(5), coin(3)];
+
+        let selected = select_coins(coins.clone(), 7, usize::MAX).unwrap();
+        assert_eq!(selected, vec![coins[1].object_ref, coins[2].object_ref]);
+        assert!(select_coins(coins.clone(), 10, usize::MAX).is_none());
+        assert!(select_coins(coins, 7, 1).is_none());
+    }
+}
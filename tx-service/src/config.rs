@@ -0,0 +1,22 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The network presets of the settings.
+//!
+//! The settings are layered as described in [`isafe_common::config`], their environment variables
+//! are prefixed with `TX_SERVICE_`, e.g. `TX_SERVICE_NODE_URL`.
+
+pub use isafe_common::config::{LayeredConfig, Network, PACKAGE_ADDRESS_ENV};
+
+/// The prefix of the environment variables of the settings.
+pub const ENV_PREFIX: &str = "TX_SERVICE_";
+
+/// The settings a network implies for the service, by option name.
+pub fn preset(network: Network) -> &'static [(&'static str, &'static str)] {
+    match network {
+        Network::Mainnet => &[("node-url", "https://api.mainnet.iota.cafe")],
+        Network::Testnet => &[("node-url", "https://api.testnet.iota.cafe")],
+        Network::Devnet => &[("node-url", "https://api.devnet.iota.cafe")],
+        Network::Localnet => &[("node-url", "http://localhost:9000")],
+    }
+}
@@ -1,22 +1,34 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use anyhow::Result;
+use chrono::NaiveDate;
 use diesel::{
-    AggregateExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+    AggregateExpressionMethods, BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
     SelectableHelper, SqliteConnection, TextExpressionMethods, dsl, insert_into, sql_types::Date,
     update,
 };
 use fastcrypto::encoding::{Base64, Encoding};
 use iota_types::{
-    base_types::IotaAddress, transaction::TransactionData, transaction::TransactionDataAPI,
+    base_types::{IotaAddress, ObjectID},
+    transaction::TransactionData,
+    transaction::TransactionDataAPI,
 };
 
-use crate::db::schema::{StoredTransaction, transactions};
+use crate::{
+    db::schema::{
+        StoredBatch, StoredComment, StoredMetadata, StoredTransaction, batch_transactions,
+        batches, comments, service_state, transaction_metadata, transaction_tags, transactions,
+    },
+    execute::{ExecutionOutcome, ExecutionStatus},
+    lifecycle::Lifecycle,
+    metadata::{Category, ProposalMetadata},
+};
 
 pub fn insert_transaction(
     conn: &mut SqliteConnection,
     tx_data: &TransactionData,
     description: Option<String>,
+    submitter: Option<&IotaAddress>,
     at: u64,
 ) -> Result<()> {
     let sender = tx_data.sender();
@@ -29,34 +41,710 @@ pub fn insert_transaction(
             transactions::tx_data.eq(encoded_tx_base64),
             transactions::added_at.eq(at as i64),
             transactions::description.eq(description),
+            transactions::submitter.eq(submitter.map(IotaAddress::to_string)),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Outcome of [`insert_or_get_transaction`].
+#[derive(Debug, PartialEq)]
+pub enum InsertOutcome {
+    /// The transaction was stored.
+    Inserted(StoredTransaction),
+    /// The same transaction with the same description was already stored.
+    Existing(StoredTransaction),
+    /// The transaction was already stored with a different description.
+    Conflict(StoredTransaction),
+}
+
+/// Stores a transaction unless it is already stored, in which case the stored version is returned.
+///
+/// The submitter of the stored version is kept. A stored version with another description or
+/// metadata is a conflict.
+pub fn insert_or_get_transaction(
+    conn: &mut SqliteConnection,
+    tx_data: &TransactionData,
+    description: Option<String>,
+    metadata: Option<&ProposalMetadata>,
+    submitter: Option<&IotaAddress>,
+    at: u64,
+) -> Result<InsertOutcome> {
+    // immediate, so concurrent submissions of the same transaction don't both try to insert
+    conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+        insert_or_get_in_transaction(conn, tx_data, description, metadata, submitter, at)
+    })
+}
+
+/// [`insert_or_get_transaction`] within a surrounding immediate transaction.
+fn insert_or_get_in_transaction(
+    conn: &mut SqliteConnection,
+    tx_data: &TransactionData,
+    description: Option<String>,
+    metadata: Option<&ProposalMetadata>,
+    submitter: Option<&IotaAddress>,
+    at: u64,
+) -> Result<InsertOutcome> {
+    // empty metadata signs like no metadata, so it is stored like none
+    let metadata = metadata.filter(|metadata| **metadata != ProposalMetadata::default());
+    let digest = tx_data.digest().to_string();
+    let existing = transactions::table
+        .filter(transactions::digest.eq(&digest))
+        .get_result::<StoredTransaction>(conn)
+        .optional()?;
+
+    if let Some(existing) = existing {
+        let same = existing.description == description
+            && get_metadata(conn, &digest)?.as_ref() == metadata;
+        return Ok(if same {
+            InsertOutcome::Existing(existing)
+        } else {
+            InsertOutcome::Conflict(existing)
+        });
+    }
+
+    insert_transaction(conn, tx_data, description, submitter, at)?;
+    if let Some(metadata) = metadata {
+        insert_metadata(conn, &digest, metadata)?;
+    }
+    Ok(InsertOutcome::Inserted(
+        transactions::table
+            .filter(transactions::digest.eq(&digest))
+            .get_result(conn)?,
+    ))
+}
+
+fn insert_metadata(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    metadata: &ProposalMetadata,
+) -> Result<()> {
+    insert_into(transaction_metadata::table)
+        .values((
+            transaction_metadata::digest.eq(digest),
+            transaction_metadata::category.eq(metadata.category.map(|c| c.to_string())),
+            transaction_metadata::invoice_ref.eq(&metadata.invoice_ref),
+            transaction_metadata::ticket_id.eq(&metadata.ticket_id),
+            transaction_metadata::due_date.eq(metadata.due_date.map(|d| d.to_string())),
         ))
         .execute(conn)?;
+    for (position, tag) in metadata.tags.iter().enumerate() {
+        insert_into(transaction_tags::table)
+            .values((
+                transaction_tags::digest.eq(digest),
+                transaction_tags::tag.eq(tag),
+                transaction_tags::position.eq(position as i32),
+            ))
+            .execute(conn)?;
+    }
     Ok(())
 }
 
+/// Returns the metadata of a transaction, `None` if it was stored without.
+pub fn get_metadata(
+    conn: &mut SqliteConnection,
+    digest: &str,
+) -> Result<Option<ProposalMetadata>> {
+    Ok(get_metadata_of(conn, &[digest.to_string()])?.remove(digest))
+}
+
+/// Returns the metadata of the transactions that have metadata, by digest.
+pub fn get_metadata_of(
+    conn: &mut SqliteConnection,
+    digests: &[String],
+) -> Result<HashMap<String, ProposalMetadata>> {
+    let stored = transaction_metadata::table
+        .filter(transaction_metadata::digest.eq_any(digests))
+        .load::<StoredMetadata>(conn)?;
+    let tags = transaction_tags::table
+        .filter(transaction_tags::digest.eq_any(digests))
+        .order((transaction_tags::digest.asc(), transaction_tags::position.asc()))
+        .select((transaction_tags::digest, transaction_tags::tag))
+        .load::<(String, String)>(conn)?;
+
+    let mut metadata = HashMap::new();
+    for stored in stored {
+        metadata.insert(
+            stored.digest,
+            ProposalMetadata {
+                category: stored.category.as_deref().map(Category::from_str).transpose()?,
+                invoice_ref: stored.invoice_ref,
+                tags: Vec::new(),
+                ticket_id: stored.ticket_id,
+                due_date: stored
+                    .due_date
+                    .as_deref()
+                    .map(str::parse::<NaiveDate>)
+                    .transpose()?,
+            },
+        );
+    }
+    for (digest, tag) in tags {
+        if let Some(metadata) = metadata.get_mut(&digest) {
+            metadata.tags.push(tag);
+        }
+    }
+    Ok(metadata)
+}
+
+/// A validated item of a batch submission.
+pub struct BatchItem {
+    /// The index of the item in the submitted batch.
+    pub position: usize,
+    pub tx_data: TransactionData,
+    pub description: Option<String>,
+    pub metadata: Option<ProposalMetadata>,
+    pub submitter: IotaAddress,
+}
+
+/// Stores the items of a batch in one transaction, so either all or none of them are stored.
+///
+/// With a `name`, the stored and already existing items are grouped under a new batch.
+/// Conflicting items are not part of the batch, and no batch is created without any item.
+pub fn insert_batch(
+    conn: &mut SqliteConnection,
+    name: Option<&str>,
+    items: &[BatchItem],
+    at: u64,
+) -> Result<(Option<StoredBatch>, Vec<InsertOutcome>)> {
+    conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+        let mut outcomes = Vec::with_capacity(items.len());
+        for item in items {
+            outcomes.push(insert_or_get_in_transaction(
+                conn,
+                &item.tx_data,
+                item.description.clone(),
+                item.metadata.as_ref(),
+                Some(&item.submitter),
+                at,
+            )?);
+        }
+
+        let Some(name) = name else {
+            return Ok((None, outcomes));
+        };
+        let grouped_any = outcomes.iter().any(|outcome| {
+            matches!(outcome, InsertOutcome::Inserted(_) | InsertOutcome::Existing(_))
+        });
+        if !grouped_any {
+            return Ok((None, outcomes));
+        }
+        let batch = insert_into(batches::table)
+            .values((batches::name.eq(name), batches::created_at.eq(at as i64)))
+            .get_result::<StoredBatch>(conn)?;
+        let mut grouped = Vec::new();
+        for (item, outcome) in items.iter().zip(&outcomes) {
+            let (InsertOutcome::Inserted(stored) | InsertOutcome::Existing(stored)) = outcome
+            else {
+                continue;
+            };
+            // the same transaction may be submitted twice within a batch
+            if grouped.contains(&stored.digest) {
+                continue;
+            }
+            insert_into(batch_transactions::table)
+                .values((
+                    batch_transactions::batch_id.eq(batch.id),
+                    batch_transactions::digest.eq(&stored.digest),
+                    batch_transactions::position.eq(item.position as i32),
+                ))
+                .execute(conn)?;
+            grouped.push(stored.digest.clone());
+        }
+        Ok((Some(batch), outcomes))
+    })
+}
+
+/// Returns a page of the batches, newest first, with the number of transactions of each, and
+/// the total number of batches.
+pub fn get_batches(
+    conn: &mut SqliteConnection,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<(StoredBatch, i64)>, i64)> {
+    let total = batches::table.count().get_result(conn)?;
+    let results = batches::table
+        .order((batches::created_at.desc(), batches::id.desc()))
+        .limit(limit)
+        .offset(offset)
+        .load::<StoredBatch>(conn)?;
+
+    let mut batches_with_size = Vec::with_capacity(results.len());
+    for batch in results {
+        let size = batch_transactions::table
+            .filter(batch_transactions::batch_id.eq(batch.id))
+            .count()
+            .get_result(conn)?;
+        batches_with_size.push((batch, size));
+    }
+    Ok((batches_with_size, total))
+}
+
+pub fn get_batch(conn: &mut SqliteConnection, id: i32) -> Result<Option<StoredBatch>> {
+    Ok(batches::table
+        .filter(batches::id.eq(id))
+        .get_result(conn)
+        .optional()?)
+}
+
+/// Returns the transactions of a batch in the order they were submitted.
+pub fn get_batch_transactions(
+    conn: &mut SqliteConnection,
+    batch_id: i32,
+) -> Result<Vec<StoredTransaction>> {
+    Ok(batch_transactions::table
+        .inner_join(transactions::table)
+        .filter(batch_transactions::batch_id.eq(batch_id))
+        .order(batch_transactions::position.asc())
+        .select(StoredTransaction::as_select())
+        .load(conn)?)
+}
+
+/// Returns the sender of a stored transaction, `None` if it isn't stored.
+pub fn get_transaction_sender(conn: &mut SqliteConnection, digest: &str) -> Result<Option<String>> {
+    Ok(transactions::table
+        .filter(transactions::digest.eq(digest))
+        .select(transactions::sender)
+        .get_result(conn)
+        .optional()?)
+}
+
+/// Returns a stored transaction, `None` if it isn't stored.
 pub fn get_transaction_by_digest(
     conn: &mut SqliteConnection,
     digest: &str,
-) -> Result<StoredTransaction> {
-    let result = transactions::table
+) -> Result<Option<StoredTransaction>> {
+    Ok(transactions::table
         .filter(transactions::digest.eq(digest))
         .get_result(conn)
-        .optional()?;
-    if let Some(stored) = result {
-        // we return the base64 encoded string directly
-        Ok(stored)
-    } else {
-        Err(anyhow::anyhow!("Transaction not found"))
+        .optional()?)
+}
+
+/// Records the outcome of an execution of a stored transaction.
+///
+/// A successful execution also moves the transaction to the executed lifecycle.
+pub fn set_execution_outcome(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    outcome: &ExecutionOutcome,
+    at: u64,
+) -> Result<()> {
+    update(transactions::table.filter(transactions::digest.eq(digest)))
+        .set((
+            transactions::execution_status.eq(outcome.status.to_string()),
+            transactions::effects_digest.eq(&outcome.effects_digest),
+            transactions::execution_error.eq(&outcome.error),
+            transactions::executed_at.eq(at as i64),
+        ))
+        .execute(conn)?;
+    if outcome.status == ExecutionStatus::Success {
+        set_lifecycle(conn, digest, Lifecycle::Executed, at)?;
     }
+    Ok(())
+}
+
+/// Moves a pending transaction to `lifecycle`, and returns whether it was stored and pending.
+///
+/// A transaction that left the pending lifecycle is not stale anymore, the stale mark is cleared.
+pub fn set_lifecycle(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    lifecycle: Lifecycle,
+    at: u64,
+) -> Result<bool> {
+    let updated = update(
+        transactions::table
+            .filter(transactions::digest.eq(digest))
+            .filter(transactions::lifecycle.eq(Lifecycle::Pending.to_string())),
+    )
+    .set((
+        transactions::lifecycle.eq(lifecycle.to_string()),
+        transactions::lifecycle_at.eq(at as i64),
+        transactions::stale_at.eq(None::<i64>),
+        transactions::stale_objects.eq(None::<String>),
+    ))
+    .execute(conn)?;
+    Ok(updated > 0)
+}
+
+/// Drops the payload of transactions that left the pending lifecycle at or before `before`,
+/// keeping the rest of the row as a tombstone, and returns the number of pruned transactions.
+pub fn prune_transactions(conn: &mut SqliteConnection, before: u64, at: u64) -> Result<usize> {
+    Ok(update(
+        transactions::table
+            .filter(transactions::lifecycle.ne(Lifecycle::Pending.to_string()))
+            .filter(transactions::lifecycle_at.le(before as i64))
+            .filter(transactions::pruned_at.is_null()),
+    )
+    .set((
+        transactions::tx_data.eq(""),
+        transactions::pruned_at.eq(at as i64),
+    ))
+    .execute(conn)?)
+}
+
+pub fn get_service_state(conn: &mut SqliteConnection, key: &str) -> Result<Option<String>> {
+    Ok(service_state::table
+        .filter(service_state::key.eq(key))
+        .select(service_state::value)
+        .get_result(conn)
+        .optional()?)
+}
+
+pub fn set_service_state(conn: &mut SqliteConnection, key: &str, value: &str) -> Result<()> {
+    insert_into(service_state::table)
+        .values((service_state::key.eq(key), service_state::value.eq(value)))
+        .on_conflict(service_state::key)
+        .do_update()
+        .set(service_state::value.eq(value))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Filter and pagination of [`get_transactions_by_sender`].
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    /// Only transactions added at or after this time, in seconds.
+    pub from: Option<u64>,
+    /// Only transactions added at or before this time, in seconds.
+    pub to: Option<u64>,
+    /// Only transactions in this lifecycle.
+    pub lifecycle: Option<Lifecycle>,
+    /// Only transactions with metadata of this category.
+    pub category: Option<Category>,
+    /// Only transactions with metadata carrying this tag.
+    pub tag: Option<String>,
+    pub limit: i64,
+    pub offset: i64,
 }
 
+/// Returns a page of the transactions of a sender, newest first, and the total number of
+/// transactions matching the filter.
 pub fn get_transactions_by_sender(
     conn: &mut SqliteConnection,
     sender: &IotaAddress,
-) -> Result<Vec<String>> {
-    let results = transactions::table
-        .filter(transactions::sender.eq(sender.to_string()))
-        .select(transactions::tx_data)
+    filter: &TransactionFilter,
+) -> Result<(Vec<StoredTransaction>, i64)> {
+    let query = || {
+        let mut query = transactions::table
+            .filter(transactions::sender.eq(sender.to_string()))
+            .into_boxed();
+        if let Some(from) = filter.from {
+            query = query.filter(transactions::added_at.ge(from as i64));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(transactions::added_at.le(to as i64));
+        }
+        if let Some(lifecycle) = filter.lifecycle {
+            query = query.filter(transactions::lifecycle.eq(lifecycle.to_string()));
+        }
+        if let Some(category) = filter.category {
+            query = query.filter(
+                transactions::digest.eq_any(
+                    transaction_metadata::table
+                        .filter(transaction_metadata::category.eq(category.to_string()))
+                        .select(transaction_metadata::digest),
+                ),
+            );
+        }
+        if let Some(tag) = &filter.tag {
+            query = query.filter(
+                transactions::digest.eq_any(
+                    transaction_tags::table
+                        .filter(transaction_tags::tag.eq(tag.clone()))
+                        .select(transaction_tags::digest),
+                ),
+            );
+        }
+        query
+    };
+
+    let total = query().count().get_result(conn)?;
+    let results = query()
+        .order((transactions::added_at.desc(), transactions::digest.asc()))
+        .limit(filter.limit)
+        .offset(filter.offset)
+        .load::<StoredTransaction>(conn)?;
+    Ok((results, total))
+}
+
+/// Returns the transactions that may still be executed: neither executed, removed nor known to
+/// be stale.
+///
+/// Rejected transactions are included, the node rejects before anything is consumed.
+/// Returns the digest and the Base64 encoded transaction data of the pending transactions that
+/// are not stale, executed or failed yet.
+pub fn get_pending_transactions(conn: &mut SqliteConnection) -> Result<Vec<(String, String)>> {
+    Ok(transactions::table
+        .filter(transactions::lifecycle.eq(Lifecycle::Pending.to_string()))
+        .filter(transactions::stale_at.is_null())
+        .filter(
+            transactions::execution_status
+                .is_null()
+                .or(transactions::execution_status.eq(ExecutionStatus::Rejected.to_string())),
+        )
+        .order(transactions::added_at.asc())
+        .select((transactions::digest, transactions::tx_data))
+        .load::<(String, String)>(conn)?)
+}
+
+/// Marks a pending transaction as stale because the given input objects moved to other
+/// versions.
+pub fn set_stale(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    moved_objects: &[ObjectID],
+    at: u64,
+) -> Result<()> {
+    update(
+        transactions::table
+            .filter(transactions::digest.eq(digest))
+            .filter(transactions::lifecycle.eq(Lifecycle::Pending.to_string())),
+    )
+        .set((
+            transactions::stale_at.eq(at as i64),
+            transactions::stale_objects.eq(serde_json::to_string(moved_objects)?),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Returns a page of the pending stale transactions, optionally of a single sender, most
+/// recently detected first, and the total number of stale transactions matching the filter.
+pub fn get_stale_transactions(
+    conn: &mut SqliteConnection,
+    sender: Option<&IotaAddress>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<StoredTransaction>, i64)> {
+    let query = || {
+        let mut query = transactions::table
+            .filter(transactions::lifecycle.eq(Lifecycle::Pending.to_string()))
+            .filter(transactions::stale_at.is_not_null())
+            .into_boxed();
+        if let Some(sender) = sender {
+            query = query.filter(transactions::sender.eq(sender.to_string()));
+        }
+        query
+    };
+
+    let total = query().count().get_result(conn)?;
+    let results = query()
+        .order((transactions::stale_at.desc(), transactions::digest.asc()))
+        .limit(limit)
+        .offset(offset)
+        .load::<StoredTransaction>(conn)?;
+    Ok((results, total))
+}
+
+/// Stores a comment unless the author already posted the same signed comment on the transaction,
+/// and returns the stored comment and whether it already existed.
+pub fn insert_or_get_comment(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    author: &IotaAddress,
+    body: &str,
+    concern: bool,
+    signature: &str,
+    at: u64,
+) -> Result<(StoredComment, bool)> {
+    conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+        let existing = comments::table
+            .filter(comments::digest.eq(digest))
+            .filter(comments::author.eq(author.to_string()))
+            .filter(comments::signature.eq(signature))
+            .get_result::<StoredComment>(conn)
+            .optional()?;
+        if let Some(existing) = existing {
+            return Ok((existing, true));
+        }
+
+        let stored = insert_into(comments::table)
+            .values((
+                comments::digest.eq(digest),
+                comments::author.eq(author.to_string()),
+                comments::body.eq(body),
+                comments::concern.eq(concern),
+                comments::signature.eq(signature),
+                comments::created_at.eq(at as i64),
+            ))
+            .get_result::<StoredComment>(conn)?;
+        Ok((stored, false))
+    })
+}
+
+/// Returns a page of the comments on a transaction, oldest first, and the total number of
+/// comments on it.
+pub fn get_comments(
+    conn: &mut SqliteConnection,
+    digest: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<StoredComment>, i64)> {
+    let total = comments::table
+        .filter(comments::digest.eq(digest))
+        .count()
+        .get_result(conn)?;
+    let results = comments::table
+        .filter(comments::digest.eq(digest))
+        .order((comments::created_at.asc(), comments::id.asc()))
+        .limit(limit)
+        .offset(offset)
+        .load::<StoredComment>(conn)?;
+    Ok((results, total))
+}
+
+/// Returns the members that flagged a concern in a comment on the transaction, in the order
+/// of their first concern.
+pub fn get_concerned_members(conn: &mut SqliteConnection, digest: &str) -> Result<Vec<String>> {
+    let authors = comments::table
+        .filter(comments::digest.eq(digest))
+        .filter(comments::concern.eq(true))
+        .order(comments::id.asc())
+        .select(comments::author)
         .load::<String>(conn)?;
-    Ok(results)
+
+    let mut concerned = Vec::new();
+    for author in authors {
+        if !concerned.contains(&author) {
+            concerned.push(author);
+        }
+    }
+    Ok(concerned)
+}
+
+#[cfg(test)]
+mod tests {
+    use iota_types::base_types::random_object_ref;
+
+    use super::*;
+    use crate::db::pool::{DbConnectionPool, DbConnectionPoolConfig};
+
+    /// Stores a transaction of `sender` added at `at` and returns its digest.
+    fn store(conn: &mut SqliteConnection, sender: IotaAddress, at: u64) -> String {
+        let tx_data = TransactionData::new_transfer_iota(
+            IotaAddress::ZERO,
+            sender,
+            Some(1),
+            random_object_ref(),
+            1_000_000,
+            1_000,
+        );
+        insert_transaction(conn, &tx_data, None, None, at).unwrap();
+        tx_data.digest().to_string()
+    }
+
+    #[test]
+    fn test_transactions_by_sender_filters() {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        let mut conn = pool.get_connection().unwrap();
+
+        let sender = IotaAddress::random_for_testing_only();
+        let digests: Vec<_> = (1..=5).map(|at| store(&mut conn, sender, at * 100)).collect();
+        store(&mut conn, IotaAddress::random_for_testing_only(), 300);
+        set_lifecycle(&mut conn, &digests[1], Lifecycle::Executed, 1_000).unwrap();
+        set_lifecycle(&mut conn, &digests[3], Lifecycle::Removed, 1_000).unwrap();
+
+        let mut list = |filter: TransactionFilter| {
+            let (transactions, total) =
+                get_transactions_by_sender(&mut conn, &sender, &filter).unwrap();
+            let digests: Vec<_> = transactions.into_iter().map(|tx| tx.digest).collect();
+            (digests, total)
+        };
+        let unlimited = || TransactionFilter {
+            limit: 100,
+            ..Default::default()
+        };
+
+        // newest first, other senders excluded
+        let (all, total) = list(unlimited());
+        assert_eq!(total, 5);
+        assert_eq!(all, digests.iter().rev().cloned().collect::<Vec<_>>());
+
+        // the bounds are inclusive
+        let (range, total) = list(TransactionFilter {
+            from: Some(200),
+            to: Some(400),
+            ..unlimited()
+        });
+        assert_eq!(total, 3);
+        assert_eq!(
+            range,
+            vec![digests[3].clone(), digests[2].clone(), digests[1].clone()]
+        );
+
+        let (pending, total) = list(TransactionFilter {
+            lifecycle: Some(Lifecycle::Pending),
+            ..unlimited()
+        });
+        assert_eq!(total, 3);
+        assert_eq!(
+            pending,
+            vec![digests[4].clone(), digests[2].clone(), digests[0].clone()]
+        );
+        let (executed, _) = list(TransactionFilter {
+            lifecycle: Some(Lifecycle::Executed),
+            from: Some(300),
+            ..unlimited()
+        });
+        assert!(executed.is_empty());
+
+        // the total counts all matches, not only the page
+        let (page, total) = list(TransactionFilter {
+            limit: 2,
+            offset: 1,
+            ..Default::default()
+        });
+        assert_eq!(total, 5);
+        assert_eq!(page, vec![digests[3].clone(), digests[2].clone()]);
+        let (beyond, total) = list(TransactionFilter {
+            limit: 2,
+            offset: 5,
+            ..Default::default()
+        });
+        assert_eq!(total, 5);
+        assert!(beyond.is_empty());
+    }
+
+    #[test]
+    fn test_comments_are_deduplicated_per_transaction_and_author() {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        let mut conn = pool.get_connection().unwrap();
+
+        let sender = IotaAddress::random_for_testing_only();
+        let [first, second] = [100, 200].map(|at| store(&mut conn, sender, at));
+        let [alice, bob] = [(); 2].map(|_| IotaAddress::random_for_testing_only());
+        let mut comment = |digest: &str, author: &IotaAddress| {
+            insert_or_get_comment(&mut conn, digest, author, "ok", false, "signature", 300).unwrap()
+        };
+
+        let (stored, existed) = comment(&first, &alice);
+        assert!(!existed);
+        let (again, existed) = comment(&first, &alice);
+        assert!(existed);
+        assert_eq!(again.id, stored.id);
+        // the same signature of another author or on another transaction is a new comment
+        assert!(!comment(&first, &bob).1);
+        assert!(!comment(&second, &alice).1);
+        assert_eq!(get_comments(&mut conn, &first, 10, 0).unwrap().1, 2);
+
+        assert_eq!(get_transaction_sender(&mut conn, &first).unwrap(), Some(sender.to_string()));
+        assert_eq!(get_transaction_sender(&mut conn, "unknown").unwrap(), None);
+    }
 }
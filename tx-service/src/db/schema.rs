@@ -11,6 +11,100 @@ pub struct StoredTransaction {
     pub added_at: i64,
     pub tx_data: String,
     pub description: Option<String>,
+    pub submitter: Option<String>,
+    pub execution_status: Option<String>,
+    pub effects_digest: Option<String>,
+    pub execution_error: Option<String>,
+    pub executed_at: Option<i64>,
+    pub stale_at: Option<i64>,
+    pub stale_objects: Option<String>,
+    pub lifecycle: String,
+    pub lifecycle_at: Option<i64>,
+    pub pruned_at: Option<i64>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, PartialEq, Debug)]
+#[diesel(table_name = comments)]
+pub struct StoredComment {
+    pub id: i32,
+    pub digest: String,
+    pub author: String,
+    pub body: String,
+    pub concern: bool,
+    pub signature: String,
+    pub created_at: i64,
+}
+
+#[derive(Queryable, Selectable, Identifiable, PartialEq, Debug)]
+#[diesel(table_name = batches)]
+pub struct StoredBatch {
+    pub id: i32,
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Queryable, Selectable, Identifiable, PartialEq, Debug)]
+#[diesel(table_name = transaction_metadata)]
+#[diesel(primary_key(digest))]
+pub struct StoredMetadata {
+    pub digest: String,
+    pub category: Option<String>,
+    pub invoice_ref: Option<String>,
+    pub ticket_id: Option<String>,
+    pub due_date: Option<String>,
+}
+
+diesel::table! {
+    batch_transactions (batch_id, digest) {
+        batch_id -> Integer,
+        digest -> Text,
+        position -> Integer,
+    }
+}
+
+diesel::table! {
+    batches (id) {
+        id -> Integer,
+        name -> Text,
+        created_at -> Int8,
+    }
+}
+
+diesel::table! {
+    comments (id) {
+        id -> Integer,
+        digest -> Text,
+        author -> Text,
+        body -> Text,
+        concern -> Bool,
+        signature -> Text,
+        created_at -> Int8,
+    }
+}
+
+diesel::table! {
+    service_state (key) {
+        key -> Text,
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    transaction_metadata (digest) {
+        digest -> Text,
+        category -> Nullable<Text>,
+        invoice_ref -> Nullable<Text>,
+        ticket_id -> Nullable<Text>,
+        due_date -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    transaction_tags (digest, tag) {
+        digest -> Text,
+        tag -> Text,
+        position -> Integer,
+    }
 }
 
 diesel::table! {
@@ -20,5 +114,31 @@ diesel::table! {
         added_at -> Int8,
         tx_data -> Text,
         description -> Nullable<Text>,
+        submitter -> Nullable<Text>,
+        execution_status -> Nullable<Text>,
+        effects_digest -> Nullable<Text>,
+        execution_error -> Nullable<Text>,
+        executed_at -> Nullable<Int8>,
+        stale_at -> Nullable<Int8>,
+        stale_objects -> Nullable<Text>,
+        lifecycle -> Text,
+        lifecycle_at -> Nullable<Int8>,
+        pruned_at -> Nullable<Int8>,
     }
 }
+
+diesel::joinable!(batch_transactions -> batches (batch_id));
+diesel::joinable!(batch_transactions -> transactions (digest));
+diesel::joinable!(comments -> transactions (digest));
+diesel::joinable!(transaction_metadata -> transactions (digest));
+diesel::joinable!(transaction_tags -> transactions (digest));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    batch_transactions,
+    batches,
+    comments,
+    service_state,
+    transaction_metadata,
+    transaction_tags,
+    transactions,
+);
@@ -0,0 +1,624 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human-readable rendering of stored `TransactionData`.
+
+use std::{collections::HashMap, fmt};
+
+use anyhow::{Result, anyhow, bail};
+use fastcrypto::encoding::{Encoding, Hex};
+use iota_types::{
+    TypeTag,
+    base_types::{IotaAddress, ObjectID, ObjectRef},
+    transaction::{
+        Argument, CallArg, Command, ObjectArg, TransactionData, TransactionDataAPI,
+        TransactionExpiration, TransactionKind,
+    },
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tracing::debug;
+
+use crate::node::Node;
+
+/// A Move type as far as it matters for decoding pure arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MoveType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    Vector(Box<MoveType>),
+    Struct {
+        address: ObjectID,
+        module: String,
+        name: String,
+        type_arguments: Vec<MoveType>,
+    },
+    TypeParameter(u16),
+    Reference(Box<MoveType>),
+}
+
+impl MoveType {
+    /// Parses the JSON-RPC representation of a normalized Move type.
+    pub(crate) fn from_normalized_json(value: &Value) -> Result<Self> {
+        if let Some(primitive) = value.as_str() {
+            return Ok(match primitive {
+                "Bool" => Self::Bool,
+                "U8" => Self::U8,
+                "U16" => Self::U16,
+                "U32" => Self::U32,
+                "U64" => Self::U64,
+                "U128" => Self::U128,
+                "U256" => Self::U256,
+                "Address" => Self::Address,
+                "Signer" => Self::Signer,
+                other => bail!("unknown normalized type {other}"),
+            });
+        }
+        let (kind, inner) = value
+            .as_object()
+            .and_then(|object| object.iter().next())
+            .ok_or_else(|| anyhow!("invalid normalized type {value}"))?;
+        Ok(match kind.as_str() {
+            "Vector" => Self::Vector(Box::new(Self::from_normalized_json(inner)?)),
+            "Reference" | "MutableReference" => {
+                Self::Reference(Box::new(Self::from_normalized_json(inner)?))
+            }
+            "TypeParameter" => Self::TypeParameter(
+                inner
+                    .as_u64()
+                    .ok_or_else(|| anyhow!("invalid type parameter {inner}"))?
+                    as u16,
+            ),
+            "Struct" => {
+                let field = |name: &str| {
+                    inner[name]
+                        .as_str()
+                        .ok_or_else(|| anyhow!("struct type without `{name}`"))
+                };
+                Self::Struct {
+                    address: ObjectID::from_hex_literal(field("address")?)?,
+                    module: field("module")?.to_string(),
+                    name: field("name")?.to_string(),
+                    type_arguments: inner["typeArguments"]
+                        .as_array()
+                        .map(|args| args.iter().map(Self::from_normalized_json).collect())
+                        .transpose()?
+                        .unwrap_or_default(),
+                }
+            }
+            other => bail!("unknown normalized type {other}"),
+        })
+    }
+
+    /// Replaces type parameters with the type arguments of a call.
+    fn instantiate(self, type_arguments: &[TypeTag]) -> Self {
+        match self {
+            Self::TypeParameter(index) => type_arguments
+                .get(index as usize)
+                .map(Self::from)
+                .unwrap_or(self),
+            Self::Vector(inner) => Self::Vector(Box::new(inner.instantiate(type_arguments))),
+            Self::Reference(inner) => Self::Reference(Box::new(inner.instantiate(type_arguments))),
+            Self::Struct {
+                address,
+                module,
+                name,
+                type_arguments: args,
+            } => Self::Struct {
+                address,
+                module,
+                name,
+                type_arguments: args
+                    .into_iter()
+                    .map(|arg| arg.instantiate(type_arguments))
+                    .collect(),
+            },
+            other => other,
+        }
+    }
+
+    fn is_struct(&self, address: u8, module: &str, name: &str) -> bool {
+        matches!(self, Self::Struct { address: a, module: m, name: n, .. }
+            if *a == ObjectID::from_single_byte(address) && m == module && n == name)
+    }
+}
+
+impl From<&TypeTag> for MoveType {
+    fn from(tag: &TypeTag) -> Self {
+        match tag {
+            TypeTag::Bool => Self::Bool,
+            TypeTag::U8 => Self::U8,
+            TypeTag::U16 => Self::U16,
+            TypeTag::U32 => Self::U32,
+            TypeTag::U64 => Self::U64,
+            TypeTag::U128 => Self::U128,
+            TypeTag::U256 => Self::U256,
+            TypeTag::Address => Self::Address,
+            TypeTag::Signer => Self::Signer,
+            TypeTag::Vector(inner) => Self::Vector(Box::new(Self::from(inner.as_ref()))),
+            TypeTag::Struct(tag) => Self::Struct {
+                address: ObjectID::from(tag.address),
+                module: tag.module.to_string(),
+                name: tag.name.to_string(),
+                type_arguments: tag.type_params.iter().map(Self::from).collect(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for MoveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool => write!(f, "bool"),
+            Self::U8 => write!(f, "u8"),
+            Self::U16 => write!(f, "u16"),
+            Self::U32 => write!(f, "u32"),
+            Self::U64 => write!(f, "u64"),
+            Self::U128 => write!(f, "u128"),
+            Self::U256 => write!(f, "u256"),
+            Self::Address => write!(f, "address"),
+            Self::Signer => write!(f, "signer"),
+            Self::Vector(inner) => write!(f, "vector<{inner}>"),
+            Self::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                write!(f, "{}::{module}::{name}", address.to_hex_literal())?;
+                if !type_arguments.is_empty() {
+                    let args = type_arguments
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+                    write!(f, "<{}>", args.join(", "))?;
+                }
+                Ok(())
+            }
+            Self::TypeParameter(index) => write!(f, "T{index}"),
+            Self::Reference(inner) => write!(f, "&{inner}"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedObjectRef {
+    pub object_id: ObjectID,
+    pub version: u64,
+    pub digest: String,
+}
+
+impl From<&ObjectRef> for DecodedObjectRef {
+    fn from((object_id, version, digest): &ObjectRef) -> Self {
+        Self {
+            object_id: *object_id,
+            version: version.value(),
+            digest: digest.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all_fields = "camelCase")]
+pub enum DecodedInput {
+    Pure {
+        /// Hex encoded BCS bytes.
+        bytes: String,
+        /// The Move type the value was decoded as, if it could be determined.
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        type_: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        value: Option<Value>,
+    },
+    ImmOrOwnedObject(DecodedObjectRef),
+    SharedObject {
+        object_id: ObjectID,
+        initial_shared_version: u64,
+        mutable: bool,
+    },
+    Receiving(DecodedObjectRef),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all_fields = "camelCase")]
+pub enum DecodedCommand {
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<String>,
+        arguments: Vec<Argument>,
+    },
+    TransferObjects {
+        objects: Vec<Argument>,
+        recipient: Argument,
+    },
+    SplitCoins {
+        coin: Argument,
+        amounts: Vec<Argument>,
+    },
+    MergeCoins {
+        destination: Argument,
+        sources: Vec<Argument>,
+    },
+    MakeMoveVec {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        type_argument: Option<String>,
+        elements: Vec<Argument>,
+    },
+    Publish {
+        modules: usize,
+        dependencies: Vec<ObjectID>,
+    },
+    Upgrade {
+        modules: usize,
+        dependencies: Vec<ObjectID>,
+        package: ObjectID,
+        ticket: Argument,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedGasData {
+    pub payment: Vec<DecodedObjectRef>,
+    pub owner: IotaAddress,
+    pub price: u64,
+    pub budget: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedTransaction {
+    pub digest: String,
+    pub sender: IotaAddress,
+    pub inputs: Vec<DecodedInput>,
+    pub commands: Vec<DecodedCommand>,
+    pub gas_data: DecodedGasData,
+    pub expiration: TransactionExpiration,
+}
+
+/// Renders a programmable transaction, decoding pure inputs with the types of the parameters they
+/// are passed to.
+pub(crate) async fn decode_transaction(
+    tx_data: &TransactionData,
+    node: &dyn Node,
+) -> Result<DecodedTransaction> {
+    let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+        bail!("only programmable transactions can be decoded");
+    };
+
+    // the first use of a pure input determines its type
+    let mut pure_types: HashMap<u16, MoveType> = HashMap::new();
+    let mut assign = |argument: &Argument, ty: MoveType| {
+        if let Argument::Input(index) = argument {
+            pure_types.entry(*index).or_insert(ty);
+        }
+    };
+    let mut signatures: HashMap<(ObjectID, String, String), Option<Vec<MoveType>>> = HashMap::new();
+    let mut commands = Vec::with_capacity(pt.commands.len());
+
+    for command in &pt.commands {
+        commands.push(match command {
+            Command::MoveCall(call) => {
+                let key = (
+                    call.package,
+                    call.module.to_string(),
+                    call.function.to_string(),
+                );
+                if !signatures.contains_key(&key) {
+                    let parameters = node
+                        .parameter_types(call.package, &key.1, &key.2)
+                        .await
+                        .inspect_err(|e| {
+                            debug!("resolving {}::{}::{} failed: {e}", key.0, key.1, key.2)
+                        })
+                        .ok();
+                    signatures.insert(key.clone(), parameters);
+                }
+                if let Some(parameters) = &signatures[&key] {
+                    for (argument, parameter) in call.arguments.iter().zip(parameters) {
+                        assign(
+                            argument,
+                            parameter.clone().instantiate(&call.type_arguments),
+                        );
+                    }
+                }
+                DecodedCommand::MoveCall {
+                    package: call.package,
+                    module: key.1,
+                    function: key.2,
+                    type_arguments: call
+                        .type_arguments
+                        .iter()
+                        .map(|tag| MoveType::from(tag).to_string())
+                        .collect(),
+                    arguments: call.arguments.clone(),
+                }
+            }
+            Command::TransferObjects(objects, recipient) => {
+                assign(recipient, MoveType::Address);
+                DecodedCommand::TransferObjects {
+                    objects: objects.clone(),
+                    recipient: *recipient,
+                }
+            }
+            Command::SplitCoins(coin, amounts) => {
+                for amount in amounts {
+                    assign(amount, MoveType::U64);
+                }
+                DecodedCommand::SplitCoins {
+                    coin: *coin,
+                    amounts: amounts.clone(),
+                }
+            }
+            Command::MergeCoins(destination, sources) => DecodedCommand::MergeCoins {
+                destination: *destination,
+                sources: sources.clone(),
+            },
+            Command::MakeMoveVec(type_argument, elements) => {
+                if let Some(tag) = type_argument {
+                    for element in elements {
+                        assign(element, MoveType::from(tag));
+                    }
+                }
+                DecodedCommand::MakeMoveVec {
+                    type_argument: type_argument
+                        .as_ref()
+                        .map(|tag| MoveType::from(tag).to_string()),
+                    elements: elements.clone(),
+                }
+            }
+            Command::Publish(modules, dependencies) => DecodedCommand::Publish {
+                modules: modules.len(),
+                dependencies: dependencies.clone(),
+            },
+            Command::Upgrade(modules, dependencies, package, ticket) => DecodedCommand::Upgrade {
+                modules: modules.len(),
+                dependencies: dependencies.clone(),
+                package: *package,
+                ticket: *ticket,
+            },
+        });
+    }
+
+    let inputs = pt
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| match input {
+            CallArg::Pure(bytes) => {
+                let ty = pure_types.get(&(index as u16)).map(|ty| match ty {
+                    MoveType::Reference(inner) => inner.as_ref(),
+                    ty => ty,
+                });
+                DecodedInput::Pure {
+                    bytes: Hex::encode_with_format(bytes),
+                    type_: ty.map(ToString::to_string),
+                    value: ty.and_then(|ty| decode_pure(ty, bytes)),
+                }
+            }
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref)) => {
+                DecodedInput::ImmOrOwnedObject(object_ref.into())
+            }
+            CallArg::Object(ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable,
+            }) => DecodedInput::SharedObject {
+                object_id: *id,
+                initial_shared_version: initial_shared_version.value(),
+                mutable: *mutable,
+            },
+            CallArg::Object(ObjectArg::Receiving(object_ref)) => {
+                DecodedInput::Receiving(object_ref.into())
+            }
+        })
+        .collect();
+
+    let gas_data = tx_data.gas_data();
+    Ok(DecodedTransaction {
+        digest: tx_data.digest().to_string(),
+        sender: tx_data.sender(),
+        inputs,
+        commands,
+        gas_data: DecodedGasData {
+            payment: gas_data.payment.iter().map(Into::into).collect(),
+            owner: gas_data.owner,
+            price: gas_data.price,
+            budget: gas_data.budget,
+        },
+        expiration: tx_data.expiration().clone(),
+    })
+}
+
+/// Decodes the BCS bytes of a pure value, returns `None` if they don't match the type.
+fn decode_pure(ty: &MoveType, bytes: &[u8]) -> Option<Value> {
+    let mut input = bytes;
+    let value = decode_value(ty, &mut input)?;
+    input.is_empty().then_some(value)
+}
+
+fn decode_value(ty: &MoveType, input: &mut &[u8]) -> Option<Value> {
+    Some(match ty {
+        MoveType::Bool => match take(input, 1)? {
+            [0] => json!(false),
+            [1] => json!(true),
+            _ => return None,
+        },
+        MoveType::U8 => json!(take(input, 1)?[0]),
+        MoveType::U16 => json!(u16::from_le_bytes(take(input, 2)?.try_into().ok()?)),
+        MoveType::U32 => json!(u32::from_le_bytes(take(input, 4)?.try_into().ok()?)),
+        // large integers as strings, like the node JSON-RPC does
+        MoveType::U64 => json!(u64::from_le_bytes(take(input, 8)?.try_into().ok()?).to_string()),
+        MoveType::U128 => {
+            json!(u128::from_le_bytes(take(input, 16)?.try_into().ok()?).to_string())
+        }
+        MoveType::U256 => json!(u256_to_string(take(input, 32)?)),
+        MoveType::Address => json!(IotaAddress::from_bytes(take(input, 32)?).ok()?),
+        MoveType::Vector(inner) if **inner == MoveType::U8 => {
+            let len = uleb128(input)?;
+            json!(Hex::encode_with_format(take(input, len)?))
+        }
+        MoveType::Vector(inner) => {
+            let len = uleb128(input)?;
+            Value::Array(
+                (0..len)
+                    .map(|_| decode_value(inner, input))
+                    .collect::<Option<_>>()?,
+            )
+        }
+        ty if ty.is_struct(1, "string", "String") || ty.is_struct(1, "ascii", "String") => {
+            let len = uleb128(input)?;
+            json!(std::str::from_utf8(take(input, len)?).ok()?)
+        }
+        ty if ty.is_struct(2, "object", "ID") => {
+            json!(IotaAddress::from_bytes(take(input, 32)?).ok()?)
+        }
+        MoveType::Struct { type_arguments, .. }
+            if ty.is_struct(1, "option", "Option") && type_arguments.len() == 1 =>
+        {
+            match uleb128(input)? {
+                0 => Value::Null,
+                1 => decode_value(&type_arguments[0], input)?,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Some(head)
+}
+
+fn uleb128(input: &mut &[u8]) -> Option<usize> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(input, 1)?[0];
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return usize::try_from(value).ok();
+        }
+    }
+    None
+}
+
+/// Renders a little endian 256 bit integer in decimal.
+fn u256_to_string(le_bytes: &[u8]) -> String {
+    let mut digits = Vec::new();
+    let mut number: Vec<u8> = le_bytes.iter().rev().copied().collect();
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u16;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | *byte as u16;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("digits are ascii")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_type() -> MoveType {
+        MoveType::Struct {
+            address: ObjectID::from_single_byte(1),
+            module: "string".to_string(),
+            name: "String".to_string(),
+            type_arguments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_decode_pure() {
+        let address = IotaAddress::random_for_testing_only();
+
+        assert_eq!(
+            decode_pure(&MoveType::U64, &bcs::to_bytes(&42u64).unwrap()),
+            Some(json!("42"))
+        );
+        assert_eq!(
+            decode_pure(&MoveType::Address, &bcs::to_bytes(&address).unwrap()),
+            Some(json!(address))
+        );
+        assert_eq!(
+            decode_pure(&string_type(), &bcs::to_bytes("invoice 42").unwrap()),
+            Some(json!("invoice 42"))
+        );
+        assert_eq!(
+            decode_pure(
+                &MoveType::Vector(Box::new(MoveType::U16)),
+                &bcs::to_bytes(&vec![1u16, 2]).unwrap()
+            ),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(
+            decode_pure(
+                &MoveType::Struct {
+                    address: ObjectID::from_single_byte(1),
+                    module: "option".to_string(),
+                    name: "Option".to_string(),
+                    type_arguments: vec![MoveType::U8],
+                },
+                &bcs::to_bytes(&Some(7u8)).unwrap()
+            ),
+            Some(json!(7))
+        );
+        assert_eq!(
+            decode_pure(&MoveType::U256, &[0xff; 32]),
+            Some(json!(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ))
+        );
+        // trailing bytes don't match the type
+        assert_eq!(
+            decode_pure(&MoveType::U8, &bcs::to_bytes(&42u64).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_normalized_type() {
+        let ty = MoveType::from_normalized_json(&json!({
+            "Vector": {
+                "Struct": {
+                    "address": "0x1",
+                    "module": "option",
+                    "name": "Option",
+                    "typeArguments": [{ "TypeParameter": 0 }]
+                }
+            }
+        }))
+        .unwrap()
+        .instantiate(&[TypeTag::U64]);
+
+        assert_eq!(
+            ty.to_string(),
+            format!(
+                "vector<{}::option::Option<u64>>",
+                ObjectID::from_single_byte(1).to_hex_literal()
+            )
+        );
+    }
+}
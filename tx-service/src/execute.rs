@@ -0,0 +1,247 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Execution of approved transactions on behalf of iSafe accounts.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use iota_json_rpc_types::{
+    IotaExecutionStatus, IotaTransactionBlockEffectsAPI, IotaTransactionBlockResponse,
+};
+use iota_types::{
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    execution_status::{self, ExecutionFailureStatus},
+    message_envelope::Message,
+    transaction::{Transaction, TransactionData, TransactionDataAPI},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{ObjectError, ObjectResolver},
+    lifecycle::DYNAMIC_AUTH_MODULE,
+    node::Node,
+};
+
+/// Error code of the `dynamic_auth` authenticator abort if the approvals don't reach the
+/// threshold, `ETransactionDoesNotHaveSufficientApprovals`.
+const INSUFFICIENT_APPROVALS_CODE: u64 = 4;
+/// Tag bit of the abort code of a clever error, which carries the error code in bits 32 to 47.
+const CLEVER_ERROR_TAG: u64 = 1 << 63;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    /// Executed successfully.
+    Success,
+    /// Executed, but the execution failed, e.g. because of an abort in a called function.
+    Failure,
+    /// Not executed, the node rejected the transaction, e.g. because the authenticator aborted.
+    Rejected,
+}
+
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExecutionStatus::Success => "success",
+            ExecutionStatus::Failure => "failure",
+            ExecutionStatus::Rejected => "rejected",
+        })
+    }
+}
+
+impl FromStr for ExecutionStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "success" => Ok(ExecutionStatus::Success),
+            "failure" => Ok(ExecutionStatus::Failure),
+            "rejected" => Ok(ExecutionStatus::Rejected),
+            other => Err(anyhow!("unknown execution status {other}")),
+        }
+    }
+}
+
+/// An abort of a Move function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAbort {
+    /// The name of the module of the aborting function.
+    pub module: String,
+    pub code: u64,
+}
+
+impl MoveAbort {
+    /// Parses an abort as the node renders it in errors, e.g. `MoveAbort(MoveLocation { module:
+    /// ModuleId { address: .., name: Identifier("dynamic_auth") }, .. }, 4)`.
+    fn parse(error: &str) -> Option<Self> {
+        let location = error.split_once("MoveAbort(MoveLocation {")?.1;
+        let (module, _) = location
+            .split_once("name: Identifier(\"")?
+            .1
+            .split_once('"')?;
+        // the code follows the location, which ends with the function name
+        let (_, code) = location
+            .split_once("function_name: ")?
+            .1
+            .split_once("}, ")?;
+        let code = code.split_once(')')?.0.trim().parse().ok()?;
+        Some(Self {
+            module: module.to_string(),
+            code,
+        })
+    }
+
+    /// The error code of a clever error, otherwise the abort code itself.
+    pub fn error_code(&self) -> u64 {
+        if self.code & CLEVER_ERROR_TAG != 0 {
+            (self.code >> 32) & 0xffff
+        } else {
+            self.code
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionOutcome {
+    pub status: ExecutionStatus,
+    pub effects_digest: Option<String>,
+    pub error: Option<String>,
+    /// The abort the transaction failed or was rejected with, if any.
+    pub abort: Option<MoveAbort>,
+}
+
+impl ExecutionOutcome {
+    /// The outcome of a transaction the node executed, successfully or not.
+    pub fn from_response(response: &IotaTransactionBlockResponse) -> Self {
+        let effects = bcs::from_bytes::<TransactionEffects>(&response.raw_effects).ok();
+        let effects_digest = effects.as_ref().map(|effects| effects.digest().to_string());
+        let abort = effects.and_then(|effects| match effects.into_status() {
+            execution_status::ExecutionStatus::Failure {
+                error: ExecutionFailureStatus::MoveAbort(location, code),
+                ..
+            } => Some(MoveAbort {
+                module: location.module.name().to_string(),
+                code,
+            }),
+            _ => None,
+        });
+        let error = match response.effects.as_ref().map(|effects| effects.status()) {
+            Some(IotaExecutionStatus::Success) => None,
+            Some(IotaExecutionStatus::Failure { error }) => Some(error.clone()),
+            None => Some("the node returned no effects".to_string()),
+        };
+
+        Self {
+            status: if error.is_none() {
+                ExecutionStatus::Success
+            } else {
+                ExecutionStatus::Failure
+            },
+            effects_digest,
+            error,
+            abort,
+        }
+    }
+
+    /// The outcome of a transaction the node rejected as invalid, with the reason it gave.
+    pub fn rejected(error: String) -> Self {
+        Self {
+            status: ExecutionStatus::Rejected,
+            effects_digest: None,
+            abort: MoveAbort::parse(&error),
+            error: Some(error),
+        }
+    }
+
+    /// Whether the authenticator aborted because the transaction is not approved by enough
+    /// members yet.
+    pub fn insufficient_approvals(&self) -> bool {
+        self.status != ExecutionStatus::Success
+            && self.abort.as_ref().is_some_and(|abort| {
+                abort.module == DYNAMIC_AUTH_MODULE
+                    && abort.error_code() == INSUFFICIENT_APPROVALS_CODE
+            })
+    }
+}
+
+/// A submission that got no verdict of the node, nothing is recorded for it.
+#[derive(Debug)]
+pub enum ExecuteError {
+    Object(ObjectError),
+    /// The node couldn't be reached or didn't answer in time.
+    Unavailable(anyhow::Error),
+    /// The node failed while processing the transaction.
+    Node(anyhow::Error),
+}
+
+impl fmt::Display for ExecuteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecuteError::Object(err) => err.fmt(f),
+            ExecuteError::Unavailable(err) => write!(f, "The node is unavailable: {err}"),
+            ExecuteError::Node(err) => write!(f, "The node failed to execute: {err}"),
+        }
+    }
+}
+
+impl From<ObjectError> for ExecuteError {
+    fn from(err: ObjectError) -> Self {
+        ExecuteError::Object(err)
+    }
+}
+
+/// Submits a transaction of an iSafe account through the quorum driver.
+///
+/// Rejections of the node are returned as outcome, so they can be recorded like executions.
+pub(crate) async fn execute_transaction(
+    node: &dyn Node,
+    resolver: &ObjectResolver,
+    tx_data: TransactionData,
+) -> Result<ExecutionOutcome, ExecuteError> {
+    let signature = resolver.account_signature(tx_data.sender(), &[]).await?;
+
+    node.execute(Transaction::from_generic_sig_data(tx_data, vec![signature]))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insufficient_approvals() {
+        let rejected = ExecutionOutcome::rejected(
+            "Transaction validator signing failed due to issues with transaction inputs: \
+             MoveAbort(MoveLocation { module: ModuleId { address: \
+             0000000000000000000000000000000000000000000000000000000000000001, name: \
+             Identifier(\"dynamic_auth\") }, function: 12, instruction: 27, function_name: \
+             Some(\"authenticate\") }, 9223372054039560455)"
+                .to_string(),
+        );
+        assert_eq!(
+            rejected.abort,
+            Some(MoveAbort {
+                module: "dynamic_auth".to_string(),
+                code: 9223372054039560455,
+            })
+        );
+        assert!(rejected.insufficient_approvals());
+
+        // the same code in another module
+        let other_module = ExecutionOutcome::rejected(
+            "MoveAbort(MoveLocation { module: ModuleId { address: 0x2, name: \
+             Identifier(\"coin\") }, function: 1, instruction: 2, function_name: None }, 4)"
+                .to_string(),
+        );
+        assert_eq!(other_module.abort.as_ref().unwrap().error_code(), 4);
+        assert!(!other_module.insufficient_approvals());
+
+        // the name of the error alone doesn't count
+        let mentioned = ExecutionOutcome::rejected(
+            "ETransactionDoesNotHaveSufficientApprovals was not raised".to_string(),
+        );
+        assert_eq!(mentioned.abort, None);
+        assert!(!mentioned.insufficient_approvals());
+    }
+}
@@ -0,0 +1,464 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Construction of `dynamic_auth` governance calls: membership, weights, threshold and guardian.
+//!
+//! Several operations are composed into one PTB. The calls are ordered so that no intermediate
+//! state violates the threshold checks of the contract, e.g. a member swap adds the new member
+//! before removing the old one.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use fastcrypto::encoding::{Base64, Encoding};
+use iota_types::{
+    Identifier,
+    base_types::{IotaAddress, ObjectID},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Argument, ObjectArg},
+};
+use serde::Deserialize;
+
+use crate::{
+    auth::ObjectResolver,
+    builder::{BuildError, BuiltTransaction, PaymentBuilder},
+    node::Node,
+};
+
+const DYNAMIC_AUTH_MODULE: &str = "dynamic_auth";
+/// Maximum number of operations composed into one transaction.
+const MAX_OPERATIONS: usize = 100;
+
+/// The members and threshold of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountState {
+    pub members: Vec<(IotaAddress, u64)>,
+    pub threshold: u64,
+}
+
+/// A governance operation, named after the `dynamic_auth` function it calls.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GovernanceOp {
+    AddMember {
+        address: IotaAddress,
+        weight: u64,
+    },
+    RemoveMember {
+        address: IotaAddress,
+    },
+    UpdateMemberWeight {
+        address: IotaAddress,
+        weight: u64,
+    },
+    SetThreshold {
+        threshold: u64,
+    },
+    /// The guardian bytes, base64 encoded.
+    SetGuardian {
+        guardian: String,
+    },
+}
+
+impl GovernanceOp {
+    fn describe(&self) -> String {
+        match self {
+            GovernanceOp::AddMember { address, weight } => {
+                format!("add member {address} with weight {weight}")
+            }
+            GovernanceOp::RemoveMember { address } => format!("remove member {address}"),
+            GovernanceOp::UpdateMemberWeight { address, weight } => {
+                format!("set weight of {address} to {weight}")
+            }
+            GovernanceOp::SetThreshold { threshold } => format!("set threshold to {threshold}"),
+            GovernanceOp::SetGuardian { .. } => "set guardian".to_string(),
+        }
+    }
+}
+
+/// Validates the operations against the current state of the account and orders them for
+/// execution.
+///
+/// Each member may only be changed once per transaction. The order is: additions, weight
+/// increases, the threshold, weight decreases, removals and the guardian. Total weight only
+/// grows before the threshold is set and only shrinks after, so if the final state satisfies
+/// the threshold, every intermediate state does too.
+pub(crate) fn plan(
+    state: &AccountState,
+    ops: &[GovernanceOp],
+) -> Result<Vec<GovernanceOp>, BuildError> {
+    let invalid = |msg: String| Err(BuildError::InvalidRequest(msg));
+
+    if ops.is_empty() || ops.len() > MAX_OPERATIONS {
+        return invalid(format!(
+            "between 1 and {MAX_OPERATIONS} operations are required"
+        ));
+    }
+
+    let current = state.members.iter().copied().collect::<HashMap<_, _>>();
+    let mut members = current.clone();
+    let mut changed = Vec::new();
+    let mut threshold = None;
+    let mut guardian_set = false;
+    for op in ops {
+        match op {
+            GovernanceOp::AddMember { address, weight }
+            | GovernanceOp::UpdateMemberWeight { address, weight } => {
+                if *weight == 0 {
+                    return invalid(format!("weight of {address} must be positive"));
+                }
+                let exists = members.contains_key(address);
+                if matches!(op, GovernanceOp::AddMember { .. }) && exists {
+                    return invalid(format!("{address} is already a member"));
+                }
+                if matches!(op, GovernanceOp::UpdateMemberWeight { .. }) && !exists {
+                    return invalid(format!("{address} is not a member"));
+                }
+                members.insert(*address, *weight);
+            }
+            GovernanceOp::RemoveMember { address } => {
+                if members.remove(address).is_none() {
+                    return invalid(format!("{address} is not a member"));
+                }
+            }
+            GovernanceOp::SetThreshold { threshold: new } => {
+                if threshold.replace(*new).is_some() {
+                    return invalid("the threshold is set more than once".to_string());
+                }
+            }
+            GovernanceOp::SetGuardian { guardian } => {
+                if Base64::decode(guardian).is_err() {
+                    return invalid("guardian is not valid base64".to_string());
+                }
+                if std::mem::replace(&mut guardian_set, true) {
+                    return invalid("the guardian is set more than once".to_string());
+                }
+            }
+        }
+        if let GovernanceOp::AddMember { address, .. }
+        | GovernanceOp::RemoveMember { address }
+        | GovernanceOp::UpdateMemberWeight { address, .. } = op
+        {
+            if changed.contains(address) {
+                return invalid(format!("{address} is changed more than once"));
+            }
+            changed.push(*address);
+        }
+    }
+
+    let total = members.values().map(|&weight| weight as u128).sum::<u128>();
+    if total > u64::MAX as u128 {
+        return invalid("the total weight overflows".to_string());
+    }
+    let final_threshold = threshold.unwrap_or(state.threshold);
+    if final_threshold == 0 {
+        return invalid("the threshold must be positive".to_string());
+    }
+    if final_threshold as u128 > total {
+        return invalid(format!(
+            "the threshold {final_threshold} exceeds the total weight {total}"
+        ));
+    }
+
+    let is_increase = |op: &GovernanceOp| match op {
+        GovernanceOp::UpdateMemberWeight { address, weight } => *weight > current[address],
+        _ => false,
+    };
+    let rank = |op: &GovernanceOp| match op {
+        GovernanceOp::AddMember { .. } => 0,
+        GovernanceOp::UpdateMemberWeight { .. } if is_increase(op) => 1,
+        GovernanceOp::SetThreshold { .. } => 2,
+        GovernanceOp::UpdateMemberWeight { .. } => 3,
+        GovernanceOp::RemoveMember { .. } => 4,
+        GovernanceOp::SetGuardian { .. } => 5,
+    };
+    let mut ordered = ops.to_vec();
+    ordered.sort_by_key(rank);
+    Ok(ordered)
+}
+
+/// Builds governance transactions of accounts, paying gas with the IOTA coins of the account.
+pub(crate) struct GovernanceBuilder {
+    reader: Arc<dyn Node>,
+    objects: Arc<ObjectResolver>,
+    payments: Arc<PaymentBuilder>,
+    package: IotaAddress,
+}
+
+impl GovernanceBuilder {
+    pub fn new(
+        reader: Arc<dyn Node>,
+        objects: Arc<ObjectResolver>,
+        payments: Arc<PaymentBuilder>,
+        package: IotaAddress,
+    ) -> Self {
+        Self {
+            reader,
+            objects,
+            payments,
+            package,
+        }
+    }
+
+    /// Composes the operations into one transaction of the account.
+    pub async fn build(
+        &self,
+        account: IotaAddress,
+        ops: &[GovernanceOp],
+        gas_budget: u64,
+    ) -> Result<BuiltTransaction, BuildError> {
+        let state = self
+            .reader
+            .account_state(account)
+            .await
+            .map_err(BuildError::Node)?
+            .ok_or_else(|| {
+                BuildError::InvalidRequest(format!("{account} is not an iSafe account"))
+            })?;
+        let ordered = plan(&state, ops)?;
+
+        let id = ObjectID::from(account);
+        let initial_shared_version = self.objects.initial_shared_version(id).await?;
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let account_arg = builder
+            .obj(ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable: true,
+            })
+            .map_err(BuildError::Node)?;
+        for op in &ordered {
+            self.move_call(&mut builder, account_arg, op)
+                .map_err(BuildError::Node)?;
+        }
+
+        let gas_payment = self.payments.gas_payment(account, gas_budget, &[]).await?;
+        // described in request order, which is how the proposer thinks of the change
+        let description = format!(
+            "Governance: {}",
+            ops.iter()
+                .map(GovernanceOp::describe)
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+        self.payments
+            .finish(account, builder, gas_payment, gas_budget, description)
+            .await
+    }
+
+    fn move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        account: Argument,
+        op: &GovernanceOp,
+    ) -> Result<()> {
+        let (function, mut args) = match op {
+            GovernanceOp::AddMember { address, weight } => (
+                "add_member",
+                vec![builder.pure(address)?, builder.pure(weight)?],
+            ),
+            GovernanceOp::RemoveMember { address } => {
+                ("remove_member", vec![builder.pure(address)?])
+            }
+            GovernanceOp::UpdateMemberWeight { address, weight } => (
+                "update_member_weight",
+                vec![builder.pure(address)?, builder.pure(weight)?],
+            ),
+            GovernanceOp::SetThreshold { threshold } => {
+                ("set_threshold", vec![builder.pure(threshold)?])
+            }
+            GovernanceOp::SetGuardian { guardian } => {
+                let guardian = Base64::decode(guardian).map_err(|err| anyhow::anyhow!(err))?;
+                ("set_guardian", vec![builder.pure(guardian)?])
+            }
+        };
+        args.insert(0, account);
+        builder.programmable_move_call(
+            ObjectID::from(self.package),
+            Identifier::new(DYNAMIC_AUTH_MODULE)?,
+            Identifier::new(function)?,
+            vec![],
+            args,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use iota_types::{
+        gas_coin::GAS,
+        transaction::{Command, TransactionDataAPI},
+    };
+
+    use super::*;
+    use crate::{
+        builder::{DEFAULT_GAS_BUDGET, tests::commands},
+        node::tests::MockNode,
+    };
+
+    fn addr(n: u8) -> IotaAddress {
+        IotaAddress::from_str(&format!("0x{n}")).unwrap()
+    }
+
+    /// Two members of weight 1 with threshold 2.
+    fn state() -> AccountState {
+        AccountState {
+            members: vec![(addr(1), 1), (addr(2), 1)],
+            threshold: 2,
+        }
+    }
+
+    fn functions(ops: &[GovernanceOp]) -> Vec<&'static str> {
+        ops.iter()
+            .map(|op| match op {
+                GovernanceOp::AddMember { .. } => "add_member",
+                GovernanceOp::RemoveMember { .. } => "remove_member",
+                GovernanceOp::UpdateMemberWeight { .. } => "update_member_weight",
+                GovernanceOp::SetThreshold { .. } => "set_threshold",
+                GovernanceOp::SetGuardian { .. } => "set_guardian",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_member_swap_adds_before_removing() {
+        let ordered = plan(
+            &state(),
+            &[
+                GovernanceOp::RemoveMember { address: addr(2) },
+                GovernanceOp::AddMember {
+                    address: addr(3),
+                    weight: 1,
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(functions(&ordered), vec!["add_member", "remove_member"]);
+    }
+
+    #[test]
+    fn test_threshold_is_set_between_increases_and_decreases() {
+        let ordered = plan(
+            &state(),
+            &[
+                GovernanceOp::UpdateMemberWeight {
+                    address: addr(1),
+                    weight: 3,
+                },
+                GovernanceOp::SetThreshold { threshold: 1 },
+                GovernanceOp::RemoveMember { address: addr(2) },
+                GovernanceOp::AddMember {
+                    address: addr(3),
+                    weight: 1,
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            functions(&ordered),
+            vec![
+                "add_member",
+                "update_member_weight",
+                "set_threshold",
+                "remove_member"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_operations_are_rejected() {
+        let rejected = |ops: &[GovernanceOp]| {
+            matches!(plan(&state(), ops), Err(BuildError::InvalidRequest(_)))
+        };
+
+        // threshold above the total weight
+        assert!(rejected(&[GovernanceOp::SetThreshold { threshold: 3 }]));
+        assert!(rejected(&[GovernanceOp::RemoveMember { address: addr(1) }]));
+        assert!(rejected(&[GovernanceOp::SetThreshold { threshold: 0 }]));
+        // duplicate and unknown members
+        assert!(rejected(&[GovernanceOp::AddMember {
+            address: addr(1),
+            weight: 1
+        }]));
+        assert!(rejected(&[GovernanceOp::RemoveMember { address: addr(3) }]));
+        assert!(rejected(&[GovernanceOp::UpdateMemberWeight {
+            address: addr(3),
+            weight: 1
+        }]));
+        // a member changed twice can't be ordered safely
+        assert!(rejected(&[
+            GovernanceOp::UpdateMemberWeight {
+                address: addr(1),
+                weight: 2
+            },
+            GovernanceOp::RemoveMember { address: addr(1) },
+        ]));
+        assert!(rejected(&[GovernanceOp::SetGuardian {
+            guardian: "not base64!".to_string()
+        }]));
+        assert!(rejected(&[]));
+    }
+
+    #[tokio::test]
+    async fn test_build_composes_calls_on_the_shared_account() {
+        let account = IotaAddress::random_for_testing_only();
+        let node = Arc::new(
+            MockNode::default()
+                .with_shared(ObjectID::from(account), 7)
+                .with_coin(account, GAS::type_tag(), DEFAULT_GAS_BUDGET)
+                .with_account(account, state()),
+        );
+        let builder = GovernanceBuilder::new(
+            node.clone(),
+            Arc::new(ObjectResolver::new(node.clone())),
+            Arc::new(PaymentBuilder::new(node)),
+            addr(0x42),
+        );
+
+        let built = builder
+            .build(
+                account,
+                &[
+                    GovernanceOp::RemoveMember { address: addr(2) },
+                    GovernanceOp::AddMember {
+                        address: addr(3),
+                        weight: 1,
+                    },
+                ],
+                DEFAULT_GAS_BUDGET,
+            )
+            .await
+            .unwrap();
+        assert_eq!(built.tx_data.sender(), account);
+        let functions = commands(&built.tx_data)
+            .into_iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => call.function.to_string(),
+                _ => panic!("not a move call"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(functions, vec!["add_member", "remove_member"]);
+        assert_eq!(
+            built.description,
+            format!(
+                "Governance: remove member {}; add member {} with weight 1",
+                addr(2),
+                addr(3)
+            )
+        );
+
+        assert!(matches!(
+            builder
+                .build(
+                    IotaAddress::ZERO,
+                    &[GovernanceOp::SetThreshold { threshold: 1 }],
+                    DEFAULT_GAS_BUDGET
+                )
+                .await,
+            Err(BuildError::InvalidRequest(_))
+        ));
+    }
+}
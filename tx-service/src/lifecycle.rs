@@ -0,0 +1,455 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Following the on-chain lifecycle of stored transactions and pruning finished ones.
+//!
+//! The `dynamic_auth` module emits an event when a proposed transaction is executed or removed.
+//! The follower pages through these events with a cursor persisted in the database, so it
+//! resumes where it stopped after a restart.
+
+use std::{fmt, str::FromStr, sync::Arc, time::Duration};
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use clap::Args;
+use diesel::Connection;
+use iota_json_rpc_types::EventPage;
+use iota_types::{base_types::IotaAddress, digests::TransactionDigest, event::EventID};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    db::{
+        pool::{DbConnectionPool, parse_duration},
+        queries,
+    },
+    node::Node,
+};
+
+pub(crate) const DYNAMIC_AUTH_MODULE: &str = "dynamic_auth";
+const EXECUTED_EVENT: &str = "TransactionExecutedEvent";
+const REMOVED_EVENT: &str = "TransactionRemovedEvent";
+/// Prefix of the key of the event cursor in the service state, followed by the package address.
+const CURSOR_KEY_PREFIX: &str = "lifecycle_event_cursor";
+/// Number of events requested per page.
+pub(crate) const EVENT_PAGE_SIZE: usize = 100;
+
+#[derive(Args, Debug, Clone)]
+pub struct LifecycleConfig {
+    /// How often the lifecycle events are polled and finished transactions pruned, in seconds.
+    #[arg(long, value_parser = parse_duration, default_value = "30")]
+    pub lifecycle_poll_interval_secs: Duration,
+    /// How long the payload of an executed or removed transaction is kept, in seconds. Kept
+    /// forever if not set.
+    #[arg(long, value_parser = parse_duration)]
+    pub retention_period_secs: Option<Duration>,
+}
+
+/// Where a stored transaction is in its on-chain lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lifecycle {
+    /// Neither executed nor removed from the account yet.
+    Pending,
+    Executed,
+    /// Removed from the account without being executed.
+    Removed,
+}
+
+impl fmt::Display for Lifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Lifecycle::Pending => "pending",
+            Lifecycle::Executed => "executed",
+            Lifecycle::Removed => "removed",
+        })
+    }
+}
+
+impl FromStr for Lifecycle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(Lifecycle::Pending),
+            "executed" => Ok(Lifecycle::Executed),
+            "removed" => Ok(Lifecycle::Removed),
+            other => Err(anyhow!("unknown lifecycle {other}")),
+        }
+    }
+}
+
+/// An event moving a proposed transaction out of the pending lifecycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleEvent {
+    pub digest: TransactionDigest,
+    pub lifecycle: Lifecycle,
+    /// When the event was emitted, in seconds.
+    pub timestamp: Option<u64>,
+}
+
+/// A page of lifecycle events.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleEventPage {
+    pub events: Vec<LifecycleEvent>,
+    /// The cursor to continue from, `None` if no events were read.
+    pub next_cursor: Option<EventID>,
+    pub has_next_page: bool,
+}
+
+impl LifecycleEventPage {
+    /// Picks the lifecycle events out of a page of events of the `dynamic_auth` module.
+    pub(crate) fn from_events(page: EventPage) -> Self {
+        let mut events = Vec::new();
+        for event in &page.data {
+            let lifecycle = match event.type_.name.as_str() {
+                EXECUTED_EVENT => Lifecycle::Executed,
+                REMOVED_EVENT => Lifecycle::Removed,
+                _ => continue,
+            };
+            // a malformed event must not block the events after it
+            let digest = match parse_digest(&event.parsed_json["transaction_digest"]) {
+                Ok(digest) => digest,
+                Err(err) => {
+                    warn!("Skipping lifecycle event {:?}: {err}", event.id);
+                    continue;
+                }
+            };
+            events.push(LifecycleEvent {
+                digest,
+                lifecycle,
+                timestamp: event.timestamp_ms.map(|ms| ms / 1000),
+            });
+        }
+        Self {
+            events,
+            // the cursor of the last event, also if it is not a lifecycle event
+            next_cursor: page.data.last().map(|event| event.id).or(page.next_cursor),
+            has_next_page: page.has_next_page,
+        }
+    }
+}
+
+/// Parses a digest rendered as JSON array of bytes, as the JSON-RPC renders `vector<u8>`.
+fn parse_digest(value: &serde_json::Value) -> Result<TransactionDigest> {
+    let bytes = value
+        .as_array()
+        .ok_or_else(|| anyhow!("digest is not an array"))?
+        .iter()
+        .map(|byte| {
+            byte.as_u64()
+                .and_then(|byte| u8::try_from(byte).ok())
+                .ok_or_else(|| anyhow!("invalid digest byte {byte}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let bytes = <[u8; 32]>::try_from(bytes)
+        .map_err(|bytes| anyhow!("digest has {} bytes instead of 32", bytes.len()))?;
+    Ok(TransactionDigest::new(bytes))
+}
+
+/// The key of the event cursor of `package` in the service state.
+fn cursor_key(package: IotaAddress) -> String {
+    format!("{CURSOR_KEY_PREFIX}:{package}")
+}
+
+/// Periodically moves stored transactions through their lifecycle and prunes finished ones.
+pub(crate) struct LifecycleFollower {
+    pool: DbConnectionPool,
+    reader: Arc<dyn Node>,
+    /// The isafe package whose events are followed, `None` to only prune.
+    package: Option<IotaAddress>,
+    interval: Duration,
+    retention: Option<Duration>,
+}
+
+impl LifecycleFollower {
+    pub fn new(
+        pool: DbConnectionPool,
+        reader: Arc<dyn Node>,
+        package: Option<IotaAddress>,
+        config: &LifecycleConfig,
+    ) -> Self {
+        Self {
+            pool,
+            reader,
+            package,
+            interval: config.lifecycle_poll_interval_secs,
+            retention: config.retention_period_secs,
+        }
+    }
+
+    pub async fn run(self, token: CancellationToken) -> Result<()> {
+        if self.package.is_none() {
+            warn!("No isafe package address set, the lifecycle of transactions is not followed");
+        }
+        match self.retention {
+            Some(retention) => {
+                info!("Pruning executed and removed transactions after {retention:?}")
+            }
+            None => info!("Executed and removed transactions are kept forever"),
+        }
+
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                _ = interval.tick() => {}
+            }
+            // the node being unreachable must not stop the service, the next round retries
+            match self.follow_events().await {
+                Ok(0) => {}
+                Ok(updated) => info!("Moved {updated} transactions out of the pending lifecycle"),
+                Err(err) => warn!("Following lifecycle events failed: {err}"),
+            }
+            match self.prune() {
+                Ok(0) => {}
+                Ok(pruned) => info!("Pruned the payload of {pruned} transactions"),
+                Err(err) => warn!("Pruning transactions failed: {err}"),
+            }
+        }
+    }
+
+    /// Applies all lifecycle events emitted since the last call and returns the number of
+    /// stored transactions that left the pending lifecycle.
+    pub async fn follow_events(&self) -> Result<usize> {
+        let Some(package) = self.package else {
+            return Ok(0);
+        };
+
+        // the cursor belongs to the package, a redeployment starts reading from the beginning
+        let cursor_key = cursor_key(package);
+        let mut cursor = {
+            let mut conn = self.pool.get_connection()?;
+            queries::get_service_state(&mut conn, &cursor_key)?
+                .map(|cursor| serde_json::from_str::<EventID>(&cursor))
+                .transpose()?
+        };
+        let mut updated = 0;
+        loop {
+            let page = self.reader.lifecycle_events(package, cursor).await?;
+            let Some(next_cursor) = page.next_cursor else {
+                break;
+            };
+
+            // the cursor only advances together with the applied events
+            let mut conn = self.pool.get_connection()?;
+            updated += conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+                let mut updated = 0;
+                for event in &page.events {
+                    let at = event
+                        .timestamp
+                        .unwrap_or_else(|| Utc::now().timestamp() as u64);
+                    if queries::set_lifecycle(conn, &event.digest.to_string(), event.lifecycle, at)?
+                    {
+                        debug!("Transaction {} is {}", event.digest, event.lifecycle);
+                        updated += 1;
+                    }
+                }
+                queries::set_service_state(
+                    conn,
+                    &cursor_key,
+                    &serde_json::to_string(&next_cursor)?,
+                )?;
+                Ok(updated)
+            })?;
+
+            cursor = Some(next_cursor);
+            if !page.has_next_page {
+                break;
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Prunes the payload of transactions that left the pending lifecycle longer than the
+    /// retention period ago.
+    pub fn prune(&self) -> Result<usize> {
+        let Some(retention) = self.retention else {
+            return Ok(0);
+        };
+        let now = Utc::now().timestamp() as u64;
+        let mut conn = self.pool.get_connection()?;
+        queries::prune_transactions(&mut conn, now.saturating_sub(retention.as_secs()), now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use iota_types::{
+        base_types::{ObjectID, random_object_ref},
+        transaction::TransactionData,
+    };
+
+    use super::*;
+    use crate::{db::pool::DbConnectionPoolConfig, node::tests::MockNode};
+
+    fn pool() -> DbConnectionPool {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        pool
+    }
+
+    fn store_transaction(pool: &DbConnectionPool) -> TransactionDigest {
+        let tx_data = TransactionData::new_transfer_iota(
+            IotaAddress::ZERO,
+            IotaAddress::ZERO,
+            Some(1),
+            random_object_ref(),
+            1_000_000,
+            1_000,
+        );
+        queries::insert_transaction(&mut pool.get_connection().unwrap(), &tx_data, None, None, 0)
+            .unwrap();
+        tx_data.digest()
+    }
+
+    fn config(retention: Option<Duration>) -> LifecycleConfig {
+        LifecycleConfig {
+            lifecycle_poll_interval_secs: Duration::from_secs(30),
+            retention_period_secs: retention,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_move_transactions_and_cursor() {
+        let pool = pool();
+        let (executed, removed, pending) = (
+            store_transaction(&pool),
+            store_transaction(&pool),
+            store_transaction(&pool),
+        );
+        let cursor = EventID {
+            tx_digest: TransactionDigest::random(),
+            event_seq: 3,
+        };
+        let node = Arc::new(MockNode {
+            event_pages: Mutex::new(vec![LifecycleEventPage {
+                events: vec![
+                    LifecycleEvent {
+                        digest: executed,
+                        lifecycle: Lifecycle::Executed,
+                        timestamp: Some(100),
+                    },
+                    LifecycleEvent {
+                        digest: removed,
+                        lifecycle: Lifecycle::Removed,
+                        timestamp: Some(200),
+                    },
+                    // transactions that were never stored are ignored
+                    LifecycleEvent {
+                        digest: TransactionDigest::random(),
+                        lifecycle: Lifecycle::Executed,
+                        timestamp: Some(200),
+                    },
+                ],
+                next_cursor: Some(cursor),
+                has_next_page: false,
+            }]),
+            ..Default::default()
+        });
+        let follower = LifecycleFollower::new(
+            pool.clone(),
+            node.clone(),
+            Some(IotaAddress::ZERO),
+            &config(None),
+        );
+
+        // a stale transaction that got executed anyway isn't listed as stale anymore
+        queries::set_stale(
+            &mut pool.get_connection().unwrap(),
+            &executed.to_string(),
+            &[ObjectID::random()],
+            50,
+        )
+        .unwrap();
+
+        assert_eq!(follower.follow_events().await.unwrap(), 2);
+        let mut conn = pool.get_connection().unwrap();
+        let lifecycle = |conn: &mut diesel::SqliteConnection, digest: &TransactionDigest| {
+            queries::get_transaction_by_digest(conn, &digest.to_string())
+                .unwrap()
+                .unwrap()
+                .lifecycle
+        };
+        assert_eq!(lifecycle(&mut conn, &executed), "executed");
+        assert_eq!(lifecycle(&mut conn, &removed), "removed");
+        assert_eq!(lifecycle(&mut conn, &pending), "pending");
+        let executed_tx = queries::get_transaction_by_digest(&mut conn, &executed.to_string())
+            .unwrap()
+            .unwrap();
+        assert!(executed_tx.stale_at.is_none());
+        assert!(executed_tx.stale_objects.is_none());
+        // only pending transactions are marked and listed as stale
+        queries::set_stale(&mut conn, &removed.to_string(), &[ObjectID::random()], 300).unwrap();
+        assert_eq!(
+            queries::get_stale_transactions(&mut conn, None, 10, 0)
+                .unwrap()
+                .1,
+            0
+        );
+        drop(conn);
+
+        // the next round continues after the stored cursor
+        assert_eq!(follower.follow_events().await.unwrap(), 0);
+        // the cursor of another package starts from the beginning
+        let redeployed = LifecycleFollower::new(
+            pool.clone(),
+            node.clone(),
+            Some(IotaAddress::random_for_testing_only()),
+            &config(None),
+        );
+        assert_eq!(redeployed.follow_events().await.unwrap(), 0);
+        assert_eq!(
+            *node.event_cursors.lock().unwrap(),
+            vec![None, Some(cursor), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_tombstones_of_finished_transactions() {
+        let pool = pool();
+        let (finished, pending) = (store_transaction(&pool), store_transaction(&pool));
+        let mut conn = pool.get_connection().unwrap();
+        queries::set_lifecycle(&mut conn, &finished.to_string(), Lifecycle::Executed, 0).unwrap();
+        drop(conn);
+
+        let follower = LifecycleFollower::new(
+            pool.clone(),
+            Arc::new(MockNode::default()),
+            None,
+            &config(Some(Duration::from_secs(60))),
+        );
+        assert_eq!(follower.prune().unwrap(), 1);
+        assert_eq!(follower.prune().unwrap(), 0);
+
+        let mut conn = pool.get_connection().unwrap();
+        let tombstone = queries::get_transaction_by_digest(&mut conn, &finished.to_string())
+            .unwrap()
+            .unwrap();
+        assert!(tombstone.pruned_at.is_some());
+        assert!(tombstone.tx_data.is_empty());
+        let kept = queries::get_transaction_by_digest(&mut conn, &pending.to_string())
+            .unwrap()
+            .unwrap();
+        assert!(kept.pruned_at.is_none());
+        assert!(!kept.tx_data.is_empty());
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        let digest = TransactionDigest::random();
+        let json = serde_json::json!(digest.inner().to_vec());
+        assert_eq!(parse_digest(&json).unwrap(), digest);
+        assert!(parse_digest(&serde_json::json!([1, 2, 3])).is_err());
+    }
+}
@@ -1,8 +1,24 @@
+mod account;
 mod api;
+mod auth;
+mod builder;
+mod config;
 mod db;
+mod decode;
+mod execute;
+mod governance;
+mod lifecycle;
+mod metadata;
+mod node;
+mod sender_check;
+mod simulate;
+mod stale;
+mod submission;
 
-use anyhow::Result;
-use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow, bail};
+use clap::{Args, Parser, Subcommand};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
@@ -12,7 +28,12 @@ use tracing_subscriber::{
 
 use crate::{
     api::start_api_server,
+    config::{ENV_PREFIX, LayeredConfig, Network, preset},
     db::pool::{DbConnectionPool, DbConnectionPoolConfig},
+    lifecycle::{LifecycleConfig, LifecycleFollower},
+    node::NodeClient,
+    sender_check::{SenderCheck, SenderCheckConfig},
+    stale::{StaleCheckConfig, StaleChecker},
 };
 
 // Define the `GIT_REVISION` and `VERSION` consts
@@ -26,28 +47,89 @@ bin_version::bin_version!();
     version = VERSION,
     propagate_version = true,
 )]
+struct Cli {
+    /// A TOML file with settings, keyed by option name. Environment variables and options
+    /// override it.
+    #[arg(long, global = true, env = "TX_SERVICE_CONFIG")]
+    config: Option<PathBuf>,
+    /// Use the endpoints and deployment of a known network as defaults.
+    #[arg(long, global = true, value_enum)]
+    network: Option<Network>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
 enum Command {
-    Start {
-        #[clap(flatten)]
-        connection_pool_config: DbConnectionPoolConfig,
-        /// The URL of an IOTA node with JSON API.
-        #[arg(long, default_value = "http://localhost:9000")]
-        node_url: String,
-        /// The port to run the API server on.
-        #[arg(long, default_value_t = 3031)]
-        api_port: u16,
+    Start(StartArgs),
+    /// Inspect the configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective settings of `start` with the layer each comes from, and validate them.
+    Print(StartArgs),
+}
+
+#[derive(Args)]
+struct StartArgs {
+    #[clap(flatten)]
+    connection_pool_config: DbConnectionPoolConfig,
+    /// The URL of an IOTA node with JSON API.
+    #[arg(long, default_value = "http://localhost:9000")]
+    node_url: String,
+    /// The port to run the API server on.
+    #[arg(long, default_value_t = 3031)]
+    api_port: u16,
+    #[clap(flatten)]
+    sender_check_config: SenderCheckConfig,
+    #[clap(flatten)]
+    stale_check_config: StaleCheckConfig,
+    #[clap(flatten)]
+    lifecycle_config: LifecycleConfig,
+}
+
+impl StartArgs {
+    /// Checks the settings the value parsers can't check on their own.
+    fn validate(&self) -> Result<()> {
+        if self.connection_pool_config.pool_size == 0 {
+            bail!("pool-size must be at least 1");
+        }
+        reqwest::Url::parse(&self.node_url)
+            .map_err(|err| anyhow!("invalid node-url {}: {err}", self.node_url))?;
+        if !self.sender_check_config.skip_sender_check
+            && self.sender_check_config.package_address.is_none()
+        {
+            bail!("package-address is required unless skip-sender-check is set");
+        }
+        if self.stale_check_config.stale_check_interval_secs.is_zero() {
+            bail!("stale-check-interval-secs must be at least 1");
+        }
+        if self.lifecycle_config.lifecycle_poll_interval_secs.is_zero() {
+            bail!("lifecycle-poll-interval-secs must be at least 1");
+        }
+        Ok(())
+    }
+}
+
 impl Command {
-    async fn execute(self) -> Result<()> {
+    async fn execute(self, config: &LayeredConfig) -> Result<()> {
         match self {
-            Command::Start {
-                connection_pool_config,
-                node_url,
-                api_port,
-            } => {
+            Command::Start(args) => {
                 info!("Starting Tx-Service version {VERSION} (git revision: {GIT_REVISION})");
+                args.validate()?;
+                let StartArgs {
+                    connection_pool_config,
+                    node_url,
+                    api_port,
+                    sender_check_config,
+                    stale_check_config,
+                    lifecycle_config,
+                } = args;
 
                 let cancel_token = CancellationToken::new();
 
@@ -56,10 +138,45 @@ impl Command {
                 let connection_pool = DbConnectionPool::new(connection_pool_config)?;
                 connection_pool.run_migrations()?;
 
+                let node = Arc::new(NodeClient::new(node_url));
+                if sender_check_config.package_address.is_none() {
+                    warn!("No isafe package address set, governance transactions can't be built");
+                }
+                let sender_check =
+                    SenderCheck::from_config(&sender_check_config, node.clone())?.map(Arc::new);
+                if sender_check.is_none() {
+                    warn!("Sender check is disabled, transactions of any sender are accepted");
+                }
+
+                // Spawn the stale transaction checker
+                let stale_checker =
+                    StaleChecker::new(connection_pool.clone(), node.clone(), &stale_check_config);
+                tasks.spawn(stale_checker.run(cancel_token.clone()));
+
+                // Spawn the lifecycle follower, which also prunes finished transactions
+                let lifecycle_follower = LifecycleFollower::new(
+                    connection_pool.clone(),
+                    node.clone(),
+                    sender_check_config.package_address,
+                    &lifecycle_config,
+                );
+                tasks.spawn(lifecycle_follower.run(cancel_token.clone()));
+
                 // Spawn the auction API server
                 let handle = cancel_token.clone();
                 let database_pool = connection_pool.clone();
-                tasks.spawn(async move { start_api_server(database_pool, api_port, handle, node_url).await });
+                let isafe_package = sender_check_config.package_address;
+                tasks.spawn(async move {
+                    start_api_server(
+                        database_pool,
+                        api_port,
+                        handle,
+                        node,
+                        sender_check,
+                        isafe_package,
+                    )
+                    .await
+                });
 
                 let mut exit_code = Ok(());
 
@@ -113,6 +230,12 @@ impl Command {
 
                 exit_code
             }
+            Command::Config {
+                command: ConfigCommand::Print(args),
+            } => {
+                print!("{}", config.render(&["config", "print"]));
+                args.validate()
+            }
         }
     }
 }
@@ -123,7 +246,14 @@ async fn main() -> Result<()> {
 
     set_up_logging()?;
 
-    Command::parse().execute().await?;
+    let (cli, config) = LayeredConfig::parse::<Cli>(ENV_PREFIX, preset)?;
+    if let Some(path) = &cli.config {
+        info!("Using configuration file {}", path.display());
+    }
+    if let Some(network) = cli.network {
+        info!("Using the defaults of {network:?}");
+    }
+    cli.command.execute(&config).await?;
     Ok(())
 }
 
@@ -142,7 +272,6 @@ fn set_up_logging() -> Result<()> {
 pub async fn interrupt_or_terminate() -> Result<()> {
     #[cfg(unix)]
     {
-        use anyhow::anyhow;
         use tokio::signal::unix::{SignalKind, signal};
         let mut terminate = signal(SignalKind::terminate())
             .map_err(|e| anyhow!("cannot listen to `SIGTERM`: {e}"))?;
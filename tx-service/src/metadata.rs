@@ -0,0 +1,206 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured metadata of proposals: category, references, tags and due date.
+//!
+//! Metadata is signed along with the description, see [`crate::submission::submission_message`].
+
+use std::{fmt, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a description, in bytes.
+pub const MAX_DESCRIPTION_LENGTH: usize = 2_000;
+/// Maximum length of an invoice reference or ticket ID, in bytes.
+const MAX_REFERENCE_LENGTH: usize = 100;
+const MAX_TAGS: usize = 10;
+/// Maximum length of a tag, in bytes.
+const MAX_TAG_LENGTH: usize = 32;
+
+/// What a proposal is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Payroll,
+    VendorPayment,
+    Governance,
+    Treasury,
+    Other,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Category::Payroll => "payroll",
+            Category::VendorPayment => "vendor_payment",
+            Category::Governance => "governance",
+            Category::Treasury => "treasury",
+            Category::Other => "other",
+        })
+    }
+}
+
+impl FromStr for Category {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "payroll" => Ok(Category::Payroll),
+            "vendor_payment" => Ok(Category::VendorPayment),
+            "governance" => Ok(Category::Governance),
+            "treasury" => Ok(Category::Treasury),
+            "other" => Ok(Category::Other),
+            other => Err(anyhow!("unknown category {other}")),
+        }
+    }
+}
+
+/// Structured metadata of a proposal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_ref: Option<String>,
+    /// Lowercase letters, digits, `-` and `_`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The ID of the proposal in an external ticket system.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_id: Option<String>,
+    /// The date the proposal should be executed by, as `YYYY-MM-DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
+}
+
+impl ProposalMetadata {
+    /// Checks the size limits and the format of the tags.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("invoiceRef", &self.invoice_ref),
+            ("ticketId", &self.ticket_id),
+        ] {
+            if let Some(value) = value {
+                if value.trim().is_empty() || value.len() > MAX_REFERENCE_LENGTH {
+                    return Err(format!(
+                        "{name} must be between 1 and {MAX_REFERENCE_LENGTH} bytes"
+                    ));
+                }
+                if value.chars().any(char::is_control) {
+                    return Err(format!("{name} must not contain control characters"));
+                }
+            }
+        }
+
+        if self.tags.len() > MAX_TAGS {
+            return Err(format!("At most {MAX_TAGS} tags are allowed"));
+        }
+        for (i, tag) in self.tags.iter().enumerate() {
+            if tag.is_empty()
+                || tag.len() > MAX_TAG_LENGTH
+                || !tag
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+            {
+                return Err(format!(
+                    "Tag `{tag}` must be 1 to {MAX_TAG_LENGTH} lowercase letters, digits, `-` or `_`"
+                ));
+            }
+            if self.tags[..i].contains(tag) {
+                return Err(format!("Tag `{tag}` is given more than once"));
+            }
+        }
+        Ok(())
+    }
+
+    /// The lines the metadata adds to the signed submission message, one per set field.
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(category) = self.category {
+            lines.push(format!("Category: {category}"));
+        }
+        if let Some(invoice_ref) = &self.invoice_ref {
+            lines.push(format!("Invoice: {invoice_ref}"));
+        }
+        if !self.tags.is_empty() {
+            lines.push(format!("Tags: {}", self.tags.join(",")));
+        }
+        if let Some(ticket_id) = &self.ticket_id {
+            lines.push(format!("Ticket: {ticket_id}"));
+        }
+        if let Some(due_date) = self.due_date {
+            lines.push(format!("Due: {due_date}"));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_metadata() {
+        let metadata = ProposalMetadata {
+            category: Some(Category::Payroll),
+            tags: vec!["q4".to_string(), "team-a".to_string()],
+            ..Default::default()
+        };
+        assert!(metadata.validate().is_ok());
+
+        let invalid = [
+            ProposalMetadata {
+                tags: vec!["Q4".to_string()],
+                ..Default::default()
+            },
+            ProposalMetadata {
+                tags: vec!["q4".to_string(), "q4".to_string()],
+                ..Default::default()
+            },
+            ProposalMetadata {
+                tags: (0..=MAX_TAGS).map(|i| i.to_string()).collect(),
+                ..Default::default()
+            },
+            ProposalMetadata {
+                invoice_ref: Some("x".repeat(MAX_REFERENCE_LENGTH + 1)),
+                ..Default::default()
+            },
+            ProposalMetadata {
+                ticket_id: Some("T-1\nCategory: governance".to_string()),
+                ..Default::default()
+            },
+        ];
+        for metadata in invalid {
+            assert!(metadata.validate().is_err(), "{metadata:?}");
+        }
+    }
+
+    #[test]
+    fn test_metadata_deserialization() {
+        let metadata: ProposalMetadata = serde_json::from_value(serde_json::json!({
+            "category": "vendor_payment",
+            "invoiceRef": "INV-42",
+            "dueDate": "2026-11-01",
+        }))
+        .unwrap();
+        assert_eq!(metadata.category, Some(Category::VendorPayment));
+        assert_eq!(
+            metadata.message_lines(),
+            vec![
+                "Category: vendor_payment",
+                "Invoice: INV-42",
+                "Due: 2026-11-01"
+            ]
+        );
+
+        assert!(
+            serde_json::from_value::<ProposalMetadata>(serde_json::json!({
+                "dueDate": "01.11.2026"
+            }))
+            .is_err()
+        );
+    }
+}
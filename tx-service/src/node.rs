@@ -0,0 +1,625 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The node client shared by all requests, and the requests the service makes to the node.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use iota_json_rpc_types::{
+    EventFilter, IotaObjectDataOptions, IotaParsedData, IotaTransactionBlockResponseOptions,
+};
+use iota_sdk::{IotaClient, IotaClientBuilder};
+use iota_types::{
+    Identifier, TypeTag,
+    base_types::{IotaAddress, ObjectID, ObjectRef},
+    dynamic_field::DynamicFieldInfo,
+    event::EventID,
+    object::Owner,
+    quorum_driver_types::ExecuteTransactionRequestType,
+    transaction::{Transaction, TransactionData},
+};
+use jsonrpsee::core::ClientError;
+use tokio::sync::OnceCell;
+
+use crate::{
+    account::{MEMBERS_TYPE, THRESHOLD_KEY_TYPE, parse_members_field, parse_u64},
+    builder::OwnedCoin,
+    decode::MoveType,
+    execute::{ExecuteError, ExecutionOutcome},
+    governance::AccountState,
+    lifecycle::{self, LifecycleEventPage},
+    sender_check::{self, SenderObject},
+    simulate::{DryRunSummary, ObjectVersion},
+};
+
+/// The JSON-RPC error codes of the node rejecting a transaction as invalid: invalid parameters,
+/// e.g. an input object in use, and transaction execution client errors, e.g. an aborting
+/// authenticator.
+const REJECTION_ERROR_CODES: [i32; 2] = [-32602, -32002];
+
+/// The requests the service makes to the node.
+///
+/// Implemented by [`NodeClient`], and by a mocked node in the tests.
+#[async_trait]
+pub(crate) trait Node: Send + Sync {
+    /// Returns the sequence number of the latest checkpoint.
+    async fn latest_checkpoint(&self) -> Result<u64>;
+
+    /// Returns the reference and owner of the latest version of the object, if it exists.
+    async fn object(&self, id: ObjectID) -> Result<Option<(ObjectRef, Owner)>>;
+
+    /// Returns the current versions of the given objects, in the same order.
+    async fn object_versions(&self, ids: &[ObjectID]) -> Result<Vec<ObjectVersion>>;
+
+    /// Returns the object with the id of `address`, if it exists.
+    async fn sender_object(&self, address: IotaAddress) -> Result<Option<SenderObject>>;
+
+    /// Returns the parameter types of `package::module::function`.
+    async fn parameter_types(
+        &self,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+    ) -> Result<Vec<MoveType>>;
+
+    async fn reference_gas_price(&self) -> Result<u64>;
+
+    /// Returns all coins of `coin_type` owned by `owner`.
+    async fn coins(&self, owner: IotaAddress, coin_type: &TypeTag) -> Result<Vec<OwnedCoin>>;
+
+    /// Returns the member addresses of the account, `None` if it has no members.
+    async fn members(&self, account: IotaAddress) -> Result<Option<Vec<IotaAddress>>>;
+
+    /// Returns the members and threshold of the account, `None` if it is not an iSafe account.
+    async fn account_state(&self, account: IotaAddress) -> Result<Option<AccountState>>;
+
+    /// Returns the lifecycle events of the isafe package emitted after `cursor`, oldest first.
+    async fn lifecycle_events(
+        &self,
+        package: IotaAddress,
+        cursor: Option<EventID>,
+    ) -> Result<LifecycleEventPage>;
+
+    /// Dry-runs the transaction against the current state.
+    async fn dry_run(&self, tx_data: TransactionData) -> Result<DryRunSummary>;
+
+    /// Executes the signed transaction and waits for its effects.
+    ///
+    /// A transaction the node rejects as invalid is returned as rejected outcome.
+    async fn execute(&self, transaction: Transaction) -> Result<ExecutionOutcome, ExecuteError>;
+}
+
+/// A client of an IOTA node that connects on first use.
+///
+/// Connecting lazily keeps the service available while the node is unreachable, requests that
+/// need the node fail until it can be reached.
+pub struct NodeClient {
+    node_url: String,
+    client: OnceCell<IotaClient>,
+}
+
+impl NodeClient {
+    pub fn new(node_url: String) -> Self {
+        Self {
+            node_url,
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Returns the client, connecting to the node if not connected yet.
+    async fn get(&self) -> Result<&IotaClient> {
+        self.client
+            .get_or_try_init(|| async {
+                Ok(IotaClientBuilder::default().build(&self.node_url).await?)
+            })
+            .await
+    }
+
+    /// Returns the JSON content of the first dynamic field of `parent` matching `matches`.
+    async fn dynamic_field(
+        &self,
+        parent: ObjectID,
+        matches: impl Fn(&DynamicFieldInfo) -> bool + Send,
+    ) -> Result<Option<serde_json::Value>> {
+        let client = self.get().await?;
+
+        let mut field = None;
+        let mut cursor = None;
+        loop {
+            let page = client
+                .read_api()
+                .get_dynamic_fields(parent, cursor, None)
+                .await?;
+            field = page
+                .data
+                .iter()
+                .find(|info| matches(info))
+                .map(|info| info.object_id);
+            if field.is_some() || !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        let Some(field_id) = field else {
+            return Ok(None);
+        };
+
+        let response = client
+            .read_api()
+            .get_object_with_options(field_id, IotaObjectDataOptions::new().with_content())
+            .await?;
+        Ok(match response.data.and_then(|data| data.content) {
+            Some(IotaParsedData::MoveObject(object)) => Some(object.fields.to_json_value()),
+            _ => None,
+        })
+    }
+}
+
+#[async_trait]
+impl Node for NodeClient {
+    async fn latest_checkpoint(&self) -> Result<u64> {
+        Ok(self
+            .get()
+            .await?
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await?)
+    }
+
+    async fn object(&self, id: ObjectID) -> Result<Option<(ObjectRef, Owner)>> {
+        let response = self
+            .get()
+            .await?
+            .read_api()
+            .get_object_with_options(id, IotaObjectDataOptions::new().with_owner())
+            .await?;
+        Ok(response.data.and_then(|data| {
+            let owner = data.owner?;
+            Some((data.object_ref(), owner))
+        }))
+    }
+
+    async fn object_versions(&self, ids: &[ObjectID]) -> Result<Vec<ObjectVersion>> {
+        let responses = self
+            .get()
+            .await?
+            .read_api()
+            .multi_get_object_with_options(ids.to_vec(), IotaObjectDataOptions::new())
+            .await?;
+        Ok(ids
+            .iter()
+            .zip(responses)
+            .map(|(&object_id, response)| ObjectVersion {
+                object_id,
+                version: response.data.map(|data| data.version.value()),
+            })
+            .collect())
+    }
+
+    async fn sender_object(&self, address: IotaAddress) -> Result<Option<SenderObject>> {
+        let id = ObjectID::from(address);
+        let response = self
+            .get()
+            .await?
+            .read_api()
+            .get_object_with_options(id, IotaObjectDataOptions::new().with_type().with_owner())
+            .await?;
+        let Some(data) = response.data else {
+            return Ok(None);
+        };
+        let object_type = match &data.type_ {
+            Some(object_type) => object_type.to_string(),
+            None => return Ok(None),
+        };
+        let shared = matches!(data.owner, Some(Owner::Shared { .. }));
+
+        // the authenticator is attached as a dynamic field of the account
+        let authenticator = self
+            .dynamic_field(id, |info| {
+                info.object_type
+                    .contains(sender_check::AUTHENTICATOR_REF_TYPE)
+            })
+            .await?
+            .map(|field| sender_check::parse_authenticator_field(&field))
+            .transpose()?;
+
+        Ok(Some(SenderObject {
+            object_type,
+            shared,
+            authenticator,
+        }))
+    }
+
+    async fn parameter_types(
+        &self,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+    ) -> Result<Vec<MoveType>> {
+        let modules = self
+            .get()
+            .await?
+            .read_api()
+            .get_normalized_move_modules_by_package(package)
+            .await?;
+        let function = modules
+            .get(module)
+            .and_then(|module| module.exposed_functions.get(function))
+            .ok_or_else(|| anyhow!("function {package}::{module}::{function} not found"))?;
+        function
+            .parameters
+            .iter()
+            .map(|parameter| MoveType::from_normalized_json(&serde_json::to_value(parameter)?))
+            .collect()
+    }
+
+    async fn reference_gas_price(&self) -> Result<u64> {
+        Ok(self
+            .get()
+            .await?
+            .governance_api()
+            .get_reference_gas_price()
+            .await?)
+    }
+
+    async fn coins(&self, owner: IotaAddress, coin_type: &TypeTag) -> Result<Vec<OwnedCoin>> {
+        let client = self.get().await?;
+        let mut coins = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = client
+                .coin_read_api()
+                .get_coins(owner, Some(coin_type.to_string()), cursor, None)
+                .await?;
+            coins.extend(page.data.iter().map(|coin| OwnedCoin {
+                object_ref: coin.object_ref(),
+                balance: coin.balance,
+            }));
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+        Ok(coins)
+    }
+
+    async fn members(&self, account: IotaAddress) -> Result<Option<Vec<IotaAddress>>> {
+        // the members are attached as a dynamic field of the account
+        let field = self
+            .dynamic_field(ObjectID::from(account), |info| {
+                info.object_type.ends_with(MEMBERS_TYPE)
+            })
+            .await?;
+        field
+            .map(|field| {
+                Ok(parse_members_field(&field)?
+                    .into_iter()
+                    .map(|(addr, _)| addr)
+                    .collect())
+            })
+            .transpose()
+    }
+
+    async fn account_state(&self, account: IotaAddress) -> Result<Option<AccountState>> {
+        let id = ObjectID::from(account);
+        let Some(members) = self
+            .dynamic_field(id, |info| info.object_type.ends_with(MEMBERS_TYPE))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(threshold) = self
+            .dynamic_field(id, |info| {
+                info.name.type_.to_string().ends_with(THRESHOLD_KEY_TYPE)
+            })
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(AccountState {
+            members: parse_members_field(&members)?,
+            threshold: parse_u64(&threshold["value"])?,
+        }))
+    }
+
+    async fn lifecycle_events(
+        &self,
+        package: IotaAddress,
+        cursor: Option<EventID>,
+    ) -> Result<LifecycleEventPage> {
+        let page = self
+            .get()
+            .await?
+            .event_api()
+            .query_events(
+                EventFilter::MoveEventModule {
+                    package: ObjectID::from(package),
+                    module: Identifier::new(lifecycle::DYNAMIC_AUTH_MODULE)?,
+                },
+                cursor,
+                Some(lifecycle::EVENT_PAGE_SIZE),
+                false,
+            )
+            .await?;
+        Ok(LifecycleEventPage::from_events(page))
+    }
+
+    async fn dry_run(&self, tx_data: TransactionData) -> Result<DryRunSummary> {
+        let response = self
+            .get()
+            .await?
+            .read_api()
+            .dry_run_transaction_block(tx_data)
+            .await;
+        match response {
+            Ok(response) => Ok(response.into()),
+            Err(err) => match rejection(&err) {
+                Some(reason) => Ok(DryRunSummary::rejected(reason)),
+                None => Err(err.into()),
+            },
+        }
+    }
+
+    async fn execute(&self, transaction: Transaction) -> Result<ExecutionOutcome, ExecuteError> {
+        let response = self
+            .get()
+            .await
+            .map_err(ExecuteError::Unavailable)?
+            .quorum_driver_api()
+            .execute_transaction_block(
+                transaction,
+                IotaTransactionBlockResponseOptions::new()
+                    .with_effects()
+                    .with_raw_effects(),
+                ExecuteTransactionRequestType::WaitForLocalExecution,
+            )
+            .await;
+        let err = match response {
+            Ok(response) => return Ok(ExecutionOutcome::from_response(&response)),
+            Err(err) => err,
+        };
+        if let Some(reason) = rejection(&err) {
+            return Ok(ExecutionOutcome::rejected(reason));
+        }
+        Err(match err {
+            iota_sdk::error::Error::Rpc(
+                ClientError::Transport(_)
+                | ClientError::RestartNeeded(_)
+                | ClientError::RequestTimeout,
+            )
+            | iota_sdk::error::Error::FailToConfirmTransactionStatus(..) => {
+                ExecuteError::Unavailable(err.into())
+            }
+            _ => ExecuteError::Node(err.into()),
+        })
+    }
+}
+
+/// The reason the node gave for rejecting a transaction as invalid, `None` for other failures
+/// like an unreachable node.
+fn rejection(err: &iota_sdk::error::Error) -> Option<String> {
+    match err {
+        iota_sdk::error::Error::Rpc(ClientError::Call(err))
+            if REJECTION_ERROR_CODES.contains(&err.code()) =>
+        {
+            Some(err.message().to_string())
+        }
+        iota_sdk::error::Error::UserInput(err) => Some(err.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use iota_types::{
+        base_types::{SequenceNumber, random_object_ref},
+        digests::ObjectDigest,
+    };
+
+    use super::*;
+    use crate::simulate::GasSummary;
+
+    /// A node with a fixed state that counts the requests.
+    #[derive(Default)]
+    pub(crate) struct MockNode {
+        pub chain_tip: Option<u64>,
+        pub objects: HashMap<ObjectID, (ObjectRef, Owner)>,
+        /// The current versions of objects, objects without a version are deleted.
+        pub versions: Mutex<HashMap<ObjectID, u64>>,
+        pub sender_objects: HashMap<IotaAddress, SenderObject>,
+        pub coins: HashMap<(IotaAddress, TypeTag), Vec<OwnedCoin>>,
+        pub accounts: HashMap<IotaAddress, AccountState>,
+        /// Pages of lifecycle events, one is served per call.
+        pub event_pages: Mutex<Vec<LifecycleEventPage>>,
+        /// The cursors the lifecycle events were requested with.
+        pub event_cursors: Mutex<Vec<Option<EventID>>>,
+        /// The error every dry run fails with, if any.
+        pub dry_run_error: Option<String>,
+        /// The reason the node rejects every dry run with, if any.
+        pub dry_run_rejection: Option<String>,
+        /// The outcome of every execution, without the node is unreachable.
+        pub execution: Option<ExecutionOutcome>,
+        pub requests: AtomicUsize,
+        pub dry_runs: AtomicUsize,
+    }
+
+    impl MockNode {
+        pub fn with_shared(mut self, id: ObjectID, initial_shared_version: u64) -> Self {
+            let version = SequenceNumber::from_u64(initial_shared_version);
+            self.objects.insert(
+                id,
+                (
+                    (id, version, ObjectDigest::random()),
+                    Owner::Shared {
+                        initial_shared_version: version,
+                    },
+                ),
+            );
+            self
+        }
+
+        pub fn with_owned(mut self, object_ref: ObjectRef, owner: IotaAddress) -> Self {
+            self.objects
+                .insert(object_ref.0, (object_ref, Owner::AddressOwner(owner)));
+            self
+        }
+
+        pub fn with_coin(mut self, owner: IotaAddress, coin_type: TypeTag, balance: u64) -> Self {
+            self.coins
+                .entry((owner, coin_type))
+                .or_default()
+                .push(OwnedCoin {
+                    object_ref: random_object_ref(),
+                    balance,
+                });
+            self
+        }
+
+        pub fn with_account(mut self, account: IotaAddress, state: AccountState) -> Self {
+            self.accounts.insert(account, state);
+            self
+        }
+
+        /// Adds an account whose members have weight 1 and any of them reaches the threshold.
+        pub fn with_members(self, account: IotaAddress, members: Vec<IotaAddress>) -> Self {
+            self.with_account(
+                account,
+                AccountState {
+                    members: members.into_iter().map(|member| (member, 1)).collect(),
+                    threshold: 1,
+                },
+            )
+        }
+
+        pub fn set_version(&self, id: ObjectID, version: u64) {
+            self.versions.lock().unwrap().insert(id, version);
+        }
+
+        fn request(&self) {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl Node for MockNode {
+        async fn latest_checkpoint(&self) -> Result<u64> {
+            self.request();
+            self.chain_tip.ok_or_else(|| anyhow!("node unreachable"))
+        }
+
+        async fn object(&self, id: ObjectID) -> Result<Option<(ObjectRef, Owner)>> {
+            self.request();
+            Ok(self.objects.get(&id).cloned())
+        }
+
+        async fn object_versions(&self, ids: &[ObjectID]) -> Result<Vec<ObjectVersion>> {
+            self.request();
+            let versions = self.versions.lock().unwrap();
+            Ok(ids
+                .iter()
+                .map(|&object_id| ObjectVersion {
+                    object_id,
+                    version: versions.get(&object_id).copied(),
+                })
+                .collect())
+        }
+
+        async fn sender_object(&self, address: IotaAddress) -> Result<Option<SenderObject>> {
+            self.request();
+            Ok(self.sender_objects.get(&address).cloned())
+        }
+
+        async fn parameter_types(
+            &self,
+            package: ObjectID,
+            module: &str,
+            function: &str,
+        ) -> Result<Vec<MoveType>> {
+            self.request();
+            Err(anyhow!(
+                "function {package}::{module}::{function} not found"
+            ))
+        }
+
+        async fn reference_gas_price(&self) -> Result<u64> {
+            self.request();
+            Ok(1_000)
+        }
+
+        async fn coins(&self, owner: IotaAddress, coin_type: &TypeTag) -> Result<Vec<OwnedCoin>> {
+            self.request();
+            Ok(self
+                .coins
+                .get(&(owner, coin_type.clone()))
+                .cloned()
+                .unwrap_or_default())
+        }
+
+        async fn members(&self, account: IotaAddress) -> Result<Option<Vec<IotaAddress>>> {
+            self.request();
+            Ok(self
+                .accounts
+                .get(&account)
+                .map(|state| state.members.iter().map(|(member, _)| *member).collect()))
+        }
+
+        async fn account_state(&self, account: IotaAddress) -> Result<Option<AccountState>> {
+            self.request();
+            Ok(self.accounts.get(&account).cloned())
+        }
+
+        async fn lifecycle_events(
+            &self,
+            _package: IotaAddress,
+            cursor: Option<EventID>,
+        ) -> Result<LifecycleEventPage> {
+            self.request();
+            self.event_cursors.lock().unwrap().push(cursor);
+            let mut pages = self.event_pages.lock().unwrap();
+            Ok(if pages.is_empty() {
+                LifecycleEventPage::default()
+            } else {
+                pages.remove(0)
+            })
+        }
+
+        async fn dry_run(&self, _: TransactionData) -> Result<DryRunSummary> {
+            self.request();
+            self.dry_runs.fetch_add(1, Ordering::SeqCst);
+            if let Some(reason) = &self.dry_run_rejection {
+                return Ok(DryRunSummary::rejected(reason.clone()));
+            }
+            Ok(DryRunSummary {
+                success: self.dry_run_error.is_none(),
+                rejected: false,
+                error: self.dry_run_error.clone(),
+                gas: GasSummary {
+                    computation_cost: 1_000,
+                    storage_cost: 2_000,
+                    storage_rebate: 500,
+                    net_gas_usage: 2_500,
+                },
+                balance_changes: vec![],
+                created: vec![],
+                mutated: vec![],
+                deleted: vec![],
+                events: vec![],
+            })
+        }
+
+        async fn execute(&self, _: Transaction) -> Result<ExecutionOutcome, ExecuteError> {
+            self.request();
+            self.execution
+                .clone()
+                .ok_or_else(|| ExecuteError::Unavailable(anyhow!("node unreachable")))
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification that the sender of a submitted transaction is an iSafe account.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow, bail};
+use clap::Args;
+use iota_types::{base_types::IotaAddress, parse_iota_struct_tag};
+use tracing::debug;
+
+use crate::{config::PACKAGE_ADDRESS_ENV, db::pool::parse_duration, node::Node};
+
+const ACCOUNT_MODULE: &str = "account";
+const ACCOUNT_STRUCT: &str = "Account";
+const AUTHENTICATOR_MODULE: &str = "dynamic_auth";
+const AUTHENTICATOR_FUNCTION: &str = "authenticate";
+/// Type of the dynamic field value that holds the authenticator of an account.
+pub(crate) const AUTHENTICATOR_REF_TYPE: &str =
+    "::authenticator_function::AuthenticatorFunctionRefV1";
+
+#[derive(Args, Debug, Clone)]
+pub struct SenderCheckConfig {
+    /// Accept transactions of any sender instead of only iSafe accounts.
+    #[arg(long)]
+    pub skip_sender_check: bool,
+    /// Address of the isafe package, required unless the sender check is skipped.
+    #[arg(long, env = PACKAGE_ADDRESS_ENV)]
+    pub package_address: Option<IotaAddress>,
+    /// How long an accepted sender is cached, in seconds. Rejected senders are checked again on
+    /// every submission, so accounts created in the meantime are accepted right away.
+    #[arg(long, value_parser = parse_duration, default_value = "300")]
+    pub sender_cache_ttl_secs: Duration,
+}
+
+/// The authenticator function attached to an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AuthenticatorRef {
+    pub package: IotaAddress,
+    pub module_name: String,
+    pub function_name: String,
+}
+
+/// What the node knows about the object behind a sender address.
+#[derive(Debug, Clone)]
+pub(crate) struct SenderObject {
+    /// The Move type of the object, e.g. `0x..::account::Account`.
+    pub object_type: String,
+    pub shared: bool,
+    pub authenticator: Option<AuthenticatorRef>,
+}
+
+/// Parses the JSON content of the `Field<_, AuthenticatorFunctionRefV1<_>>` dynamic field.
+pub(crate) fn parse_authenticator_field(field: &serde_json::Value) -> Result<AuthenticatorRef> {
+    let value = &field["value"];
+    let string_field = |name: &str| {
+        value[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("authenticator field without `{name}`"))
+    };
+
+    Ok(AuthenticatorRef {
+        package: IotaAddress::from_str(&string_field("package")?)?,
+        module_name: string_field("module_name")?,
+        function_name: string_field("function_name")?,
+    })
+}
+
+/// Checks senders of submitted transactions and caches the accepted ones.
+pub(crate) struct SenderCheck {
+    package_address: IotaAddress,
+    reader: Arc<dyn Node>,
+    cache_ttl: Duration,
+    /// Accepted senders with the time of the check.
+    accepted: Mutex<HashMap<IotaAddress, Instant>>,
+}
+
+impl SenderCheck {
+    /// Creates the check from the configuration, returns `None` if it is disabled.
+    pub fn from_config(config: &SenderCheckConfig, reader: Arc<dyn Node>) -> Result<Option<Self>> {
+        if config.skip_sender_check {
+            return Ok(None);
+        }
+        let Some(package_address) = config.package_address else {
+            bail!("--package-address is required unless --skip-sender-check is set");
+        };
+
+        Ok(Some(Self {
+            package_address,
+            reader,
+            cache_ttl: config.sender_cache_ttl_secs,
+            accepted: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Returns the reason why `sender` is not accepted, or `None` if it is an iSafe account.
+    ///
+    /// Only accepted senders are cached, node errors are returned as errors.
+    pub async fn rejection(&self, sender: IotaAddress) -> Result<Option<String>> {
+        if let Some(checked_at) = self.accepted.lock().unwrap().get(&sender) {
+            if checked_at.elapsed() < self.cache_ttl {
+                return Ok(None);
+            }
+        }
+
+        let rejection = self.check(sender).await?;
+        debug!("Checked sender {sender}: {rejection:?}");
+        let mut accepted = self.accepted.lock().unwrap();
+        match rejection {
+            None => accepted.insert(sender, Instant::now()),
+            Some(_) => accepted.remove(&sender),
+        };
+        Ok(rejection)
+    }
+
+    async fn check(&self, sender: IotaAddress) -> Result<Option<String>> {
+        let Some(object) = self.reader.sender_object(sender).await? else {
+            return Ok(Some(format!("Sender {sender} is not an iSafe account")));
+        };
+
+        let is_account = parse_iota_struct_tag(&object.object_type).is_ok_and(|tag| {
+            IotaAddress::from(tag.address) == self.package_address
+                && tag.module.as_str() == ACCOUNT_MODULE
+                && tag.name.as_str() == ACCOUNT_STRUCT
+        });
+        if !is_account {
+            return Ok(Some(format!(
+                "Sender {sender} is an object of type {}, not an iSafe account",
+                object.object_type
+            )));
+        }
+        if !object.shared {
+            return Ok(Some(format!(
+                "Sender {sender} is an iSafe account that is not shared"
+            )));
+        }
+
+        let expected = AuthenticatorRef {
+            package: self.package_address,
+            module_name: AUTHENTICATOR_MODULE.to_string(),
+            function_name: AUTHENTICATOR_FUNCTION.to_string(),
+        };
+        match object.authenticator {
+            Some(authenticator) if authenticator == expected => Ok(None),
+            Some(authenticator) => Ok(Some(format!(
+                "Sender {sender} uses the authenticator {}::{}::{} instead of {}::{}::{}",
+                authenticator.package,
+                authenticator.module_name,
+                authenticator.function_name,
+                expected.package,
+                expected.module_name,
+                expected.function_name
+            ))),
+            None => Ok(Some(format!(
+                "Sender {sender} has no authenticator attached"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::node::tests::MockNode;
+
+    fn sender_check(
+        node: &Arc<MockNode>,
+        package_address: IotaAddress,
+        ttl: Duration,
+    ) -> SenderCheck {
+        SenderCheck {
+            package_address,
+            reader: node.clone(),
+            cache_ttl: ttl,
+            accepted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn account(package: IotaAddress, shared: bool) -> SenderObject {
+        SenderObject {
+            object_type: format!("{package}::{ACCOUNT_MODULE}::{ACCOUNT_STRUCT}"),
+            shared,
+            authenticator: Some(AuthenticatorRef {
+                package,
+                module_name: AUTHENTICATOR_MODULE.to_string(),
+                function_name: AUTHENTICATOR_FUNCTION.to_string(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sender_check() {
+        let package = IotaAddress::random_for_testing_only();
+        let [isafe, owned, other, unknown] =
+            [(); 4].map(|_| IotaAddress::random_for_testing_only());
+        let mut node = MockNode::default();
+        node.sender_objects.insert(isafe, account(package, true));
+        node.sender_objects.insert(owned, account(package, false));
+        node.sender_objects
+            .insert(other, account(IotaAddress::random_for_testing_only(), true));
+        let node = Arc::new(node);
+        let check = sender_check(&node, package, Duration::from_secs(300));
+
+        assert_eq!(check.rejection(isafe).await.unwrap(), None);
+        assert!(
+            check
+                .rejection(owned)
+                .await
+                .unwrap()
+                .unwrap()
+                .contains("not shared")
+        );
+        assert!(
+            check
+                .rejection(other)
+                .await
+                .unwrap()
+                .unwrap()
+                .contains("of type")
+        );
+        assert!(
+            check
+                .rejection(unknown)
+                .await
+                .unwrap()
+                .unwrap()
+                .contains("is not an iSafe account")
+        );
+        assert_eq!(node.requests.load(Ordering::SeqCst), 4);
+
+        // accepted senders are cached, rejected ones are checked again
+        assert_eq!(check.rejection(isafe).await.unwrap(), None);
+        assert_eq!(node.requests.load(Ordering::SeqCst), 4);
+        assert!(check.rejection(unknown).await.unwrap().is_some());
+        assert_eq!(node.requests.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_sender_cache_expires() {
+        let package = IotaAddress::random_for_testing_only();
+        let isafe = IotaAddress::random_for_testing_only();
+        let mut node = MockNode::default();
+        node.sender_objects.insert(isafe, account(package, true));
+        let node = Arc::new(node);
+        let check = sender_check(&node, package, Duration::ZERO);
+
+        assert_eq!(check.rejection(isafe).await.unwrap(), None);
+        assert_eq!(check.rejection(isafe).await.unwrap(), None);
+        assert_eq!(node.requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dry-run simulation of stored transactions.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use iota_json_rpc_types::{
+    DryRunTransactionBlockResponse, IotaExecutionStatus, IotaTransactionBlockEffectsAPI,
+    ObjectChange,
+};
+use iota_types::{
+    base_types::{IotaAddress, ObjectID},
+    transaction::{CallArg, ObjectArg, TransactionData, TransactionDataAPI, TransactionKind},
+};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::node::Node;
+
+/// Maximum number of cached simulations, the least recently used one is evicted beyond.
+const MAX_CACHED_SIMULATIONS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasSummary {
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// Computation and storage cost minus the storage rebate.
+    pub net_gas_usage: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBalanceChange {
+    pub owner: serde_json::Value,
+    pub coin_type: String,
+    /// Signed amount, as string since it may not fit a JSON number.
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedObject {
+    pub object_id: ObjectID,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedEvent {
+    pub event_type: String,
+    pub sender: IotaAddress,
+    pub data: serde_json::Value,
+}
+
+/// The summarized effects of a dry run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunSummary {
+    pub success: bool,
+    /// Set if the node rejected the transaction without executing it, there are no effects then.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub rejected: bool,
+    /// The execution error if the dry run failed, or the reason of the rejection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub gas: GasSummary,
+    pub balance_changes: Vec<SimulatedBalanceChange>,
+    pub created: Vec<SimulatedObject>,
+    pub mutated: Vec<SimulatedObject>,
+    pub deleted: Vec<SimulatedObject>,
+    pub events: Vec<SimulatedEvent>,
+}
+
+impl From<DryRunTransactionBlockResponse> for DryRunSummary {
+    fn from(response: DryRunTransactionBlockResponse) -> Self {
+        let error = match response.effects.status() {
+            IotaExecutionStatus::Success => None,
+            IotaExecutionStatus::Failure { error } => Some(error.clone()),
+        };
+        let gas = response.effects.gas_cost_summary();
+
+        let mut created = Vec::new();
+        let mut mutated = Vec::new();
+        let mut deleted = Vec::new();
+        for change in response.object_changes {
+            let (list, object_id, object_type) = match change {
+                ObjectChange::Published { package_id, .. } => (&mut created, package_id, None),
+                ObjectChange::Created {
+                    object_id,
+                    object_type,
+                    ..
+                } => (&mut created, object_id, Some(object_type)),
+                ObjectChange::Mutated {
+                    object_id,
+                    object_type,
+                    ..
+                }
+                | ObjectChange::Transferred {
+                    object_id,
+                    object_type,
+                    ..
+                } => (&mut mutated, object_id, Some(object_type)),
+                ObjectChange::Deleted {
+                    object_id,
+                    object_type,
+                    ..
+                }
+                | ObjectChange::Wrapped {
+                    object_id,
+                    object_type,
+                    ..
+                } => (&mut deleted, object_id, Some(object_type)),
+            };
+            list.push(SimulatedObject {
+                object_id,
+                object_type: object_type.map(|t| t.to_string()),
+            });
+        }
+
+        Self {
+            success: error.is_none(),
+            rejected: false,
+            error,
+            gas: GasSummary {
+                computation_cost: gas.computation_cost,
+                storage_cost: gas.storage_cost,
+                storage_rebate: gas.storage_rebate,
+                net_gas_usage: gas.net_gas_usage(),
+            },
+            balance_changes: response
+                .balance_changes
+                .into_iter()
+                .map(|change| SimulatedBalanceChange {
+                    owner: serde_json::to_value(change.owner).unwrap_or_default(),
+                    coin_type: change.coin_type.to_string(),
+                    amount: change.amount.to_string(),
+                })
+                .collect(),
+            created,
+            mutated,
+            deleted,
+            events: response
+                .events
+                .data
+                .into_iter()
+                .map(|event| SimulatedEvent {
+                    event_type: event.type_.to_string(),
+                    sender: event.sender,
+                    data: event.parsed_json,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DryRunSummary {
+    /// The summary of a transaction the node rejected as invalid, with the reason it gave.
+    pub fn rejected(reason: String) -> Self {
+        Self {
+            success: false,
+            rejected: true,
+            error: Some(reason),
+            gas: GasSummary {
+                computation_cost: 0,
+                storage_cost: 0,
+                storage_rebate: 0,
+                net_gas_usage: 0,
+            },
+            balance_changes: vec![],
+            created: vec![],
+            mutated: vec![],
+            deleted: vec![],
+            events: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectVersion {
+    pub object_id: ObjectID,
+    /// The current version, `None` if the object does not exist anymore.
+    pub version: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub digest: String,
+    #[serde(flatten)]
+    pub summary: DryRunSummary,
+    /// Set if the transaction would not execute successfully in the current state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    /// The versions of the input objects the simulation ran against.
+    pub object_versions: Vec<ObjectVersion>,
+    pub simulated_at: u64,
+    /// Whether the report was served from the cache.
+    pub cached: bool,
+}
+
+/// A cache of bounded size that evicts the least recently used entry.
+struct LruCache<K, V> {
+    capacity: usize,
+    /// The values with the tick they were last used at.
+    entries: HashMap<K, (V, u64)>,
+    tick: u64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, used) = self.entries.get_mut(key)?;
+        *used = self.tick;
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let least_recent = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recent) = least_recent {
+                self.entries.remove(&least_recent);
+            }
+        }
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
+/// Simulates transactions and caches the reports per digest and input object versions.
+pub(crate) struct Simulator {
+    node: Arc<dyn Node>,
+    cache: Mutex<LruCache<(String, Vec<ObjectVersion>), SimulationReport>>,
+}
+
+impl Simulator {
+    pub fn new(node: Arc<dyn Node>) -> Self {
+        Self {
+            node,
+            cache: Mutex::new(LruCache::new(MAX_CACHED_SIMULATIONS)),
+        }
+    }
+
+    /// Simulates the transaction, unless it was already simulated with the same versions of its
+    /// input objects.
+    pub async fn simulate(&self, tx_data: &TransactionData) -> Result<SimulationReport> {
+        let digest = tx_data.digest().to_string();
+        let object_versions = self.node.object_versions(&input_objects(tx_data)).await?;
+        let key = (digest, object_versions);
+
+        if let Some(report) = self.cache.lock().unwrap().get(&key) {
+            return Ok(SimulationReport {
+                cached: true,
+                ..report
+            });
+        }
+
+        debug!("Simulating transaction {}", key.0);
+        // only an unreachable or failing node is an error, its verdict goes into the report
+        let summary = self.node.dry_run(tx_data.clone()).await?;
+        let report = SimulationReport {
+            digest: key.0.clone(),
+            warning: summary.error.as_ref().map(|error| {
+                if summary.rejected {
+                    format!(
+                        "The node rejected the transaction, executing it now would fail: {error}"
+                    )
+                } else {
+                    format!("The dry run failed, executing the transaction now would fail: {error}")
+                }
+            }),
+            summary,
+            object_versions: key.1.clone(),
+            simulated_at: Utc::now().timestamp() as u64,
+            cached: false,
+        };
+
+        self.cache.lock().unwrap().insert(key, report.clone());
+        Ok(report)
+    }
+}
+
+/// The objects the outcome of the transaction depends on, in a stable order.
+fn input_objects(tx_data: &TransactionData) -> Vec<ObjectID> {
+    let mut ids = Vec::new();
+    if let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() {
+        for input in &pt.inputs {
+            match input {
+                CallArg::Pure(_) => {}
+                CallArg::Object(ObjectArg::ImmOrOwnedObject((id, ..)))
+                | CallArg::Object(ObjectArg::Receiving((id, ..)))
+                | CallArg::Object(ObjectArg::SharedObject { id, .. }) => ids.push(*id),
+            }
+        }
+    }
+    ids.extend(tx_data.gas_data().payment.iter().map(|(id, ..)| *id));
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use iota_types::base_types::{ObjectRef, random_object_ref};
+
+    use super::*;
+    use crate::node::tests::MockNode;
+
+    fn tx_data(gas: ObjectRef) -> TransactionData {
+        TransactionData::new_transfer_iota(
+            IotaAddress::ZERO,
+            IotaAddress::ZERO,
+            Some(1),
+            gas,
+            1_000_000,
+            1_000,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_simulation_is_cached_per_object_versions() {
+        let gas = random_object_ref();
+        let node = Arc::new(MockNode::default());
+        node.set_version(gas.0, 0);
+        let simulator = Simulator::new(node.clone());
+        let tx_data = tx_data(gas);
+
+        let first = simulator.simulate(&tx_data).await.unwrap();
+        assert!(!first.cached);
+        assert!(first.warning.is_none());
+        assert_eq!(first.summary.gas.net_gas_usage, 2_500);
+
+        let second = simulator.simulate(&tx_data).await.unwrap();
+        assert!(second.cached);
+        assert_eq!(node.dry_runs.load(Ordering::SeqCst), 1);
+
+        // the gas coin changed, the cached report is outdated
+        node.set_version(gas.0, 1);
+        let third = simulator.simulate(&tx_data).await.unwrap();
+        assert!(!third.cached);
+        assert_eq!(third.object_versions[0].version, Some(1));
+        assert_eq!(node.dry_runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_simulation_warns() {
+        let simulator = Simulator::new(Arc::new(MockNode {
+            dry_run_error: Some("InsufficientGas".to_string()),
+            ..Default::default()
+        }));
+
+        let report = simulator
+            .simulate(&tx_data(random_object_ref()))
+            .await
+            .unwrap();
+        assert!(!report.summary.success);
+        assert!(report.warning.unwrap().contains("InsufficientGas"));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_simulation_is_reported() {
+        let simulator = Simulator::new(Arc::new(MockNode {
+            dry_run_rejection: Some("Transaction needs to be signed".to_string()),
+            ..Default::default()
+        }));
+
+        let report = simulator
+            .simulate(&tx_data(random_object_ref()))
+            .await
+            .unwrap();
+        assert!(!report.summary.success);
+        assert!(report.summary.rejected);
+        assert_eq!(report.summary.gas.net_gas_usage, 0);
+        assert!(report.warning.unwrap().starts_with("The node rejected"));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        assert_eq!(cache.get(&1), Some("a"));
+
+        cache.insert(3, "c");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&3), Some("c"));
+
+        // replacing an entry doesn't evict another one
+        cache.insert(3, "d");
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&3), Some("d"));
+    }
+}
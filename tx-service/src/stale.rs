@@ -0,0 +1,311 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detection of stored transactions that can never execute because their inputs moved on.
+//!
+//! A transaction pins the versions of its owned inputs and gas coins. Once one of them is used
+//! by another transaction, the pinned version is gone and the stored transaction is stale.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use clap::Args;
+use fastcrypto::encoding::{Base64, Encoding};
+use iota_types::{
+    base_types::{ObjectID, ObjectRef},
+    transaction::{CallArg, ObjectArg, TransactionData, TransactionDataAPI, TransactionKind},
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    db::{
+        pool::{DbConnectionPool, parse_duration},
+        queries,
+    },
+    node::Node,
+    simulate::ObjectVersion,
+};
+
+/// The most objects requested from the node at once.
+const OBJECT_BATCH_SIZE: usize = 50;
+
+#[derive(Args, Debug, Clone)]
+pub struct StaleCheckConfig {
+    /// How often the pending transactions are checked for moved inputs, in seconds.
+    #[arg(long, value_parser = parse_duration, default_value = "60")]
+    pub stale_check_interval_secs: Duration,
+}
+
+/// The objects whose version is pinned by the transaction: owned and receiving inputs and the
+/// gas payment.
+pub(crate) fn pinned_objects(tx_data: &TransactionData) -> Vec<ObjectRef> {
+    let mut refs = Vec::new();
+    if let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() {
+        for input in &pt.inputs {
+            match input {
+                CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref))
+                | CallArg::Object(ObjectArg::Receiving(object_ref)) => refs.push(*object_ref),
+                CallArg::Pure(_) | CallArg::Object(ObjectArg::SharedObject { .. }) => {}
+            }
+        }
+    }
+    refs.extend(tx_data.gas_data().payment.iter().copied());
+    refs
+}
+
+/// Returns the pinned objects that are no longer at their pinned version, in the order of
+/// `pinned`. `current` holds the current versions in the same order.
+pub(crate) fn moved_objects(pinned: &[ObjectRef], current: &[ObjectVersion]) -> Vec<ObjectID> {
+    pinned
+        .iter()
+        .zip(current)
+        .filter(|((_, version, _), current)| current.version != Some(version.value()))
+        .map(|((id, ..), _)| *id)
+        .collect()
+}
+
+/// Periodically marks pending transactions as stale once their pinned inputs moved on.
+pub(crate) struct StaleChecker {
+    pool: DbConnectionPool,
+    reader: Arc<dyn Node>,
+    interval: Duration,
+}
+
+impl StaleChecker {
+    pub fn new(pool: DbConnectionPool, reader: Arc<dyn Node>, config: &StaleCheckConfig) -> Self {
+        Self {
+            pool,
+            reader,
+            interval: config.stale_check_interval_secs,
+        }
+    }
+
+    pub async fn run(self, token: CancellationToken) -> Result<()> {
+        info!(
+            "Checking pending transactions for stale inputs every {:?}",
+            self.interval
+        );
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                _ = interval.tick() => {}
+            }
+            // the node being unreachable must not stop the service, the next round retries
+            match self.check_pending().await {
+                Ok(0) => {}
+                Ok(stale) => info!("Marked {stale} transactions as stale"),
+                Err(err) => warn!("Checking for stale transactions failed: {err}"),
+            }
+        }
+    }
+
+    /// Checks all pending transactions once and returns the number of newly stale ones.
+    ///
+    /// The pinned objects of all transactions are requested in batches. A transaction that
+    /// can't be checked is logged and skipped, only an unreachable node fails the round.
+    pub async fn check_pending(&self) -> Result<usize> {
+        let pending = {
+            let mut conn = self.pool.get_connection()?;
+            queries::get_pending_transactions(&mut conn)?
+        };
+
+        let mut pinned_by_transaction = Vec::with_capacity(pending.len());
+        let mut ids = BTreeSet::new();
+        for (digest, tx_data) in pending {
+            let pinned = match decode(&tx_data) {
+                Ok(tx_data) => pinned_objects(&tx_data),
+                Err(err) => {
+                    warn!("Skipping the stale check of transaction {digest}: {err}");
+                    continue;
+                }
+            };
+            if pinned.is_empty() {
+                continue;
+            }
+            ids.extend(pinned.iter().map(|(id, ..)| *id));
+            pinned_by_transaction.push((digest, pinned));
+        }
+
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let mut versions = HashMap::with_capacity(ids.len());
+        for batch in ids.chunks(OBJECT_BATCH_SIZE) {
+            for current in self.reader.object_versions(batch).await? {
+                versions.insert(current.object_id, current);
+            }
+        }
+
+        let mut stale = 0;
+        for (digest, pinned) in pinned_by_transaction {
+            let Some(current) = pinned
+                .iter()
+                .map(|(id, ..)| versions.get(id).cloned())
+                .collect::<Option<Vec<_>>>()
+            else {
+                warn!("Skipping the stale check of transaction {digest}: missing object versions");
+                continue;
+            };
+            let moved = moved_objects(&pinned, &current);
+            if moved.is_empty() {
+                continue;
+            }
+
+            debug!("Transaction {digest} is stale, moved objects: {moved:?}");
+            let marked = self.pool.get_connection().and_then(|mut conn| {
+                queries::set_stale(&mut conn, &digest, &moved, Utc::now().timestamp() as u64)
+            });
+            match marked {
+                Ok(()) => stale += 1,
+                Err(err) => warn!("Failed to mark transaction {digest} as stale: {err}"),
+            }
+        }
+        Ok(stale)
+    }
+}
+
+fn decode(tx_data: &str) -> Result<TransactionData> {
+    Ok(bcs::from_bytes(
+        &Base64::decode(tx_data).map_err(|err| anyhow!(err))?,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use iota_types::base_types::{IotaAddress, random_object_ref};
+
+    use super::*;
+    use crate::{db::pool::DbConnectionPoolConfig, node::tests::MockNode};
+
+    fn pool() -> DbConnectionPool {
+        let pool = DbConnectionPool::new_with_path(
+            ":memory:",
+            DbConnectionPoolConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        pool.run_migrations().unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_transaction_with_moved_gas_coin_is_stale() {
+        let pool = pool();
+        let gas = random_object_ref();
+        let tx_data = TransactionData::new_transfer_iota(
+            IotaAddress::ZERO,
+            IotaAddress::ZERO,
+            Some(1),
+            gas,
+            1_000_000,
+            1_000,
+        );
+        let digest = tx_data.digest().to_string();
+        queries::insert_transaction(&mut pool.get_connection().unwrap(), &tx_data, None, None, 0)
+            .unwrap();
+
+        let node = Arc::new(MockNode::default());
+        node.set_version(gas.0, gas.1.value());
+        let checker = StaleChecker::new(
+            pool.clone(),
+            node.clone(),
+            &StaleCheckConfig {
+                stale_check_interval_secs: Duration::from_secs(60),
+            },
+        );
+
+        assert_eq!(checker.check_pending().await.unwrap(), 0);
+
+        node.set_version(gas.0, gas.1.value() + 1);
+        assert_eq!(checker.check_pending().await.unwrap(), 1);
+
+        let mut conn = pool.get_connection().unwrap();
+        let stored = queries::get_transaction_by_digest(&mut conn, &digest)
+            .unwrap()
+            .unwrap();
+        assert!(stored.stale_at.is_some());
+        assert_eq!(
+            serde_json::from_str::<Vec<ObjectID>>(&stored.stale_objects.unwrap()).unwrap(),
+            vec![gas.0]
+        );
+
+        // stale transactions are not checked again
+        assert!(
+            queries::get_pending_transactions(&mut conn)
+                .unwrap()
+                .is_empty()
+        );
+        let (stale, total) = queries::get_stale_transactions(&mut conn, None, 10, 0).unwrap();
+        assert_eq!((stale.len(), total), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_are_checked_in_one_batch() {
+        use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+        use crate::db::schema::transactions;
+
+        let pool = pool();
+        let node = Arc::new(MockNode::default());
+        let mut conn = pool.get_connection().unwrap();
+        let mut digests = Vec::new();
+        for _ in 0..3 {
+            let gas = random_object_ref();
+            let tx_data = TransactionData::new_transfer_iota(
+                IotaAddress::ZERO,
+                IotaAddress::ZERO,
+                Some(1),
+                gas,
+                1_000_000,
+                1_000,
+            );
+            queries::insert_transaction(&mut conn, &tx_data, None, None, 0).unwrap();
+            node.set_version(gas.0, gas.1.value() + 1);
+            digests.push(tx_data.digest().to_string());
+        }
+        // a transaction that can't be decoded doesn't stop the others from being checked
+        diesel::update(transactions::table.filter(transactions::digest.eq(&digests[0])))
+            .set(transactions::tx_data.eq("not base64"))
+            .execute(&mut conn)
+            .unwrap();
+        drop(conn);
+
+        let checker = StaleChecker::new(
+            pool.clone(),
+            node.clone(),
+            &StaleCheckConfig {
+                stale_check_interval_secs: Duration::from_secs(60),
+            },
+        );
+        assert_eq!(checker.check_pending().await.unwrap(), 2);
+        assert_eq!(node.requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let mut conn = pool.get_connection().unwrap();
+        let pending = queries::get_pending_transactions(&mut conn).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, digests[0]);
+    }
+
+    #[test]
+    fn test_deleted_objects_moved() {
+        let pinned = [random_object_ref(), random_object_ref()];
+        let current = [
+            ObjectVersion {
+                object_id: pinned[0].0,
+                version: Some(pinned[0].1.value()),
+            },
+            ObjectVersion {
+                object_id: pinned[1].0,
+                version: None,
+            },
+        ];
+        assert_eq!(moved_objects(&pinned, &current), vec![pinned[1].0]);
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (c) 2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification that submissions and comments are signed by a member of the sender account.
+//!
+//! The submitter signs a personal message binding the transaction digest to the description, so
+//! descriptions can't be attached by anyone who is not a member. Comments are signed the same way.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::Result;
+use fastcrypto::{
+    encoding::{Base64, Encoding},
+    traits::ToFromBytes,
+};
+use iota_types::{
+    base_types::IotaAddress,
+    crypto::{IotaSignature, Signature},
+    digests::TransactionDigest,
+};
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+
+use crate::{metadata::ProposalMetadata, node::Node};
+
+/// The message a submitter signs for a transaction, its metadata and its description.
+///
+/// Each set metadata field adds a line before the description, which may span several lines
+/// and therefore comes last.
+pub fn submission_message(
+    digest: &TransactionDigest,
+    description: Option<&str>,
+    metadata: Option<&ProposalMetadata>,
+) -> String {
+    let mut message = format!("iSafe transaction proposal\nDigest: {digest}\n");
+    for line in metadata
+        .map(ProposalMetadata::message_lines)
+        .unwrap_or_default()
+    {
+        message.push_str(&line);
+        message.push('\n');
+    }
+    message.push_str("Description: ");
+    message.push_str(description.unwrap_or_default());
+    message
+}
+
+/// The message a member signs to comment on a transaction.
+pub fn comment_message(digest: &TransactionDigest, body: &str, concern: bool) -> String {
+    format!(
+        "iSafe transaction comment\nDigest: {digest}\nConcern: {}\nComment: {body}",
+        if concern { "yes" } else { "no" }
+    )
+}
+
+#[derive(Debug)]
+pub enum SubmissionError {
+    /// The signature is malformed or doesn't sign the submission message.
+    InvalidSignature(String),
+    /// The signer is not a member of the sender account.
+    NotMember {
+        signer: IotaAddress,
+        account: IotaAddress,
+    },
+    Node(anyhow::Error),
+}
+
+impl fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmissionError::InvalidSignature(msg) => {
+                write!(f, "Invalid submission signature: {msg}")
+            }
+            SubmissionError::NotMember { signer, account } => {
+                write!(f, "Signer {signer} is not a member of account {account}")
+            }
+            SubmissionError::Node(err) => write!(f, "Node request failed: {err}"),
+        }
+    }
+}
+
+/// Verifies the signatures of submissions.
+pub(crate) struct SubmissionVerifier {
+    reader: Arc<dyn Node>,
+}
+
+impl SubmissionVerifier {
+    pub fn new(reader: Arc<dyn Node>) -> Self {
+        Self { reader }
+    }
+
+    /// Verifies that `signature` is a personal message signature of a member of `account` over
+    /// the submission message, and returns the signer.
+    pub async fn verify(
+        &self,
+        account: IotaAddress,
+        digest: &TransactionDigest,
+        description: Option<&str>,
+        metadata: Option<&ProposalMetadata>,
+        signature: &str,
+    ) -> Result<IotaAddress, SubmissionError> {
+        self.verify_message(
+            account,
+            &submission_message(digest, description, metadata),
+            signature,
+        )
+        .await
+    }
+
+    /// Verifies that `signature` is a personal message signature of a member of `account` over
+    /// `message`, and returns the signer.
+    ///
+    /// Only single key signatures are accepted.
+    pub async fn verify_message(
+        &self,
+        account: IotaAddress,
+        message: &str,
+        signature: &str,
+    ) -> Result<IotaAddress, SubmissionError> {
+        let signature = Base64::decode(signature)
+            .map_err(|_| SubmissionError::InvalidSignature("not valid base64".to_string()))
+            .and_then(|bytes| {
+                Signature::from_bytes(&bytes)
+                    .map_err(|err| SubmissionError::InvalidSignature(err.to_string()))
+            })?;
+        let public_key = signature
+            .to_public_key()
+            .map_err(|err| SubmissionError::InvalidSignature(err.to_string()))?;
+        let signer = IotaAddress::from(&public_key);
+
+        let message = IntentMessage::new(
+            Intent::personal_message(),
+            PersonalMessage {
+                message: message.as_bytes().to_vec(),
+            },
+        );
+        signature
+            .verify_secure(&message, signer, signature.scheme())
+            .map_err(|err| SubmissionError::InvalidSignature(err.to_string()))?;
+
+        let members = self
+            .reader
+            .members(account)
+            .await
+            .map_err(SubmissionError::Node)?
+            .unwrap_or_default();
+        if !members.contains(&signer) {
+            return Err(SubmissionError::NotMember { signer, account });
+        }
+        Ok(signer)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use iota_types::crypto::{AccountKeyPair, get_key_pair};
+
+    use super::*;
+    use crate::node::tests::MockNode;
+
+    /// Signs the message like a wallet signs a personal message.
+    pub(crate) fn sign_message(keypair: &AccountKeyPair, message: &str) -> String {
+        let message = IntentMessage::new(
+            Intent::personal_message(),
+            PersonalMessage {
+                message: message.as_bytes().to_vec(),
+            },
+        );
+        Base64::encode(Signature::new_secure(&message, keypair).as_ref())
+    }
+
+    pub(crate) fn sign_submission(
+        keypair: &AccountKeyPair,
+        digest: &TransactionDigest,
+        description: Option<&str>,
+        metadata: Option<&ProposalMetadata>,
+    ) -> String {
+        sign_message(keypair, &submission_message(digest, description, metadata))
+    }
+
+    #[tokio::test]
+    async fn test_member_signature_is_accepted() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let (outsider, outsider_keypair): (_, AccountKeyPair) = get_key_pair();
+        let account = IotaAddress::random_for_testing_only();
+        let digest = TransactionDigest::random();
+        let verifier = SubmissionVerifier::new(Arc::new(
+            MockNode::default().with_members(account, vec![member]),
+        ));
+
+        let signature = sign_submission(&keypair, &digest, Some("pay rent"), None);
+        assert_eq!(
+            verifier
+                .verify(account, &digest, Some("pay rent"), None, &signature)
+                .await
+                .unwrap(),
+            member
+        );
+
+        // the signature doesn't cover another description
+        assert!(matches!(
+            verifier
+                .verify(account, &digest, Some("pay more rent"), None, &signature)
+                .await,
+            Err(SubmissionError::InvalidSignature(_))
+        ));
+
+        let signature = sign_submission(&outsider_keypair, &digest, Some("pay rent"), None);
+        assert!(matches!(
+            verifier
+                .verify(account, &digest, Some("pay rent"), None, &signature)
+                .await,
+            Err(SubmissionError::NotMember { signer, .. }) if signer == outsider
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_is_signed() {
+        let (member, keypair): (_, AccountKeyPair) = get_key_pair();
+        let account = IotaAddress::random_for_testing_only();
+        let digest = TransactionDigest::random();
+        let verifier = SubmissionVerifier::new(Arc::new(
+            MockNode::default().with_members(account, vec![member]),
+        ));
+        let metadata = ProposalMetadata {
+            tags: vec!["q4".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            submission_message(&digest, Some("salaries"), Some(&metadata)),
+            format!(
+                "iSafe transaction proposal\nDigest: {digest}\nTags: q4\nDescription: salaries"
+            )
+        );
+
+        let signature = sign_submission(&keypair, &digest, Some("salaries"), Some(&metadata));
+        assert!(
+            verifier
+                .verify(
+                    account,
+                    &digest,
+                    Some("salaries"),
+                    Some(&metadata),
+                    &signature
+                )
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            verifier
+                .verify(account, &digest, Some("salaries"), None, &signature)
+                .await,
+            Err(SubmissionError::InvalidSignature(_))
+        ));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct MessageVector {
+        digest: TransactionDigest,
+        description: String,
+        metadata: Option<ProposalMetadata>,
+        message: String,
+    }
+
+    /// The dapp checks its messages against the same vectors.
+    #[test]
+    fn test_submission_message_vectors() {
+        let vectors: Vec<MessageVector> =
+            serde_json::from_str(include_str!("../../test-vectors/submission-messages.json"))
+                .unwrap();
+        for vector in vectors {
+            assert_eq!(
+                submission_message(
+                    &vector.digest,
+                    Some(&vector.description),
+                    vector.metadata.as_ref()
+                ),
+                vector.message
+            );
+        }
+    }
+}